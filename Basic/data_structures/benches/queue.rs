@@ -0,0 +1,99 @@
+//! Compares FIFO throughput of the `Queue` implementations.
+//!
+//! Run with `cargo bench --bench queue`. Each round fills a queue with `n`
+//! elements and drains it, so the O(n) `dequeue` of `Vec` shows up as
+//! quadratic growth.
+
+use std::{collections::VecDeque, hint::black_box, time::Instant};
+
+use data_structures::{
+    linked_list::{DoublyLinkedList, SingleLinkedList},
+    queue::{Queue, RingQueue},
+};
+
+/// `VecDeque` as the std baseline.
+struct StdQueue(VecDeque<usize>);
+
+impl Queue<usize> for StdQueue {
+    fn enqueue(&mut self, x: usize) {
+        self.0.push_back(x)
+    }
+
+    fn dequeue(&mut self) -> Option<usize> {
+        self.0.pop_front()
+    }
+
+    fn peek(&self) -> Option<&usize> {
+        self.0.front()
+    }
+
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    fn new() -> Self {
+        StdQueue(VecDeque::new())
+    }
+}
+
+fn fill_drain<Q: Queue<usize>>(n: usize) -> usize {
+    let mut queue = Q::new();
+    for i in 0..n {
+        queue.enqueue(i);
+    }
+    let mut sum = 0;
+    while let Some(x) = queue.dequeue() {
+        sum += x;
+    }
+    sum
+}
+
+/// Interleaves enqueues and dequeues so the queue stays `window` long, like a
+/// sliding window over a stream.
+fn sliding_window<Q: Queue<usize>>(n: usize, window: usize) -> usize {
+    let mut queue = Q::new();
+    let mut sum = 0;
+    for i in 0..n {
+        queue.enqueue(i);
+        if queue.size() > window {
+            sum += queue.dequeue().unwrap_or(0);
+        }
+    }
+    sum
+}
+
+fn bench<F: FnMut() -> usize>(name: &str, rounds: u32, mut f: F) {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(f());
+    }
+    let per_round = start.elapsed() / rounds;
+    println!("{name:<40} {per_round:>12.2?}");
+}
+
+fn main() {
+    for n in [1_000, 10_000, 100_000] {
+        println!("fill then drain, n = {n}");
+        bench("Vec", 5, || fill_drain::<Vec<_>>(n));
+        bench("RingQueue", 5, || fill_drain::<RingQueue<_>>(n));
+        // `SingleLinkedList::push_tail` walks the whole list.
+        if n <= 10_000 {
+            bench("SingleLinkedList", 5, || {
+                fill_drain::<SingleLinkedList<_>>(n)
+            });
+        }
+        bench("DoublyLinkedList", 5, || {
+            fill_drain::<DoublyLinkedList<_>>(n)
+        });
+        bench("VecDeque", 5, || fill_drain::<StdQueue>(n));
+    }
+
+    let (n, window) = (200_000, 1_000);
+    println!("sliding window, n = {n}, window = {window}");
+    bench("Vec", 5, || sliding_window::<Vec<_>>(n, window));
+    bench("RingQueue", 5, || sliding_window::<RingQueue<_>>(n, window));
+    bench("DoublyLinkedList", 5, || {
+        sliding_window::<DoublyLinkedList<_>>(n, window)
+    });
+    bench("VecDeque", 5, || sliding_window::<StdQueue>(n, window));
+}
@@ -0,0 +1,126 @@
+//! Compares the B-tree and B+ tree maps with `BinaryTree` and std's
+//! `BTreeMap`.
+//!
+//! Run with `cargo bench --bench tree`. Keys are random, so the unbalanced
+//! `BinaryTree` stays around O(log n) deep; on sorted keys it would degrade
+//! into a list.
+
+use std::{collections::BTreeMap, hint::black_box, time::Instant};
+
+use data_structures::tree::{BPlusTree, BTree, BinaryTree};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn bench<F: FnMut() -> usize>(name: &str, rounds: u32, mut f: F) {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        black_box(f());
+    }
+    let per_round = start.elapsed() / rounds;
+    println!("{name:<40} {per_round:>12.2?}");
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for n in [1_000, 10_000, 100_000] {
+        let keys = (0..n).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+        let mut sorted = keys.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let binary_tree = || {
+            let mut tree = BinaryTree::new();
+            keys.iter().for_each(|&k| tree.insert(k));
+            tree
+        };
+        let b_tree = |t| {
+            let mut tree = BTree::with_min_degree(t);
+            keys.iter().for_each(|&k| _ = tree.insert(k, k));
+            tree
+        };
+        let b_plus_tree = |t| {
+            let mut tree = BPlusTree::with_min_degree(t);
+            keys.iter().for_each(|&k| _ = tree.insert(k, k));
+            tree
+        };
+        let std_map = || keys.iter().map(|&k| (k, k)).collect::<BTreeMap<_, _>>();
+
+        println!("insert random keys, n = {n}");
+        bench("BinaryTree", 5, || binary_tree().iter().count());
+        for t in [2, 6, 32] {
+            bench(&format!("BTree, t = {t}"), 5, || b_tree(t).len());
+            bench(&format!("BPlusTree, t = {t}"), 5, || b_plus_tree(t).len());
+        }
+        bench("BTreeMap", 5, || std_map().len());
+
+        let (binary_tree, b_tree, b_plus_tree, std_map) =
+            (binary_tree(), b_tree(6), b_plus_tree(6), std_map());
+
+        println!("look up every key, n = {n}");
+        bench("BinaryTree", 5, || {
+            keys.iter()
+                .filter(|k| binary_tree.find(k).is_some())
+                .count()
+        });
+        bench("BTree", 5, || {
+            keys.iter().filter_map(|k| b_tree.get(k)).count()
+        });
+        bench("BPlusTree", 5, || {
+            keys.iter().filter_map(|k| b_plus_tree.get(k)).count()
+        });
+        bench("BTreeMap", 5, || {
+            keys.iter().filter_map(|k| std_map.get(k)).count()
+        });
+
+        // Every scan reads each key, so `BTreeMap` cannot shortcut to its
+        // length.
+        println!("scan in order, n = {n}");
+        bench("BinaryTree", 5, || {
+            binary_tree
+                .iter()
+                .filter(|node| node.borrow().value % 2 == 0)
+                .count()
+        });
+        bench("BTree", 5, || {
+            b_tree.iter().filter(|(k, _)| *k % 2 == 0).count()
+        });
+        bench("BPlusTree", 5, || {
+            b_plus_tree.iter().filter(|(k, _)| *k % 2 == 0).count()
+        });
+        bench("BTreeMap", 5, || {
+            std_map.iter().filter(|(k, _)| *k % 2 == 0).count()
+        });
+
+        // Each scan covers about 1% of the keys.
+        let scans = (0..100)
+            .map(|_| {
+                let i = rng.gen_range(0..sorted.len() - sorted.len() / 100);
+                (sorted[i], sorted[i + sorted.len() / 100])
+            })
+            .collect::<Vec<_>>();
+        println!("100 range scans over 1% of the keys, n = {n}");
+        bench("BPlusTree", 5, || {
+            scans
+                .iter()
+                .map(|&(a, b)| b_plus_tree.range(a..b).count())
+                .sum()
+        });
+        bench("BTreeMap", 5, || {
+            scans
+                .iter()
+                .map(|&(a, b)| std_map.range(a..b).count())
+                .sum()
+        });
+
+        println!("build from sorted keys, n = {n}");
+        let entries = || sorted.iter().map(|&k| (k, k));
+        bench("BTree::from_sorted", 5, || {
+            BTree::from_sorted(entries()).len()
+        });
+        bench("BPlusTree::from_sorted", 5, || {
+            BPlusTree::from_sorted(entries()).len()
+        });
+        bench("BTreeMap::from_iter", 5, || {
+            entries().collect::<BTreeMap<_, _>>().len()
+        });
+    }
+}
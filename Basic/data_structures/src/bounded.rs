@@ -0,0 +1,138 @@
+use crate::{
+    queue::{BoundedQueue, Queue},
+    stack::{BoundedStack, Stack},
+};
+
+/// Caps any `Queue` or `Stack` at a fixed number of elements.
+///
+/// `try_enqueue` and `try_push` hand the element back when the container is
+/// full, while the plain `enqueue` and `push` panic. The wrapper counts the
+/// elements itself, since `size` is O(n) for some containers.
+#[derive(Debug, Clone)]
+pub struct Bounded<C> {
+    inner: C,
+    capacity: usize,
+    len: usize,
+}
+
+impl<C> Bounded<C> {
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<T, Q: Queue<T>> Queue<T> for Bounded<Q> {
+    fn enqueue(&mut self, x: T) {
+        if self.try_enqueue(x).is_err() {
+            panic!("queue is full");
+        }
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        let x = self.inner.dequeue()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.inner.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn new() -> Self {
+        BoundedQueue::with_capacity(usize::MAX)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+        self.len = 0;
+    }
+}
+
+impl<T, Q: Queue<T>> BoundedQueue<T> for Bounded<Q> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Q::new(),
+            capacity,
+            len: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn try_enqueue(&mut self, x: T) -> Result<(), T> {
+        if self.len >= self.capacity {
+            return Err(x);
+        }
+        self.inner.enqueue(x);
+        self.len += 1;
+        Ok(())
+    }
+}
+
+impl<T, S: Stack<T>> Stack<T> for Bounded<S> {
+    fn push(&mut self, x: T) {
+        if self.try_push(x).is_err() {
+            panic!("stack is full");
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let x = self.inner.pop()?;
+        self.len -= 1;
+        Some(x)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.inner.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn new() -> Self {
+        BoundedStack::with_capacity(usize::MAX)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+        self.len = 0;
+    }
+}
+
+impl<T, S: Stack<T>> BoundedStack<T> for Bounded<S> {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: S::new(),
+            capacity,
+            len: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn try_push(&mut self, x: T) -> Result<(), T> {
+        if self.len >= self.capacity {
+            return Err(x);
+        }
+        self.inner.push(x);
+        self.len += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,536 @@
+use std::{borrow::Borrow, collections::HashMap, fmt::Debug, hash::Hash};
+
+use crate::linked_list::{ArenaDoublyLinkedList, NodeId};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    pub fn hit_ratio(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+
+    fn record(&mut self, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+    }
+}
+
+type EvictionCallback<K, V> = Box<dyn FnMut(K, V)>;
+
+/// A least-recently-used cache.
+///
+/// Entries are kept in an `ArenaDoublyLinkedList` ordered from most to least
+/// recently used, and a `HashMap` maps each key to its list node, so lookup,
+/// promotion and eviction are all O(1).
+pub struct LruCache<K, V> {
+    map: HashMap<K, NodeId>,
+    list: ArenaDoublyLinkedList<(K, V)>,
+    capacity: usize,
+    stats: CacheStats,
+    on_evict: Option<EvictionCallback<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be positive");
+        Self {
+            map: HashMap::with_capacity(capacity),
+            list: ArenaDoublyLinkedList::new(),
+            capacity,
+            stats: CacheStats::default(),
+            on_evict: None,
+        }
+    }
+
+    /// Calls `f` with every entry evicted to make room for a new one.
+    pub fn on_evict(mut self, f: impl FnMut(K, V) + 'static) -> Self {
+        self.on_evict = Some(Box::new(f));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Looks up `key` without marking it as used or touching the stats.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let id = *self.map.get(key)?;
+        self.list.get(id).map(|(_, v)| v)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|v| &*v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let id = self.map.get(key).copied();
+        self.stats.record(id.is_some());
+        let id = id?;
+        self.list.move_to_front(id);
+        self.list.get_mut(id).map(|(_, v)| v)
+    }
+
+    /// Inserts or updates `key` and marks it as most recently used. Returns
+    /// the previous value of `key`. Evicts the least recently used entry if
+    /// a new key would exceed the capacity.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&id) = self.map.get(&key) {
+            self.list.move_to_front(id);
+            return self
+                .list
+                .get_mut(id)
+                .map(|(_, v)| std::mem::replace(v, value));
+        }
+
+        if self.map.len() == self.capacity {
+            self.evict();
+        }
+        let id = self.list.push_front((key.clone(), value));
+        self.map.insert(key, id);
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let id = self.map.remove(key)?;
+        self.list.remove(id).map(|(_, v)| v)
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    /// Keys from most to least recently used.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.list.iter().map(|(k, _)| k)
+    }
+
+    fn evict(&mut self) {
+        if let Some((key, value)) = self.list.pop_back() {
+            self.map.remove(&key);
+            self.stats.evictions += 1;
+            if let Some(f) = self.on_evict.as_mut() {
+                f(key, value);
+            }
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.list.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct LfuEntry<V> {
+    value: V,
+    frequency: u64,
+    node: NodeId,
+}
+
+/// A least-frequently-used cache; ties are broken by evicting the least
+/// recently used key.
+///
+/// Keys with the same use count share an `ArenaDoublyLinkedList` bucket kept
+/// in recency order, and the cache tracks the lowest populated count, so
+/// every operation is O(1).
+pub struct LfuCache<K, V> {
+    map: HashMap<K, LfuEntry<V>>,
+    buckets: HashMap<u64, ArenaDoublyLinkedList<K>>,
+    min_frequency: u64,
+    capacity: usize,
+    stats: CacheStats,
+    on_evict: Option<EvictionCallback<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be positive");
+        Self {
+            map: HashMap::with_capacity(capacity),
+            buckets: HashMap::new(),
+            min_frequency: 0,
+            capacity,
+            stats: CacheStats::default(),
+            on_evict: None,
+        }
+    }
+
+    /// Calls `f` with every entry evicted to make room for a new one.
+    pub fn on_evict(mut self, f: impl FnMut(K, V) + 'static) -> Self {
+        self.on_evict = Some(Box::new(f));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// How many times `key` has been put or hit since it was inserted.
+    pub fn frequency<Q>(&self, key: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(|entry| entry.frequency)
+    }
+
+    /// Looks up `key` without counting a use or touching the stats.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(|entry| &entry.value)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|v| &*v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hit = self.map.contains_key(key);
+        self.stats.record(hit);
+        if !hit {
+            return None;
+        }
+        self.touch(key);
+        self.map.get_mut(key).map(|entry| &mut entry.value)
+    }
+
+    /// Inserts or updates `key`, counting one use of it. Returns the previous
+    /// value of `key`. Evicts the least frequently used entry if a new key
+    /// would exceed the capacity.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if self.map.contains_key(&key) {
+            self.touch(&key);
+            return self
+                .map
+                .get_mut(&key)
+                .map(|entry| std::mem::replace(&mut entry.value, value));
+        }
+
+        if self.map.len() == self.capacity {
+            self.evict();
+        }
+        let node = self.buckets.entry(1).or_default().push_front(key.clone());
+        self.map.insert(
+            key,
+            LfuEntry {
+                value,
+                frequency: 1,
+                node,
+            },
+        );
+        self.min_frequency = 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entry = self.map.remove(key)?;
+        // `min_frequency` may now name an empty bucket. That is harmless: the
+        // cache is below capacity, so nothing is evicted before the next new
+        // key resets it to 1.
+        self.unlink(entry.frequency, entry.node);
+        Some(entry.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.buckets.clear();
+        self.min_frequency = 0;
+    }
+
+    /// Removes `node` from the bucket of `frequency`, dropping the bucket once
+    /// it is empty.
+    fn unlink(&mut self, frequency: u64, node: NodeId) -> Option<K> {
+        let bucket = self.buckets.get_mut(&frequency)?;
+        let key = bucket.remove(node);
+        if bucket.is_empty() {
+            self.buckets.remove(&frequency);
+        }
+        key
+    }
+
+    /// Moves `key` up to the next frequency bucket.
+    fn touch<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(entry) = self.map.get(key) else {
+            return;
+        };
+        let (frequency, node) = (entry.frequency, entry.node);
+        let Some(key) = self.unlink(frequency, node) else {
+            return;
+        };
+        if self.min_frequency == frequency && !self.buckets.contains_key(&frequency) {
+            self.min_frequency = frequency + 1;
+        }
+
+        let node = self
+            .buckets
+            .entry(frequency + 1)
+            .or_default()
+            .push_front(key.clone());
+        if let Some(entry) = self.map.get_mut::<K>(&key) {
+            entry.frequency = frequency + 1;
+            entry.node = node;
+        }
+    }
+
+    fn evict(&mut self) {
+        let Some(bucket) = self.buckets.get_mut(&self.min_frequency) else {
+            return;
+        };
+        let Some(key) = bucket.pop_back() else {
+            return;
+        };
+        if bucket.is_empty() {
+            self.buckets.remove(&self.min_frequency);
+        }
+        if let Some(entry) = self.map.remove(&key) {
+            self.stats.evictions += 1;
+            if let Some(f) = self.on_evict.as_mut() {
+                f(key, entry.value);
+            }
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LfuCache<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.map.iter().map(|(k, entry)| (k, &entry.value)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    use rand::Rng;
+
+    use super::{CacheStats, LfuCache, LruCache};
+
+    #[test]
+    fn test_lru_eviction_order() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let log = evicted.clone();
+        let mut cache = LruCache::new(3).on_evict(move |k, v| log.borrow_mut().push((k, v)));
+
+        for i in 0..3 {
+            assert!(cache.put(i, i * 10).is_none());
+        }
+        assert!(cache.get(&0) == Some(&0));
+        assert!(cache.put(1, 11) == Some(10));
+        cache.put(3, 30);
+        cache.put(4, 40);
+
+        assert!(*evicted.borrow() == vec![(2, 20), (0, 0)]);
+        assert!(cache.keys().copied().eq([4, 3, 1]));
+        assert!(cache.peek(&1) == Some(&11) && cache.get(&2).is_none());
+        assert!(cache.remove(&3) == Some(30) && cache.len() == 2);
+        assert!(
+            cache.stats()
+                == CacheStats {
+                    hits: 1,
+                    misses: 1,
+                    evictions: 2
+                }
+        );
+        assert!(cache.stats().hit_ratio() == 0.5);
+    }
+
+    #[test]
+    fn test_lfu_eviction_order() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let log = evicted.clone();
+        let mut cache = LfuCache::new(3).on_evict(move |k, _| log.borrow_mut().push(k));
+
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        cache.get(&"a");
+        cache.get(&"a");
+        cache.get(&"b");
+        // c has the fewest uses.
+        cache.put("d", 4);
+        // d has 1 use, b has 2.
+        cache.put("e", 5);
+        cache.get(&"e");
+        // b and e both have 2 uses; b was used less recently.
+        cache.put("f", 6);
+
+        assert!(*evicted.borrow() == vec!["c", "d", "b"]);
+        assert!(cache.frequency(&"a") == Some(3) && cache.frequency(&"e") == Some(2));
+        assert!(cache.remove(&"f") == Some(6));
+        cache.put("g", 7);
+        cache.put("h", 8);
+        assert!(*evicted.borrow() == vec!["c", "d", "b", "g"]);
+        assert!(cache.stats().evictions == 4 && cache.stats().hits == 4);
+    }
+
+    #[test]
+    fn test_lru_against_naive_model() {
+        let mut rng = rand::thread_rng();
+        let mut cache = LruCache::new(16);
+        // Keys from least to most recently used.
+        let mut model: Vec<(i32, i32)> = Vec::new();
+
+        for i in 0..20000 {
+            let key = rng.gen_range(0..40);
+            let position = model.iter().position(|(k, _)| *k == key);
+            if rng.gen_bool(0.5) {
+                let expect = position.map(|p| model.remove(p).1);
+                if expect.is_none() && model.len() == 16 {
+                    model.remove(0);
+                }
+                model.push((key, i));
+                assert!(cache.put(key, i) == expect);
+            } else {
+                let expect = position.map(|p| {
+                    let entry = model.remove(p);
+                    model.push(entry);
+                    entry.1
+                });
+                assert!(cache.get(&key).copied() == expect);
+            }
+            assert!(cache.len() == model.len());
+        }
+        assert!(cache.keys().eq(model.iter().rev().map(|(k, _)| k)));
+    }
+
+    #[test]
+    fn test_lfu_against_naive_model() {
+        let mut rng = rand::thread_rng();
+        let mut cache = LfuCache::new(16);
+        // key -> (value, frequency, last use)
+        let mut model: HashMap<i32, (i32, u64, usize)> = HashMap::new();
+
+        for i in 0..20000 {
+            let key = rng.gen_range(0..40);
+            match rng.gen_range(0..10) {
+                0 => assert!(cache.remove(&key) == model.remove(&key).map(|e| e.0)),
+                1..=5 => {
+                    let expect = model.get_mut(&key).map(|e| {
+                        e.1 += 1;
+                        e.2 = i;
+                        std::mem::replace(&mut e.0, i as i32)
+                    });
+                    if expect.is_none() {
+                        if model.len() == 16 {
+                            let victim = *model.iter().min_by_key(|(_, e)| (e.1, e.2)).unwrap().0;
+                            model.remove(&victim);
+                        }
+                        model.insert(key, (i as i32, 1, i));
+                    }
+                    assert!(cache.put(key, i as i32) == expect);
+                }
+                _ => {
+                    let expect = model.get_mut(&key).map(|e| {
+                        e.1 += 1;
+                        e.2 = i;
+                        e.0
+                    });
+                    assert!(cache.get(&key).copied() == expect);
+                }
+            }
+            assert!(cache.len() == model.len());
+        }
+        for (key, (value, frequency, _)) in &model {
+            assert!(cache.peek(key) == Some(value));
+            assert!(cache.frequency(key) == Some(*frequency));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use crate::linked_list::DoublyLinkedList;
+
+/// A double-ended queue.
+///
+/// Besides the impls below, `queue::RingQueue` implements it with O(1)
+/// operations at both ends.
+pub trait Deque<T> {
+    fn push_front(&mut self, x: T);
+    fn push_back(&mut self, x: T);
+    fn pop_front(&mut self) -> Option<T>;
+    fn pop_back(&mut self) -> Option<T>;
+    fn size(&self) -> usize;
+    fn new() -> Self;
+}
+
+impl<T> Deque<T> for VecDeque<T> {
+    fn push_front(&mut self, x: T) {
+        self.push_front(x)
+    }
+
+    fn push_back(&mut self, x: T) {
+        self.push_back(x)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        VecDeque::new()
+    }
+}
+
+impl<T> Deque<T> for DoublyLinkedList<T> {
+    fn push_front(&mut self, x: T) {
+        self.push_front(x)
+    }
+
+    fn push_back(&mut self, x: T) {
+        self.push_back(x)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        DoublyLinkedList::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::RingQueue;
+
+    fn check_deque<D: Deque<i32>>() {
+        let mut deque = D::new();
+        for i in 0..10 {
+            deque.push_back(i);
+            deque.push_front(-i);
+        }
+
+        assert!(deque.size() == 20);
+
+        for i in (0..10).rev() {
+            assert!(-i == deque.pop_front().unwrap());
+            assert!(i == deque.pop_back().unwrap());
+        }
+
+        assert!(deque.size() == 0);
+        assert!(deque.pop_front().is_none() && deque.pop_back().is_none());
+    }
+
+    #[test]
+    fn test_vec_deque() {
+        check_deque::<VecDeque<_>>();
+    }
+
+    #[test]
+    fn test_ring_deque() {
+        check_deque::<RingQueue<_>>();
+    }
+
+    #[test]
+    fn test_doubly_list_deque() {
+        check_deque::<DoublyLinkedList<_>>();
+    }
+}
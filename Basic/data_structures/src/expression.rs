@@ -0,0 +1,178 @@
+//! A small calculator engine: infix expressions are tokenized, converted to
+//! reverse Polish notation with the shunting-yard algorithm, and evaluated
+//! against a `Context` of variables and functions. Both steps run on the
+//! crate's `Stack` trait.
+//!
+//! Besides `+ - * / %`, expressions support unary minus, right-associative
+//! `^`, parentheses and function calls like `max(x, 2)`.
+
+use std::fmt::Display;
+
+pub mod context;
+pub use context::Context;
+pub mod parser;
+pub use parser::{Expression, Item, Operator};
+pub mod token;
+pub use token::{tokenize, Token};
+
+/// Parses `src` and evaluates it against `Context::new()`.
+pub fn eval(src: &str) -> Result<f64, Error> {
+    Expression::parse(src)?.eval(&Context::new())
+}
+
+/// What went wrong, and the byte position in the source where it did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub pos: usize,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, pos: usize) -> Self {
+        Self { kind, pos }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    UnexpectedChar(char),
+    InvalidNumber,
+    UnexpectedToken(Token),
+    UnexpectedEnd,
+    UnmatchedLeftParen,
+    UnmatchedRightParen,
+    /// A comma outside the parentheses of a function call.
+    MisplacedComma,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    DivisionByZero,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::UnexpectedToken(token) => write!(f, "unexpected `{token}`"),
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ErrorKind::UnmatchedLeftParen => write!(f, "unclosed `(`"),
+            ErrorKind::UnmatchedRightParen => write!(f, "unmatched `)`"),
+            ErrorKind::MisplacedComma => write!(f, "comma outside a function call"),
+            ErrorKind::UnknownVariable(name) => write!(f, "unknown variable `{name}`"),
+            ErrorKind::UnknownFunction(name) => write!(f, "unknown function `{name}`"),
+            ErrorKind::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "`{name}` takes {expected} argument(s) but {found} were given"
+            ),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.pos)
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linked_list::SingleLinkedList;
+
+    #[test]
+    fn test_arithmetic() {
+        let cases = [
+            ("1 + 2 * 3", 7.0),
+            ("(1 + 2) * 3", 9.0),
+            ("10 - 4 - 3", 3.0),
+            ("2 ^ 3 ^ 2", 512.0),
+            ("-2 ^ 2", -4.0),
+            ("2 ^ -1", 0.5),
+            ("-(3 - 5) * --2", 4.0),
+            ("7 % 4 / 2", 1.5),
+            ("1.5e1 + .5", 15.5),
+        ];
+        for (src, expected) in cases {
+            assert!(eval(src) == Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_variables_and_functions() {
+        let mut context = Context::new();
+        context.set("x", 3.0);
+        context.set("y", 4.0);
+        let expression = Expression::parse("hypot(x, y) + max(x, -y) * sqrt(16)").unwrap();
+        assert!(expression.eval(&context) == Ok(17.0));
+
+        context.define("clamp", 3, |args| args[0].clamp(args[1], args[2]));
+        context.define("answer", 0, |_| 42.0);
+        let expression = Expression::parse("clamp(x * 10, 0, 25) + answer()").unwrap();
+        assert!(expression.eval(&context) == Ok(67.0));
+
+        // The same expression can be evaluated against changing variables.
+        let expression = Expression::parse("x ^ 2").unwrap();
+        let squares = (1..=4)
+            .map(|x| {
+                context.set("x", x as f64);
+                expression.eval(&context).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert!(squares == [1.0, 4.0, 9.0, 16.0]);
+        assert!(eval("round(sin(pi / 2) * e ^ 0)") == Ok(1.0));
+    }
+
+    #[test]
+    fn test_evaluation_errors() {
+        let error = |src, kind, pos| eval(src) == Err(Error::new(kind, pos));
+        assert!(error(
+            "1 + x",
+            ErrorKind::UnknownVariable("x".to_string()),
+            4
+        ));
+        assert!(error(
+            "2 * f(1)",
+            ErrorKind::UnknownFunction("f".to_string()),
+            4
+        ));
+        assert!(error("1 / (2 - 2)", ErrorKind::DivisionByZero, 2));
+        assert!(error("1 % 0", ErrorKind::DivisionByZero, 2));
+        let wrong_arity = ErrorKind::WrongArity {
+            name: "max".to_string(),
+            expected: 2,
+            found: 3,
+        };
+        assert!(error(" max(1, 2, 3)", wrong_arity, 1));
+
+        let error = eval("sqrt(2 +* 3)").unwrap_err();
+        assert!(error.to_string() == "unexpected `*` at byte 8");
+        assert!(Context::empty().get("pi").is_none() && !Context::empty().is_defined("sin"));
+    }
+
+    #[test]
+    fn test_any_stack() {
+        let mut context = Context::new();
+        context.set("t", 0.25);
+        for src in [
+            "1 - 2 - 3",
+            "-t ^ 2 * atan2(1, 1) / pi",
+            "max(min(1, t), 0)",
+        ] {
+            let expression = Expression::parse(src).unwrap();
+            let on_list = expression.eval_with::<SingleLinkedList<_>>(&context);
+            assert!(on_list == expression.eval(&context));
+        }
+    }
+}
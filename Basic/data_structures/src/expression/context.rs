@@ -0,0 +1,180 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use super::{
+    parser::{Expression, Item, Operator},
+    Error, ErrorKind,
+};
+use crate::stack::Stack;
+
+type Body = Box<dyn Fn(&[f64]) -> f64>;
+
+struct Function {
+    arity: usize,
+    f: Body,
+}
+
+/// The variables and functions an expression is evaluated against.
+///
+/// `Context::new` starts with the constants `pi` and `e` and the usual
+/// functions of `f64`: `sqrt`, `abs`, `exp`, `ln`, `log10`, `log2`, `sin`,
+/// `cos`, `tan`, `asin`, `acos`, `atan`, `floor`, `ceil`, `round`, and the
+/// two-argument `atan2`, `hypot`, `min` and `max`.
+pub struct Context {
+    variables: HashMap<String, f64>,
+    functions: HashMap<String, Function>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        let mut context = Self::empty();
+        context.set("pi", std::f64::consts::PI);
+        context.set("e", std::f64::consts::E);
+        let unary: [(_, fn(_) -> _); 15] = [
+            ("sqrt", f64::sqrt),
+            ("abs", f64::abs),
+            ("exp", f64::exp),
+            ("ln", f64::ln),
+            ("log10", f64::log10),
+            ("log2", f64::log2),
+            ("sin", f64::sin),
+            ("cos", f64::cos),
+            ("tan", f64::tan),
+            ("asin", f64::asin),
+            ("acos", f64::acos),
+            ("atan", f64::atan),
+            ("floor", f64::floor),
+            ("ceil", f64::ceil),
+            ("round", f64::round),
+        ];
+        for (name, f) in unary {
+            context.define(name, 1, move |args| f(args[0]));
+        }
+        let binary: [(_, fn(_, _) -> _); 4] = [
+            ("atan2", f64::atan2),
+            ("hypot", f64::hypot),
+            ("min", f64::min),
+            ("max", f64::max),
+        ];
+        for (name, f) in binary {
+            context.define(name, 2, move |args| f(args[0], args[1]));
+        }
+        context
+    }
+
+    /// Creates a context with no variables or functions.
+    pub fn empty() -> Self {
+        Self {
+            variables: HashMap::new(),
+            functions: HashMap::new(),
+        }
+    }
+
+    /// Sets a variable and returns its previous value.
+    pub fn set(&mut self, name: impl Into<String>, value: f64) -> Option<f64> {
+        self.variables.insert(name.into(), value)
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.variables.get(name).copied()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<f64> {
+        self.variables.remove(name)
+    }
+
+    /// Defines, or redefines, a function taking exactly `arity` arguments.
+    /// `f` is only ever called with a slice of that length.
+    pub fn define(
+        &mut self,
+        name: impl Into<String>,
+        arity: usize,
+        f: impl Fn(&[f64]) -> f64 + 'static,
+    ) {
+        let f = Box::new(f);
+        self.functions.insert(name.into(), Function { arity, f });
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut functions = self
+            .functions
+            .iter()
+            .map(|(name, function)| format!("{name}/{}", function.arity))
+            .collect::<Vec<_>>();
+        functions.sort_unstable();
+        f.debug_struct("Context")
+            .field("variables", &self.variables)
+            .field("functions", &functions)
+            .finish()
+    }
+}
+
+impl Expression {
+    /// Evaluates the expression. Errors point at the variable, function or
+    /// operator that failed.
+    pub fn eval(&self, context: &Context) -> Result<f64, Error> {
+        self.eval_with::<Vec<_>>(context)
+    }
+
+    /// Runs the RPN on an `S` of intermediate values. Parsing guarantees
+    /// that every operator and call finds its operands on the stack.
+    pub(super) fn eval_with<S: Stack<f64>>(&self, context: &Context) -> Result<f64, Error> {
+        let mut values = S::new();
+        let pop = |values: &mut S| values.pop().expect("the parser checked the operands");
+        for (pos, item) in &self.rpn {
+            let value = match item {
+                Item::Number(x) => *x,
+                Item::Variable(name) => context
+                    .get(name)
+                    .ok_or_else(|| Error::new(ErrorKind::UnknownVariable(name.clone()), *pos))?,
+                Item::Operator(Operator::Neg) => -pop(&mut values),
+                Item::Operator(op) => {
+                    let b = pop(&mut values);
+                    let a = pop(&mut values);
+                    match op {
+                        Operator::Add => a + b,
+                        Operator::Sub => a - b,
+                        Operator::Mul => a * b,
+                        Operator::Div | Operator::Rem if b == 0.0 => {
+                            return Err(Error::new(ErrorKind::DivisionByZero, *pos));
+                        }
+                        Operator::Div => a / b,
+                        Operator::Rem => a % b,
+                        Operator::Pow => a.powf(b),
+                        Operator::Neg => unreachable!(),
+                    }
+                }
+                Item::Call { name, arity } => {
+                    let Some(function) = context.functions.get(name) else {
+                        let kind = ErrorKind::UnknownFunction(name.clone());
+                        return Err(Error::new(kind, *pos));
+                    };
+                    if function.arity != *arity {
+                        let kind = ErrorKind::WrongArity {
+                            name: name.clone(),
+                            expected: function.arity,
+                            found: *arity,
+                        };
+                        return Err(Error::new(kind, *pos));
+                    }
+                    let mut args = (0..*arity).map(|_| pop(&mut values)).collect::<Vec<_>>();
+                    args.reverse();
+                    (function.f)(&args)
+                }
+            };
+            values.push(value);
+        }
+        Ok(pop(&mut values))
+    }
+}
@@ -0,0 +1,352 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{
+    token::{tokenize, Token},
+    Error, ErrorKind,
+};
+use crate::stack::Stack;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    /// Unary minus.
+    Neg,
+}
+
+impl Operator {
+    fn binary(token: &Token) -> Option<Self> {
+        Some(match token {
+            Token::Plus => Operator::Add,
+            Token::Minus => Operator::Sub,
+            Token::Star => Operator::Mul,
+            Token::Slash => Operator::Div,
+            Token::Percent => Operator::Rem,
+            Token::Caret => Operator::Pow,
+            _ => return None,
+        })
+    }
+
+    /// Unary minus binds tighter than `*` but looser than `^`, so `-2^2` is
+    /// `-(2^2)` and `2^-1` is `2^(-1)`.
+    fn precedence(self) -> u8 {
+        match self {
+            Operator::Add | Operator::Sub => 1,
+            Operator::Mul | Operator::Div | Operator::Rem => 2,
+            Operator::Neg => 3,
+            Operator::Pow => 4,
+        }
+    }
+
+    fn is_right_associative(self) -> bool {
+        matches!(self, Operator::Pow | Operator::Neg)
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::Rem => "%",
+            Operator::Pow => "^",
+            Operator::Neg => "neg",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+/// One step of an expression in reverse Polish notation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Number(f64),
+    Variable(String),
+    Operator(Operator),
+    /// Calls a function on the `arity` values on top of the stack.
+    Call {
+        name: String,
+        arity: usize,
+    },
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Item::Number(x) => write!(f, "{x}"),
+            Item::Variable(name) => write!(f, "{name}"),
+            Item::Operator(op) => write!(f, "{op}"),
+            Item::Call { name, arity } => write!(f, "{name}/{arity}"),
+        }
+    }
+}
+
+/// A parsed expression, kept in reverse Polish notation. Every item carries
+/// the byte position of the token it came from, for error reporting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub(super) rpn: Vec<(usize, Item)>,
+}
+
+impl Expression {
+    pub fn parse(src: &str) -> Result<Self, Error> {
+        shunting_yard::<Vec<_>>(src)
+    }
+
+    pub fn rpn(&self) -> &[(usize, Item)] {
+        &self.rpn
+    }
+}
+
+impl FromStr for Expression {
+    type Err = Error;
+
+    fn from_str(src: &str) -> Result<Self, Error> {
+        Self::parse(src)
+    }
+}
+
+/// Prints the expression in reverse Polish notation, separated by spaces.
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (_, item)) in self.rpn.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What waits on the operator stack.
+#[derive(Debug)]
+pub(super) enum Pending {
+    Operator(Operator),
+    /// An opening parenthesis. For a function call, it also holds the
+    /// function's name and how many commas have been seen.
+    Paren {
+        call: Option<(usize, String)>,
+        commas: usize,
+    },
+}
+
+/// Converts `src` to reverse Polish notation with Dijkstra's shunting-yard
+/// algorithm, keeping the pending operators and parentheses on an `S`.
+///
+/// Besides the stack, the parser tracks whether it expects an operand or an
+/// operator next, which tells unary from binary minus and catches malformed
+/// input like `1 2` or `1 +` at the offending token.
+pub(super) fn shunting_yard<S: Stack<(usize, Pending)>>(src: &str) -> Result<Expression, Error> {
+    let tokens = tokenize(src)?;
+    let mut rpn = Vec::new();
+    let mut pending = S::new();
+    let mut expect_operand = true;
+
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some((pos, token)) = tokens.next() {
+        let unexpected = || Error::new(ErrorKind::UnexpectedToken(token.clone()), pos);
+        match &token {
+            Token::Number(_) | Token::Ident(_) if !expect_operand => return Err(unexpected()),
+            Token::Number(x) => {
+                rpn.push((pos, Item::Number(*x)));
+                expect_operand = false;
+            }
+            Token::Ident(name) => {
+                if tokens
+                    .next_if(|(_, token)| *token == Token::LeftParen)
+                    .is_some()
+                {
+                    let call = Some((pos, name.clone()));
+                    pending.push((pos, Pending::Paren { call, commas: 0 }));
+                } else {
+                    rpn.push((pos, Item::Variable(name.clone())));
+                    expect_operand = false;
+                }
+            }
+            Token::Minus if expect_operand => {
+                // A prefix operator has no left operand to finish, so it
+                // never pops anything.
+                pending.push((pos, Pending::Operator(Operator::Neg)));
+            }
+            Token::LeftParen if expect_operand => {
+                let paren = Pending::Paren {
+                    call: None,
+                    commas: 0,
+                };
+                pending.push((pos, paren));
+            }
+            Token::Comma if !expect_operand => {
+                pop_operators(&mut pending, &mut rpn, 0);
+                match pending.pop() {
+                    Some((
+                        paren_pos,
+                        Pending::Paren {
+                            call: Some(call),
+                            commas,
+                        },
+                    )) => {
+                        let paren = Pending::Paren {
+                            call: Some(call),
+                            commas: commas + 1,
+                        };
+                        pending.push((paren_pos, paren));
+                        expect_operand = true;
+                    }
+                    _ => return Err(Error::new(ErrorKind::MisplacedComma, pos)),
+                }
+            }
+            Token::RightParen if expect_operand => {
+                // Only `f()` closes right after an opening parenthesis.
+                match pending.pop() {
+                    Some((
+                        _,
+                        Pending::Paren {
+                            call: Some((call_pos, name)),
+                            commas: 0,
+                        },
+                    )) => {
+                        rpn.push((call_pos, Item::Call { name, arity: 0 }));
+                        expect_operand = false;
+                    }
+                    _ => return Err(unexpected()),
+                }
+            }
+            Token::RightParen => {
+                pop_operators(&mut pending, &mut rpn, 0);
+                match pending.pop() {
+                    Some((
+                        _,
+                        Pending::Paren {
+                            call: Some((call_pos, name)),
+                            commas,
+                        },
+                    )) => {
+                        let arity = commas + 1;
+                        rpn.push((call_pos, Item::Call { name, arity }));
+                    }
+                    Some((_, Pending::Paren { call: None, .. })) => {}
+                    _ => return Err(Error::new(ErrorKind::UnmatchedRightParen, pos)),
+                }
+            }
+            _ if expect_operand => return Err(unexpected()),
+            token => {
+                let Some(op) = Operator::binary(token) else {
+                    return Err(unexpected());
+                };
+                // Operators that bind at least as tightly, or strictly more
+                // tightly for right-associative `op`, are finished first.
+                let min = op.precedence() + u8::from(op.is_right_associative());
+                pop_operators(&mut pending, &mut rpn, min);
+                pending.push((pos, Pending::Operator(op)));
+                expect_operand = true;
+            }
+        }
+    }
+
+    if expect_operand {
+        return Err(Error::new(ErrorKind::UnexpectedEnd, src.len()));
+    }
+    while let Some((pos, entry)) = pending.pop() {
+        match entry {
+            Pending::Operator(op) => rpn.push((pos, Item::Operator(op))),
+            Pending::Paren { .. } => return Err(Error::new(ErrorKind::UnmatchedLeftParen, pos)),
+        }
+    }
+    Ok(Expression { rpn })
+}
+
+/// Moves operators with a precedence of at least `min` from the top of
+/// `pending` to the output, stopping at a parenthesis.
+fn pop_operators<S: Stack<(usize, Pending)>>(
+    pending: &mut S,
+    rpn: &mut Vec<(usize, Item)>,
+    min: u8,
+) {
+    loop {
+        let op = match pending.peek() {
+            Some((_, Pending::Operator(op))) if op.precedence() >= min => *op,
+            _ => return,
+        };
+        if let Some((pos, _)) = pending.pop() {
+            rpn.push((pos, Item::Operator(op)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linked_list::SingleLinkedList;
+
+    fn rpn(src: &str) -> String {
+        Expression::parse(src).unwrap().to_string()
+    }
+
+    fn error(src: &str) -> Error {
+        Expression::parse(src).unwrap_err()
+    }
+
+    #[test]
+    fn test_precedence_and_associativity() {
+        assert!(rpn("1 + 2 * 3") == "1 2 3 * +");
+        assert!(rpn("(1 + 2) * 3") == "1 2 + 3 *");
+        assert!(rpn("8 - 4 - 2") == "8 4 - 2 -");
+        assert!(rpn("8 / 4 % 3 * 2") == "8 4 / 3 % 2 *");
+        assert!(rpn("2 ^ 3 ^ 2") == "2 3 2 ^ ^");
+        assert!(rpn("-2 ^ 2") == "2 2 ^ neg");
+        assert!(rpn("2 ^ -1") == "2 1 neg ^");
+        assert!(rpn("--x * -y") == "x neg neg y neg *");
+        assert!(rpn("1 - -1") == "1 1 neg -");
+    }
+
+    #[test]
+    fn test_functions() {
+        assert!(rpn("max(1, 2 + x) * 2") == "1 2 x + max/2 2 *");
+        assert!(rpn("f(g(x), (1), h())") == "x g/1 1 h/0 f/3");
+        assert!(rpn("-sin(x)^2") == "x sin/1 2 ^ neg");
+        assert!(rpn("sin (x)") == "x sin/1");
+
+        // Calls are reported where the function name starts.
+        let expression = Expression::parse("1 + f(2)").unwrap();
+        assert!(expression.rpn()[1] == (6, Item::Number(2.0)));
+        let call = Item::Call {
+            name: "f".to_string(),
+            arity: 1,
+        };
+        assert!(expression.rpn()[2] == (4, call));
+    }
+
+    #[test]
+    fn test_errors_carry_positions() {
+        let unexpected = |token, pos| Error::new(ErrorKind::UnexpectedToken(token), pos);
+        assert!(error("1 2") == unexpected(Token::Number(2.0), 2));
+        assert!(error("1 * * 2") == unexpected(Token::Star, 4));
+        assert!(error("2 (1)") == unexpected(Token::LeftParen, 2));
+        assert!(error("()") == unexpected(Token::RightParen, 1));
+        assert!(error("f(1,)") == unexpected(Token::RightParen, 4));
+        assert!(error("f(,1)") == unexpected(Token::Comma, 2));
+        assert!(error("(-)") == unexpected(Token::RightParen, 2));
+        assert!(error("1 +") == Error::new(ErrorKind::UnexpectedEnd, 3));
+        assert!(error("") == Error::new(ErrorKind::UnexpectedEnd, 0));
+        assert!(error("(1 + 2") == Error::new(ErrorKind::UnmatchedLeftParen, 0));
+        assert!(error("1 + 2)") == Error::new(ErrorKind::UnmatchedRightParen, 5));
+        assert!(error("(1, 2)") == Error::new(ErrorKind::MisplacedComma, 2));
+        assert!(error("1, 2") == Error::new(ErrorKind::MisplacedComma, 1));
+        assert!(error("1 # 2") == Error::new(ErrorKind::UnexpectedChar('#'), 2));
+    }
+
+    #[test]
+    fn test_any_stack() {
+        for src in ["1 + 2 * 3", "-f(x, 2 ^ y) % 4", "((1))"] {
+            let on_list = shunting_yard::<SingleLinkedList<_>>(src).unwrap();
+            assert!(on_list == Expression::parse(src).unwrap());
+        }
+        assert!(shunting_yard::<SingleLinkedList<_>>("(").is_err());
+    }
+}
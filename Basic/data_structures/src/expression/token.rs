@@ -0,0 +1,156 @@
+use std::fmt::Display;
+
+use super::{Error, ErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(x) => write!(f, "{x}"),
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::Percent => write!(f, "%"),
+            Token::Caret => write!(f, "^"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+/// Splits `src` into tokens, each paired with the byte position it starts at.
+///
+/// Numbers are decimal with an optional fraction and exponent, like `2`,
+/// `.5` or `1.5e-3`. Identifiers start with a letter or `_` and go on with
+/// letters, digits and `_`.
+pub fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, Error> {
+    let bytes = src.as_bytes();
+    let digits_from = |mut i: usize| {
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some((pos, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ',' => Token::Comma,
+            '0'..='9' | '.' => {
+                let mut end = digits_from(pos);
+                if bytes.get(end) == Some(&b'.') {
+                    end = digits_from(end + 1);
+                }
+                if end == pos + 1 && c == '.' {
+                    return Err(Error::new(ErrorKind::UnexpectedChar('.'), pos));
+                }
+                // Only take an exponent that has digits, so `2e` is `2` then `e`.
+                if let Some(b'e' | b'E') = bytes.get(end) {
+                    let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+                    let exponent = end + 1 + sign;
+                    if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+                        end = digits_from(exponent);
+                    }
+                }
+                while chars.next_if(|&(i, _)| i < end).is_some() {}
+                match src[pos..end].parse() {
+                    Ok(x) => Token::Number(x),
+                    Err(_) => return Err(Error::new(ErrorKind::InvalidNumber, pos)),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = pos + c.len_utf8();
+                while let Some((i, c)) = chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '_') {
+                    end = i + c.len_utf8();
+                }
+                Token::Ident(src[pos..end].to_string())
+            }
+            c => return Err(Error::new(ErrorKind::UnexpectedChar(c), pos)),
+        };
+        tokens.push((pos, token));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_and_positions() {
+        let tokens = tokenize(" x_1*(2.5e3 - .5)^f(y,  1E-2)%3/ 4.").unwrap();
+        let expected = [
+            (1, Token::Ident("x_1".to_string())),
+            (4, Token::Star),
+            (5, Token::LeftParen),
+            (6, Token::Number(2500.0)),
+            (12, Token::Minus),
+            (14, Token::Number(0.5)),
+            (16, Token::RightParen),
+            (17, Token::Caret),
+            (18, Token::Ident("f".to_string())),
+            (19, Token::LeftParen),
+            (20, Token::Ident("y".to_string())),
+            (21, Token::Comma),
+            (24, Token::Number(0.01)),
+            (28, Token::RightParen),
+            (29, Token::Percent),
+            (30, Token::Number(3.0)),
+            (31, Token::Slash),
+            (33, Token::Number(4.0)),
+        ];
+        assert!(tokens == expected);
+    }
+
+    #[test]
+    fn test_exponent_needs_digits() {
+        let tokens = tokenize("2e+x 3e").unwrap();
+        let expected = [
+            (0, Token::Number(2.0)),
+            (1, Token::Ident("e".to_string())),
+            (2, Token::Plus),
+            (3, Token::Ident("x".to_string())),
+            (5, Token::Number(3.0)),
+            (6, Token::Ident("e".to_string())),
+        ];
+        assert!(tokens == expected);
+    }
+
+    #[test]
+    fn test_unicode_and_errors() {
+        let tokens = tokenize("θ·2").unwrap_err();
+        assert!(tokens == Error::new(ErrorKind::UnexpectedChar('·'), 2));
+        let tokens = tokenize("αβ + 1").unwrap();
+        assert!(tokens[0] == (0, Token::Ident("αβ".to_string())));
+        assert!(tokens[1] == (5, Token::Plus));
+        assert!(tokenize("1 + . 2").unwrap_err() == Error::new(ErrorKind::UnexpectedChar('.'), 4));
+        assert!(tokenize("1 $").unwrap_err() == Error::new(ErrorKind::UnexpectedChar('$'), 2));
+    }
+}
@@ -0,0 +1,247 @@
+use std::cmp::Ordering;
+
+pub mod binomial_heap;
+pub use binomial_heap::BinomialHeap;
+pub mod dary_heap;
+pub use dary_heap::{BinaryHeap, DaryHeap};
+pub mod fibonacci_heap;
+pub use fibonacci_heap::FibonacciHeap;
+pub mod handle;
+pub mod indexed_heap;
+pub use indexed_heap::{IndexedHeap, KeyedHeap};
+pub mod leftist_heap;
+pub use leftist_heap::{LeftistHeap, SkewHeap, SpineHeap};
+pub mod min_max_heap;
+pub use min_max_heap::MinMaxHeap;
+pub mod pairing_heap;
+pub use pairing_heap::PairingHeap;
+
+/// Decides the order in which a heap hands out its elements. The element that
+/// compares `Less` than all others is the "min" and comes out first.
+pub trait Compare<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Orders elements by `Ord`, giving a min-heap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Natural;
+
+impl<T: Ord + ?Sized> Compare<T> for Natural {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Orders elements by reversed `Ord`, giving a max-heap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Reversed;
+
+impl<T: Ord + ?Sized> Compare<T> for Reversed {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// Orders elements by the key `F` extracts from them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ByKey<F>(pub F);
+
+impl<T: ?Sized, K: Ord, F: Fn(&T) -> K> Compare<T> for ByKey<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.0(a).cmp(&self.0(b))
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// A collection that always hands out its smallest element first, where
+/// "smallest" is decided by the heap's comparator.
+pub trait PriorityQueue<T> {
+    fn push(&mut self, x: T);
+    fn pop_min(&mut self) -> Option<T>;
+    fn peek(&self) -> Option<&T>;
+    fn size(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Consumes the heap and returns its elements in the order `pop_min`
+    /// would have produced them.
+    fn into_sorted_vec(mut self) -> Vec<T>
+    where
+        Self: Sized,
+    {
+        let mut sorted = Vec::with_capacity(self.size());
+        while let Some(x) = self.pop_min() {
+            sorted.push(x);
+        }
+        sorted
+    }
+}
+
+/// A priority queue that can also hand out its largest element.
+pub trait DoubleEndedPriorityQueue<T>: PriorityQueue<T> {
+    fn pop_max(&mut self) -> Option<T>;
+    fn peek_max(&self) -> Option<&T>;
+}
+
+/// A priority queue that can absorb another one of its kind and lower the
+/// value of any element it handed out a handle for.
+///
+/// Handles never keep their element alive and stop resolving once it is
+/// popped. They stay valid when their heap is melded into another.
+pub trait MeldableHeap<T>: PriorityQueue<T> {
+    type Handle: Clone;
+
+    /// Pushes `x` and returns a handle to it.
+    fn insert(&mut self, x: T) -> Self::Handle;
+
+    /// Moves every element of `other` into `self`.
+    fn meld(&mut self, other: Self);
+
+    /// Replaces the element behind `handle` with the smaller `x` and returns
+    /// the old one, or `None` if the handle does not resolve in this heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is greater than the current element.
+    fn decrease_key(&mut self, handle: &Self::Handle, x: T) -> Option<T>;
+
+    fn get(&self, handle: &Self::Handle) -> Option<&T>;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    fn check_priority_queue<H: PriorityQueue<i32>>(mut heap: H, descending: bool) {
+        let mut rng = rand::thread_rng();
+        let mut model = Vec::new();
+        for _ in 0..2000 {
+            if rng.gen_bool(0.6) {
+                let x = rng.gen_range(-500..500);
+                heap.push(x);
+                model.push(x);
+            } else {
+                model.sort_unstable();
+                if descending {
+                    model.reverse();
+                }
+                let expected = (!model.is_empty()).then(|| model.remove(0));
+                assert!(heap.peek().copied() == expected);
+                assert!(heap.pop_min() == expected);
+            }
+            assert!(heap.size() == model.len());
+        }
+
+        model.sort_unstable();
+        if descending {
+            model.reverse();
+        }
+        assert!(heap.into_sorted_vec() == model);
+    }
+
+    #[test]
+    fn test_heaps_against_sorted_vec() {
+        check_priority_queue(BinaryHeap::new(), false);
+        check_priority_queue(DaryHeap::<_, 3>::new(), false);
+        check_priority_queue(DaryHeap::<_, 8>::new(), false);
+        check_priority_queue(MinMaxHeap::new(), false);
+
+        check_priority_queue(BinaryHeap::with_comparator(Reversed), true);
+        check_priority_queue(DaryHeap::<_, 4, _>::with_comparator(Reversed), true);
+        check_priority_queue(MinMaxHeap::with_comparator(Reversed), true);
+    }
+
+    /// Runs random operations on two heaps, melding them now and then, and
+    /// compares against sorted vectors of (value, handle) pairs.
+    fn check_meldable_heap<H: MeldableHeap<i32>>(make: impl Fn() -> H) {
+        let mut rng = rand::thread_rng();
+        let mut heaps = [make(), make()];
+        let mut models: [Vec<(i32, H::Handle)>; 2] = [Vec::new(), Vec::new()];
+        for _ in 0..3000 {
+            let i = rng.gen_range(0..2);
+            match rng.gen_range(0..10) {
+                0..=3 => {
+                    let x = rng.gen_range(-1000..1000);
+                    let handle = heaps[i].insert(x);
+                    models[i].push((x, handle));
+                }
+                4 | 5 if !models[i].is_empty() => {
+                    let k = rng.gen_range(0..models[i].len());
+                    let (x, handle) = &mut models[i][k];
+                    let smaller = *x - rng.gen_range(0..100);
+                    assert!(heaps[i].decrease_key(handle, smaller) == Some(*x));
+                    *x = smaller;
+                }
+                6 | 7 => {
+                    let popped = heaps[i].pop_min();
+                    let min = models[i].iter().map(|&(x, _)| x).min();
+                    assert!(popped == min);
+                    // Of several equal minima, the popped one is the one
+                    // whose handle stopped resolving.
+                    let heap = &heaps[i];
+                    models[i].retain(|(_, handle)| heap.get(handle).is_some());
+                }
+                8 => {
+                    let other = std::mem::replace(&mut heaps[1 - i], make());
+                    let moved = std::mem::take(&mut models[1 - i]);
+                    heaps[i].meld(other);
+                    models[i].extend(moved);
+                }
+                _ => {}
+            }
+            for (heap, model) in heaps.iter().zip(&models) {
+                assert!(heap.size() == model.len());
+                assert!(heap.peek().copied() == model.iter().map(|&(x, _)| x).min());
+                for (x, handle) in model {
+                    assert!(heap.get(handle) == Some(x));
+                }
+            }
+            // A handle only resolves in the heap that holds its element.
+            for (heap, model) in heaps.iter().zip(models.iter().rev()) {
+                assert!(model.iter().all(|(_, handle)| heap.get(handle).is_none()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_meldable_heaps_against_model() {
+        check_meldable_heap(PairingHeap::new);
+        check_meldable_heap(LeftistHeap::new);
+        check_meldable_heap(SkewHeap::new);
+        check_meldable_heap(BinomialHeap::new);
+        check_meldable_heap(FibonacciHeap::new);
+    }
+
+    #[test]
+    fn test_comparators() {
+        let mut heap = BinaryHeap::with_comparator(ByKey(|s: &&str| s.len()));
+        heap.extend(["ccc", "a", "bb", "dddd"]);
+        assert!(heap.into_sorted_vec() == ["a", "bb", "ccc", "dddd"]);
+
+        let mut heap = DaryHeap::<_, 4, _>::with_comparator(|a: &(i32, i32), b: &(i32, i32)| {
+            b.1.cmp(&a.1).then(a.0.cmp(&b.0))
+        });
+        heap.extend([(1, 1), (2, 3), (3, 3), (4, 2)]);
+        assert!(heap.into_sorted_vec() == [(2, 3), (3, 3), (4, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn test_empty_heaps() {
+        let mut heap: BinaryHeap<i32> = BinaryHeap::new();
+        assert!(heap.is_empty() && heap.peek().is_none() && heap.pop_min().is_none());
+        assert!(heap.into_sorted_vec().is_empty());
+
+        let mut heap: MinMaxHeap<i32> = MinMaxHeap::new();
+        assert!(heap.pop_min().is_none() && heap.pop_max().is_none());
+        assert!(heap.peek_max().is_none());
+    }
+}
@@ -0,0 +1,419 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    rc::{Rc, Weak},
+};
+
+use super::{
+    handle::{self, Entry, HasEntry},
+    Compare, MeldableHeap, Natural, PriorityQueue,
+};
+use crate::owner::Owner;
+
+/// An element of a `BinomialHeap` and the tree node currently holding it.
+/// `decrease_key` moves items up the tree by swapping them between nodes, so
+/// handles point at items rather than nodes.
+pub struct Item<T> {
+    entry: Entry<T>,
+    node: RefCell<Weak<Node<T>>>,
+}
+
+impl<T> HasEntry for Item<T> {
+    type Value = T;
+
+    fn entry(&self) -> &Entry<T> {
+        &self.entry
+    }
+}
+
+/// The root of a binomial tree of order k has k children, of orders 0 to
+/// k - 1 in that order.
+struct Node<T> {
+    item: RefCell<Rc<Item<T>>>,
+    parent: RefCell<Weak<Node<T>>>,
+    children: RefCell<Vec<Rc<Node<T>>>>,
+}
+
+/// A handle to an element of a `BinomialHeap`.
+pub type Handle<T> = handle::Handle<Item<T>>;
+
+/// A forest with at most one binomial tree of each order, like the bits of
+/// the element count. Melding adds two forests like binary numbers in
+/// O(log n), and inserting is O(1) amortized.
+pub struct BinomialHeap<T, C = Natural> {
+    /// The tree of order k, if any, at index k.
+    roots: Vec<Option<Rc<Node<T>>>>,
+    min: Option<usize>,
+    len: usize,
+    owner: Rc<Owner>,
+    cmp: C,
+}
+
+impl<T: Ord> BinomialHeap<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, C> BinomialHeap<T, C> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        let mut stack: Vec<_> = self.roots.drain(..).flatten().collect();
+        while let Some(node) = stack.pop() {
+            stack.append(&mut node.children.borrow_mut());
+        }
+        self.min = None;
+        self.len = 0;
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        let root = self.roots[self.min?].as_ref()?;
+        Some(Self::value(root))
+    }
+
+    /// The item outlives the borrow of the node's `RefCell`, and items are
+    /// only moved between nodes through `&mut self`.
+    fn value(node: &Node<T>) -> &T {
+        let value: *const T = node.item.borrow().entry.value();
+        unsafe { &*value }
+    }
+}
+
+impl<T, C: Compare<T>> BinomialHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            roots: Vec::new(),
+            min: None,
+            len: 0,
+            owner: Rc::default(),
+            cmp,
+        }
+    }
+
+    pub fn insert(&mut self, x: T) -> Handle<T> {
+        let item = Rc::new(Item {
+            entry: Entry::new(x, &self.owner),
+            node: RefCell::new(Weak::new()),
+        });
+        let handle = Handle::new(&item);
+        let node = Rc::new(Node {
+            item: RefCell::new(item),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(Vec::new()),
+        });
+        *node.item.borrow().node.borrow_mut() = Rc::downgrade(&node);
+
+        // Incrementing the binary counter: carry until a free order.
+        let mut carry = node;
+        let mut order = 0;
+        while let Some(tree) = self.roots.get_mut(order).and_then(Option::take) {
+            carry = self.link(tree, carry);
+            order += 1;
+        }
+        if order == self.roots.len() {
+            self.roots.push(None);
+        }
+        self.roots[order] = Some(carry);
+
+        // The minimum either got carried into the new tree, whose root is
+        // then the minimum, or is still where it was.
+        self.min = match self.min {
+            Some(min) if min > order && !self.less_root(order, min) => Some(min),
+            _ => Some(order),
+        };
+        self.len += 1;
+        handle
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        let root = self.roots[self.min?].take()?;
+        let children = root.children.take();
+        for child in &children {
+            *child.parent.borrow_mut() = Weak::new();
+        }
+        self.add(children.into_iter().map(Some).collect());
+        self.len -= 1;
+
+        let item = root.item.borrow().clone();
+        drop(root);
+        match Rc::try_unwrap(item) {
+            Ok(item) => Some(item.entry.into_value()),
+            Err(_) => unreachable!("the heap holds the only strong reference to an item"),
+        }
+    }
+
+    /// Moves every element of `other` into `self` in O(log n).
+    pub fn meld(&mut self, mut other: Self) {
+        Owner::adopt(&mut self.owner, &other.owner);
+        self.len += std::mem::take(&mut other.len);
+        other.min = None;
+        self.add(std::mem::take(&mut other.roots));
+    }
+
+    /// See `MeldableHeap::decrease_key`. The element is swapped up towards
+    /// the root of its tree, in O(log n).
+    pub fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        let item = handle.resolve(&self.owner)?;
+        assert!(
+            self.cmp.compare(&x, item.entry.value()).is_le(),
+            "decrease_key would increase the element"
+        );
+        // `&mut self` rules out any reference to the old value.
+        let old = unsafe { item.entry.replace(x) };
+
+        let mut node = item.node.borrow().upgrade()?;
+        loop {
+            let parent = node.parent.borrow().upgrade();
+            let Some(parent) = parent.filter(|parent| self.less(&node, parent)) else {
+                break;
+            };
+            node.item.swap(&parent.item);
+            *node.item.borrow().node.borrow_mut() = Rc::downgrade(&node);
+            *parent.item.borrow().node.borrow_mut() = Rc::downgrade(&parent);
+            node = parent;
+        }
+        self.update_min();
+        Some(old)
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        handle.get(&self.owner)
+    }
+
+    fn less(&self, a: &Node<T>, b: &Node<T>) -> bool {
+        self.cmp.compare(Self::value(a), Self::value(b)).is_lt()
+    }
+
+    fn less_root(&self, a: usize, b: usize) -> bool {
+        match (&self.roots[a], &self.roots[b]) {
+            (Some(a), Some(b)) => self.less(a, b),
+            _ => false,
+        }
+    }
+
+    /// Makes the root with the larger element the last child of the other.
+    /// Both trees must have the same order.
+    fn link(&self, a: Rc<Node<T>>, b: Rc<Node<T>>) -> Rc<Node<T>> {
+        let (parent, child) = if self.less(&b, &a) { (b, a) } else { (a, b) };
+        *child.parent.borrow_mut() = Rc::downgrade(&parent);
+        parent.children.borrow_mut().push(child);
+        parent
+    }
+
+    /// Adds the forest `other`, indexed by order, like binary numbers.
+    fn add(&mut self, other: Vec<Option<Rc<Node<T>>>>) {
+        let len = self.roots.len().max(other.len());
+        self.roots.resize_with(len, || None);
+        let mut other = other.into_iter();
+        let mut carry = None;
+        for order in 0..len {
+            let mut trees = [
+                self.roots[order].take(),
+                other.next().flatten(),
+                carry.take(),
+            ]
+            .into_iter()
+            .flatten();
+            match (trees.next(), trees.next(), trees.next()) {
+                (Some(a), Some(b), rest) => {
+                    self.roots[order] = rest;
+                    carry = Some(self.link(a, b));
+                }
+                (tree, _, _) => self.roots[order] = tree,
+            }
+        }
+        self.roots.extend(carry.map(Some));
+        while let Some(None) = self.roots.last() {
+            self.roots.pop();
+        }
+        self.update_min();
+    }
+
+    fn update_min(&mut self) {
+        self.min = (0..self.roots.len())
+            .filter(|&order| self.roots[order].is_some())
+            .reduce(|min, order| {
+                if self.less_root(order, min) {
+                    order
+                } else {
+                    min
+                }
+            });
+    }
+}
+
+impl<T, C> Drop for BinomialHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for BinomialHeap<T, C> {
+    fn push(&mut self, x: T) {
+        self.insert(x);
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for BinomialHeap<T, C> {
+    type Handle = Handle<T>;
+
+    fn insert(&mut self, x: T) -> Handle<T> {
+        self.insert(x)
+    }
+
+    fn meld(&mut self, other: Self) {
+        self.meld(other)
+    }
+
+    fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        self.decrease_key(handle, x)
+    }
+
+    fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.get(handle)
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for BinomialHeap<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, C> Debug for BinomialHeap<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BinomialHeap")
+            .field("len", &self.len)
+            .field("min", &self.peek())
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for BinomialHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for BinomialHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| {
+            self.insert(x);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use rand::Rng;
+
+    use super::*;
+
+    /// Checks that the tree at index k is a heap-ordered binomial tree of
+    /// order k, and that the trees hold the bits of `len`.
+    fn check_forest<T, C: Compare<T>>(heap: &BinomialHeap<T, C>) {
+        let mut count = 0;
+        for (order, root) in heap.roots.iter().enumerate() {
+            let Some(root) = root else { continue };
+            count += 1 << order;
+            assert!(root.parent.borrow().upgrade().is_none());
+            let mut stack = vec![(root.clone(), order)];
+            while let Some((node, order)) = stack.pop() {
+                assert!(Rc::ptr_eq(
+                    &node.item.borrow().node.borrow().upgrade().unwrap(),
+                    &node
+                ));
+                let children = node.children.borrow();
+                assert!(children.len() == order);
+                for (child_order, child) in children.iter().enumerate() {
+                    assert!(!heap.less(child, &node));
+                    assert!(Rc::ptr_eq(&child.parent.borrow().upgrade().unwrap(), &node));
+                    stack.push((child.clone(), child_order));
+                }
+            }
+        }
+        assert!(count == heap.len());
+        if let Some(min) = heap.min {
+            for order in 0..heap.roots.len() {
+                assert!(!heap.less_root(order, min));
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_is_amortized_constant() {
+        let n: usize = 1 << 12;
+        let count = Cell::new(0);
+        let cmp = |a: &u32, b: &u32| {
+            count.set(count.get() + 1);
+            a.cmp(b)
+        };
+        let mut rng = rand::thread_rng();
+        let mut heap = BinomialHeap::with_comparator(cmp);
+        for i in 0..n {
+            heap.insert(rng.gen());
+            // Like incrementing a binary counter: fewer than i links so far,
+            // plus at most one comparison against the old minimum each.
+            assert!(count.get() <= 2 * i);
+        }
+        check_forest(&heap);
+
+        // `pop_min` is O(log n): about log n links and a scan of the roots.
+        let log_n = n.ilog2() as usize;
+        count.set(0);
+        heap.pop_min();
+        assert!(count.get() <= 3 * log_n);
+    }
+
+    #[test]
+    fn test_meld_and_decrease_key() {
+        let mut rng = rand::thread_rng();
+        let mut a = BinomialHeap::new();
+        let mut b = BinomialHeap::new();
+        let handles = (0..300)
+            .map(|i| {
+                let heap = if i % 3 == 0 { &mut a } else { &mut b };
+                heap.insert(rng.gen_range(1000..2000))
+            })
+            .collect::<Vec<_>>();
+        check_forest(&a);
+        check_forest(&b);
+        a.meld(b);
+        check_forest(&a);
+
+        for handle in &handles {
+            let x = *a.get(handle).unwrap();
+            assert!(a.decrease_key(handle, x - rng.gen_range(0..1000)) == Some(x));
+            check_forest(&a);
+        }
+        let mut last = 0;
+        while let Some(x) = a.pop_min() {
+            assert!(x >= last);
+            last = x;
+            check_forest(&a);
+        }
+    }
+}
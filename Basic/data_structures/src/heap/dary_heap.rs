@@ -0,0 +1,222 @@
+use std::fmt::Debug;
+
+use super::{Compare, Natural, PriorityQueue};
+
+/// The ordinary binary heap, i.e. a d-ary heap with two children per node.
+pub type BinaryHeap<T, C = Natural> = DaryHeap<T, 2, C>;
+
+/// An implicit heap stored in a `Vec` where every node has `D` children.
+///
+/// A larger arity makes the tree shallower, so `push` does fewer comparisons
+/// while `pop_min` does more, since it has to pick the best of `D` children
+/// at every level.
+#[derive(Clone)]
+pub struct DaryHeap<T, const D: usize, C = Natural> {
+    data: Vec<T>,
+    cmp: C,
+}
+
+impl<T: Ord, const D: usize> DaryHeap<T, D> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, const D: usize, C: Compare<T>> DaryHeap<T, D, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self::from_vec_with(Vec::new(), cmp)
+    }
+
+    /// Builds a heap out of `data` in O(n).
+    pub fn from_vec_with(data: Vec<T>, cmp: C) -> Self {
+        const { assert!(D >= 2, "a heap needs at least two children per node") };
+        let mut heap = Self { data, cmp };
+        for i in (0..heap.data.len() / D + 1).rev() {
+            heap.sift_down(i, heap.data.len());
+        }
+        heap
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    pub fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.data.push(x);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        let x = self.data.pop()?;
+        if self.data.is_empty() {
+            return Some(x);
+        }
+        let min = std::mem::replace(&mut self.data[0], x);
+        self.sift_down(0, self.data.len());
+        Some(min)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    /// Iterates over the elements in no particular order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Returns the underlying vector, in heap order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Sorts in place by repeatedly moving the minimum behind the shrinking
+    /// heap, which leaves the vector in reverse order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        for end in (1..self.data.len()).rev() {
+            self.data.swap(0, end);
+            self.sift_down(0, end);
+        }
+        self.data.reverse();
+        self.data
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        self.cmp.compare(&self.data[i], &self.data[j]).is_lt()
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / D;
+            if !self.less(i, parent) {
+                break;
+            }
+            self.data.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    /// Sifts the element at `i` down within the first `end` elements.
+    fn sift_down(&mut self, mut i: usize, end: usize) {
+        loop {
+            let first = i * D + 1;
+            if first >= end {
+                break;
+            }
+            let best = (first + 1..(first + D).min(end)).fold(first, |best, child| {
+                if self.less(child, best) {
+                    child
+                } else {
+                    best
+                }
+            });
+            if !self.less(best, i) {
+                break;
+            }
+            self.data.swap(i, best);
+            i = best;
+        }
+    }
+}
+
+impl<T, const D: usize, C: Compare<T>> PriorityQueue<T> for DaryHeap<T, D, C> {
+    fn push(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn into_sorted_vec(self) -> Vec<T> {
+        self.into_sorted_vec()
+    }
+}
+
+impl<T, const D: usize, C: Compare<T> + Default> Default for DaryHeap<T, D, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, const D: usize, C> Debug for DaryHeap<T, D, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+impl<T, const D: usize, C: Compare<T> + Default> FromIterator<T> for DaryHeap<T, D, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec_with(iter.into_iter().collect(), C::default())
+    }
+}
+
+impl<T, const D: usize, C: Compare<T>> Extend<T> for DaryHeap<T, D, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::heap::Reversed;
+
+    fn check_heap_order<T, const D: usize, C: Compare<T>>(heap: &DaryHeap<T, D, C>) {
+        for i in 1..heap.len() {
+            assert!(!heap.less(i, (i - 1) / D));
+        }
+    }
+
+    #[test]
+    fn test_heapify() {
+        let mut rng = rand::thread_rng();
+        for len in [0, 1, 2, 5, 100, 1001] {
+            let data = (0..len).map(|_| rng.gen_range(0..50)).collect::<Vec<_>>();
+            let mut sorted = data.clone();
+            sorted.sort_unstable();
+
+            let heap = BinaryHeap::from_vec_with(data.clone(), Natural);
+            check_heap_order(&heap);
+            assert!(heap.into_sorted_vec() == sorted);
+
+            let heap = DaryHeap::<_, 5, _>::from_vec_with(data, Reversed);
+            check_heap_order(&heap);
+            sorted.reverse();
+            assert!(heap.into_sorted_vec() == sorted);
+        }
+    }
+
+    #[test]
+    fn test_collect_and_iter() {
+        let heap: DaryHeap<_, 3> = [5, 3, 9, 1, 7].into_iter().collect();
+        check_heap_order(&heap);
+        assert!(heap.peek() == Some(&1) && heap.len() == 5);
+
+        let mut items = heap.iter().copied().collect::<Vec<_>>();
+        items.sort_unstable();
+        assert!(items == [1, 3, 5, 7, 9]);
+    }
+}
@@ -0,0 +1,433 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    rc::{Rc, Weak},
+};
+
+use super::{
+    handle::{self, Entry, HasEntry},
+    Compare, MeldableHeap, Natural, PriorityQueue,
+};
+use crate::{
+    linked_list::{doubly_linked_list, DoublyLinkedList},
+    owner::Owner,
+};
+
+type List<T> = DoublyLinkedList<Rc<Node<T>>>;
+
+/// A node of a `FibonacciHeap`.
+pub struct Node<T> {
+    entry: Entry<T>,
+    parent: RefCell<Weak<Node<T>>>,
+    children: RefCell<List<T>>,
+    /// Where the node sits in its parent's child list, or in the root list.
+    slot: RefCell<Option<doubly_linked_list::Handle<Rc<Node<T>>>>>,
+    /// Set when the node has lost a child since it was linked.
+    marked: Cell<bool>,
+}
+
+impl<T> HasEntry for Node<T> {
+    type Value = T;
+
+    fn entry(&self) -> &Entry<T> {
+        &self.entry
+    }
+}
+
+/// A handle to an element of a `FibonacciHeap`.
+pub type Handle<T> = handle::Handle<Node<T>>;
+
+/// A lazily consolidated forest of heap-ordered trees.
+///
+/// `insert`, `meld` and `decrease_key` take O(1) amortized: they only add
+/// trees to the root list, or cut them off with cascading cuts. `pop_min`
+/// links trees of equal degree and takes O(log n) amortized. Checking that
+/// a handle belongs to the heap adds an inverse-Ackermann factor, which is
+/// at most 4 for any heap that fits in memory.
+pub struct FibonacciHeap<T, C = Natural> {
+    roots: List<T>,
+    min: Option<Rc<Node<T>>>,
+    len: usize,
+    owner: Rc<Owner>,
+    cmp: C,
+}
+
+impl<T: Ord> FibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, C> FibonacciHeap<T, C> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Takes the trees apart node by node, since dropping them recursively
+    /// could overflow the stack on a tall tree.
+    pub fn clear(&mut self) {
+        self.min = None;
+        let mut stack = Vec::new();
+        while let Some(node) = self.roots.pop_front() {
+            stack.push(node);
+        }
+        while let Some(node) = stack.pop() {
+            let mut children = node.children.borrow_mut();
+            while let Some(child) = children.pop_front() {
+                stack.push(child);
+            }
+        }
+        self.len = 0;
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.min.as_deref().map(Self::value)
+    }
+
+    fn value(node: &Node<T>) -> &T {
+        node.entry.value()
+    }
+}
+
+impl<T, C: Compare<T>> FibonacciHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            roots: DoublyLinkedList::new(),
+            min: None,
+            len: 0,
+            owner: Rc::default(),
+            cmp,
+        }
+    }
+
+    pub fn insert(&mut self, x: T) -> Handle<T> {
+        let node = Rc::new(Node {
+            entry: Entry::new(x, &self.owner),
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(DoublyLinkedList::new()),
+            slot: RefCell::new(None),
+            marked: Cell::new(false),
+        });
+        let handle = Handle::new(&node);
+        self.add_root(node);
+        self.len += 1;
+        handle
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        let min = self.min.take()?;
+        let slot = min.slot.take()?;
+        self.roots.remove(&slot);
+        let children = std::mem::take(&mut *min.children.borrow_mut());
+        for child in children {
+            *child.parent.borrow_mut() = Weak::new();
+            child.marked.set(false);
+            self.roots.push_back(child.clone());
+            *child.slot.borrow_mut() = self.roots.back_handle();
+        }
+        self.consolidate();
+        self.len -= 1;
+
+        match Rc::try_unwrap(min) {
+            Ok(node) => Some(node.entry.into_value()),
+            Err(_) => unreachable!("the heap holds the only strong reference to a node"),
+        }
+    }
+
+    /// Moves every element of `other` into `self` in O(1), by appending its
+    /// root list.
+    pub fn meld(&mut self, mut other: Self) {
+        Owner::adopt(&mut self.owner, &other.owner);
+        self.len += std::mem::take(&mut other.len);
+        self.roots.append(&mut other.roots);
+        if let Some(min) = other.min.take() {
+            self.update_min(min);
+        }
+    }
+
+    /// See `MeldableHeap::decrease_key`. If the element moves above its
+    /// parent, it is cut off into the root list, and so is every marked
+    /// ancestor above it.
+    pub fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        let node = handle.resolve(&self.owner)?;
+        assert!(
+            self.cmp.compare(&x, Self::value(&node)).is_le(),
+            "decrease_key would increase the element"
+        );
+        // `&mut self` rules out any reference to the old value.
+        let old = unsafe { node.entry.replace(x) };
+
+        let parent = node.parent.borrow().upgrade();
+        if let Some(parent) = parent.filter(|parent| self.less(&node, parent)) {
+            self.cut(&node, &parent);
+            self.cascading_cut(parent);
+        }
+        self.update_min(node);
+        Some(old)
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        handle.get(&self.owner)
+    }
+
+    fn less(&self, a: &Node<T>, b: &Node<T>) -> bool {
+        self.cmp.compare(Self::value(a), Self::value(b)).is_lt()
+    }
+
+    fn update_min(&mut self, node: Rc<Node<T>>) {
+        if self.min.as_ref().is_none_or(|min| self.less(&node, min)) {
+            self.min = Some(node);
+        }
+    }
+
+    fn add_root(&mut self, node: Rc<Node<T>>) {
+        self.roots.push_back(node.clone());
+        *node.slot.borrow_mut() = self.roots.back_handle();
+        self.update_min(node);
+    }
+
+    /// Makes the root with the larger element a child of the other.
+    fn link(&self, a: Rc<Node<T>>, b: Rc<Node<T>>) -> Rc<Node<T>> {
+        let (parent, child) = if self.less(&b, &a) { (b, a) } else { (a, b) };
+        *child.parent.borrow_mut() = Rc::downgrade(&parent);
+        child.marked.set(false);
+        let mut children = parent.children.borrow_mut();
+        children.push_back(child.clone());
+        *child.slot.borrow_mut() = children.back_handle();
+        drop(children);
+        parent
+    }
+
+    /// Links roots of equal degree until all degrees differ.
+    fn consolidate(&mut self) {
+        let mut by_degree: Vec<Option<Rc<Node<T>>>> = Vec::new();
+        while let Some(mut tree) = self.roots.pop_front() {
+            loop {
+                let degree = tree.children.borrow().len();
+                if degree >= by_degree.len() {
+                    by_degree.resize_with(degree + 1, || None);
+                }
+                match by_degree[degree].take() {
+                    Some(other) => tree = self.link(tree, other),
+                    None => {
+                        by_degree[degree] = Some(tree);
+                        break;
+                    }
+                }
+            }
+        }
+        for tree in by_degree.into_iter().flatten() {
+            self.add_root(tree);
+        }
+    }
+
+    /// Moves `node` from the child list of `parent` to the root list.
+    fn cut(&mut self, node: &Rc<Node<T>>, parent: &Node<T>) {
+        if let Some(slot) = node.slot.take() {
+            parent.children.borrow_mut().remove(&slot);
+        }
+        *node.parent.borrow_mut() = Weak::new();
+        node.marked.set(false);
+        self.roots.push_back(node.clone());
+        *node.slot.borrow_mut() = self.roots.back_handle();
+    }
+
+    /// Marks `node` after it lost a child, or cuts it too if it already had
+    /// lost one, and so on up the tree.
+    fn cascading_cut(&mut self, mut node: Rc<Node<T>>) {
+        loop {
+            let parent = node.parent.borrow().upgrade();
+            let Some(parent) = parent else { break };
+            if !node.marked.replace(true) {
+                break;
+            }
+            self.cut(&node, &parent);
+            node = parent;
+        }
+    }
+}
+
+impl<T, C> Drop for FibonacciHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for FibonacciHeap<T, C> {
+    fn push(&mut self, x: T) {
+        self.insert(x);
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for FibonacciHeap<T, C> {
+    type Handle = Handle<T>;
+
+    fn insert(&mut self, x: T) -> Handle<T> {
+        self.insert(x)
+    }
+
+    fn meld(&mut self, other: Self) {
+        self.meld(other)
+    }
+
+    fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        self.decrease_key(handle, x)
+    }
+
+    fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.get(handle)
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for FibonacciHeap<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, C> Debug for FibonacciHeap<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FibonacciHeap")
+            .field("len", &self.len)
+            .field("min", &self.peek())
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for FibonacciHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for FibonacciHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| {
+            self.insert(x);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use rand::Rng;
+
+    use super::*;
+
+    /// Checks heap order, parent and slot links, and that a node of degree
+    /// k heads at least F(k + 2) nodes. Returns the largest root degree.
+    fn check_forest<T, C: Compare<T>>(heap: &FibonacciHeap<T, C>) -> usize {
+        fn size<T, C: Compare<T>>(heap: &FibonacciHeap<T, C>, node: &Rc<Node<T>>) -> usize {
+            let (mut a, mut b) = (1, 2);
+            for _ in 0..node.children.borrow().len() {
+                (a, b) = (b, a + b);
+            }
+            let mut total = 1;
+            for child in node.children.borrow().iter() {
+                assert!(!heap.less(child, node));
+                assert!(Rc::ptr_eq(&child.parent.borrow().upgrade().unwrap(), node));
+                let slot = child.slot.borrow().clone().unwrap();
+                assert!(node.children.borrow().contains(&slot));
+                total += size(heap, child);
+            }
+            assert!(total >= a);
+            total
+        }
+
+        let mut total = 0;
+        let mut max_degree = 0;
+        for root in heap.roots.iter() {
+            assert!(root.parent.borrow().upgrade().is_none());
+            assert!(heap.roots.contains(&root.slot.borrow().clone().unwrap()));
+            assert!(!heap.less(root, heap.min.as_ref().unwrap()));
+            max_degree = max_degree.max(root.children.borrow().len());
+            total += size(heap, root);
+        }
+        assert!(total == heap.len());
+        max_degree
+    }
+
+    #[test]
+    fn test_amortized_comparisons() {
+        let n: usize = 1 << 12;
+        let log_n = n.ilog2() as usize;
+        let count = Cell::new(0);
+        let cmp = |a: &u64, b: &u64| {
+            count.set(count.get() + 1);
+            a.cmp(b)
+        };
+        let mut rng = rand::thread_rng();
+
+        // Each insert compares against the minimum once and nothing else.
+        let mut heap = FibonacciHeap::with_comparator(cmp);
+        let handles = (0..n)
+            .map(|_| heap.insert(rng.gen_range(n as u64..2 * n as u64)))
+            .collect::<Vec<_>>();
+        assert!(count.get() == n - 1);
+
+        // The first pop links everything into O(log n) trees.
+        heap.pop_min();
+        assert!(check_forest(&heap) <= 2 * log_n);
+
+        // decrease_key compares against the old element, the parent and the
+        // minimum, however many cascading cuts it makes.
+        count.set(0);
+        for handle in &handles {
+            if let Some(&x) = heap.get(handle) {
+                heap.decrease_key(handle, x - rng.gen_range(0..n as u64));
+            }
+        }
+        assert!(count.get() <= 3 * n);
+        check_forest(&heap);
+
+        // The remaining pops cost O(log n) amortized, and degrees stay within
+        // log_phi(n) < 1.45 log2(n).
+        count.set(0);
+        let mut last = 0;
+        while let Some(x) = heap.pop_min() {
+            assert!(x >= last);
+            last = x;
+            if heap.len() % 256 == 0 && !heap.is_empty() {
+                assert!(check_forest(&heap) * 2 <= 3 * log_n);
+            }
+        }
+        assert!(count.get() <= 4 * n * log_n);
+    }
+
+    #[test]
+    fn test_cascading_cuts() {
+        let mut heap = FibonacciHeap::new();
+        let handles = (0..64).map(|i| heap.insert(i + 100)).collect::<Vec<_>>();
+        heap.pop_min();
+        assert!(check_forest(&heap) == 5);
+
+        // Repeatedly cutting from the same trees marks and then cuts their
+        // ancestors; the structure stays valid throughout.
+        for (i, handle) in handles.iter().enumerate().skip(1).rev() {
+            assert!(heap.decrease_key(handle, i as i32) == Some(i as i32 + 100));
+            check_forest(&heap);
+            assert!(heap.peek() == Some(&(i as i32)));
+        }
+        assert!(heap.into_sorted_vec() == (1..64).collect::<Vec<_>>());
+    }
+}
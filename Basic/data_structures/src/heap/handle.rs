@@ -0,0 +1,97 @@
+//! Handles to the elements of the node-based meldable heaps.
+//!
+//! Each element sits in an `Entry` inside a node behind an `Rc`. Only the
+//! heap holds nodes strongly, and a `Handle` holds its node weakly, so it
+//! never keeps an element alive. The entry also carries the `Owner` tag of
+//! the heap it was inserted into, so a handle only resolves in the heap that
+//! holds its element now, after any number of melds.
+//!
+//! The value itself is kept in an `UnsafeCell`, so that a heap can lend out
+//! `&T` from `&self` without a `RefCell` guard. This is sound because:
+//!
+//! - a heap only writes a value, in `decrease_key`, or moves it out, when
+//!   popping, through `&mut self`, so no reference it lent out is alive then;
+//! - a reference lent out through `&self` lives no longer than that borrow,
+//!   during which the heap keeps the node alive.
+
+use std::{
+    cell::{RefCell, UnsafeCell},
+    rc::{Rc, Weak},
+};
+
+use crate::owner::Owner;
+
+/// An element of a heap, and the tag of the heap holding it.
+pub(crate) struct Entry<T> {
+    value: UnsafeCell<T>,
+    owner: RefCell<Rc<Owner>>,
+}
+
+impl<T> Entry<T> {
+    pub(crate) fn new(value: T, owner: &Rc<Owner>) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            owner: RefCell::new(owner.clone()),
+        }
+    }
+
+    pub(crate) fn value(&self) -> &T {
+        unsafe { &*self.value.get() }
+    }
+
+    /// Puts `x` in place of the value and returns the old one.
+    ///
+    /// # Safety
+    ///
+    /// The heap holding the entry must be borrowed mutably, and no reference
+    /// returned by `value` may be alive.
+    pub(crate) unsafe fn replace(&self, x: T) -> T {
+        std::mem::replace(unsafe { &mut *self.value.get() }, x)
+    }
+
+    pub(crate) fn into_value(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+/// A heap node that a `Handle` can point at.
+pub(crate) trait HasEntry {
+    type Value;
+
+    fn entry(&self) -> &Entry<Self::Value>;
+}
+
+/// A handle to an element of a heap whose nodes are of type `N`. Each heap
+/// names its own as `Handle<T>`.
+pub struct Handle<N>(Weak<N>);
+
+impl<N> Clone for Handle<N> {
+    fn clone(&self) -> Self {
+        Handle(self.0.clone())
+    }
+}
+
+impl<N> Handle<N> {
+    pub(crate) fn new(node: &Rc<N>) -> Self {
+        Handle(Rc::downgrade(node))
+    }
+
+    /// The node, if it is still in the heap owning the root `owner`.
+    pub(crate) fn resolve(&self, owner: &Rc<Owner>) -> Option<Rc<N>>
+    where
+        N: HasEntry,
+    {
+        let node = self.0.upgrade()?;
+        Owner::owns(owner, &node.entry().owner).then_some(node)
+    }
+
+    /// The value, if it is still in the heap owning the root `owner`. The
+    /// heap keeps the node alive for as long as `owner` is borrowed.
+    pub(crate) fn get<'a>(&self, owner: &'a Rc<Owner>) -> Option<&'a N::Value>
+    where
+        N: HasEntry,
+    {
+        let node = self.resolve(owner)?;
+        Some(unsafe { &*node.entry().value.get() })
+    }
+}
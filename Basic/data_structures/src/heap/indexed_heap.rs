@@ -0,0 +1,489 @@
+use std::{borrow::Borrow, collections::HashMap, fmt::Debug, hash::Hash};
+
+use super::{Compare, Natural};
+
+/// A binary min-heap of priorities addressed by `usize` handles chosen by the
+/// caller, such as vertex numbers.
+///
+/// Each handle remembers where its priority sits in the heap, so a priority
+/// can be changed or removed in O(log n) without searching for it. Storage
+/// grows with the largest handle used, so handles should be dense.
+#[derive(Clone)]
+pub struct IndexedHeap<P, C = Natural> {
+    heap: Vec<usize>,
+    /// For each handle, its position in `heap` and its priority.
+    slots: Vec<Option<(usize, P)>>,
+    cmp: C,
+}
+
+impl<P: Ord> IndexedHeap<P> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<P, C: Compare<P>> IndexedHeap<P, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            heap: Vec::new(),
+            slots: Vec::new(),
+            cmp,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.slots.clear();
+    }
+
+    pub fn contains(&self, handle: usize) -> bool {
+        self.priority(handle).is_some()
+    }
+
+    pub fn priority(&self, handle: usize) -> Option<&P> {
+        self.slots.get(handle)?.as_ref().map(|(_, p)| p)
+    }
+
+    /// Inserts `handle` with `priority`. If the handle is already present its
+    /// priority is replaced, moving it either way, and the old one returned.
+    pub fn insert(&mut self, handle: usize, priority: P) -> Option<P> {
+        if self.contains(handle) {
+            return self.set_priority(handle, priority);
+        }
+        if handle >= self.slots.len() {
+            self.slots.resize_with(handle + 1, || None);
+        }
+        self.slots[handle] = Some((self.heap.len(), priority));
+        self.heap.push(handle);
+        self.sift_up(self.heap.len() - 1);
+        None
+    }
+
+    /// Lowers the priority of `handle` and returns the old one, or `None` if
+    /// the handle is not in the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priority` is greater than the current priority.
+    pub fn decrease_key(&mut self, handle: usize, priority: P) -> Option<P> {
+        let current = self.priority(handle)?;
+        assert!(
+            self.cmp.compare(&priority, current).is_le(),
+            "decrease_key would increase the priority"
+        );
+        self.set_priority(handle, priority)
+    }
+
+    /// Raises the priority of `handle` and returns the old one, or `None` if
+    /// the handle is not in the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priority` is less than the current priority.
+    pub fn increase_key(&mut self, handle: usize, priority: P) -> Option<P> {
+        let current = self.priority(handle)?;
+        assert!(
+            self.cmp.compare(&priority, current).is_ge(),
+            "increase_key would decrease the priority"
+        );
+        self.set_priority(handle, priority)
+    }
+
+    pub fn remove(&mut self, handle: usize) -> Option<P> {
+        let (pos, _) = self.slots.get(handle)?.as_ref()?;
+        let pos = *pos;
+        let last = self.heap.len() - 1;
+        self.swap(pos, last);
+        self.heap.pop();
+        let (_, priority) = self.slots[handle].take()?;
+        if pos < last {
+            let pos = self.sift_up(pos);
+            self.sift_down(pos);
+        }
+        Some(priority)
+    }
+
+    pub fn peek(&self) -> Option<(usize, &P)> {
+        let handle = *self.heap.first()?;
+        self.priority(handle).map(|p| (handle, p))
+    }
+
+    pub fn pop_min(&mut self) -> Option<(usize, P)> {
+        let handle = *self.heap.first()?;
+        self.remove(handle).map(|p| (handle, p))
+    }
+
+    /// Iterates over the handles and their priorities in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &P)> {
+        self.heap.iter().map(|&handle| {
+            let (_, p) = self.slots[handle].as_ref().unwrap();
+            (handle, p)
+        })
+    }
+
+    fn set_priority(&mut self, handle: usize, priority: P) -> Option<P> {
+        let (pos, old) = self.slots.get_mut(handle)?.as_mut()?;
+        let pos = *pos;
+        let old = std::mem::replace(old, priority);
+        let pos = self.sift_up(pos);
+        self.sift_down(pos);
+        Some(old)
+    }
+
+    fn priority_at(&self, pos: usize) -> &P {
+        &self.slots[self.heap[pos]].as_ref().unwrap().1
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        self.cmp
+            .compare(self.priority_at(i), self.priority_at(j))
+            .is_lt()
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        for pos in [i, j] {
+            if let Some((slot_pos, _)) = &mut self.slots[self.heap[pos]] {
+                *slot_pos = pos;
+            }
+        }
+    }
+
+    /// Returns the position the element ends up at.
+    fn sift_up(&mut self, mut i: usize) -> usize {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.less(i, parent) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+        i
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let left = 2 * i + 1;
+            if left >= self.heap.len() {
+                break;
+            }
+            let right = left + 1;
+            let child = if right < self.heap.len() && self.less(right, left) {
+                right
+            } else {
+                left
+            };
+            if !self.less(child, i) {
+                break;
+            }
+            self.swap(i, child);
+            i = child;
+        }
+    }
+}
+
+impl<P: Ord> Default for IndexedHeap<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Debug, C: Compare<P>> Debug for IndexedHeap<P, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An `IndexedHeap` addressed by arbitrary keys, which a `HashMap`
+/// translates into handles. Handles of removed keys are reused.
+#[derive(Clone)]
+pub struct KeyedHeap<K, P, C = Natural> {
+    heap: IndexedHeap<P, C>,
+    handles: HashMap<K, usize>,
+    keys: Vec<Option<K>>,
+    free: Vec<usize>,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> KeyedHeap<K, P> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<K: Hash + Eq + Clone, P, C: Compare<P>> KeyedHeap<K, P, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            heap: IndexedHeap::with_comparator(cmp),
+            handles: HashMap::new(),
+            keys: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.handles.clear();
+        self.keys.clear();
+        self.free.clear();
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.handles.contains_key(key)
+    }
+
+    pub fn priority<Q>(&self, key: &Q) -> Option<&P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.heap.priority(*self.handles.get(key)?)
+    }
+
+    /// Inserts `key` with `priority`, replacing and returning the priority
+    /// of an existing entry.
+    pub fn insert(&mut self, key: K, priority: P) -> Option<P> {
+        if let Some(&handle) = self.handles.get(&key) {
+            return self.heap.insert(handle, priority);
+        }
+        let handle = self.free.pop().unwrap_or(self.keys.len());
+        if handle == self.keys.len() {
+            self.keys.push(None);
+        }
+        self.keys[handle] = Some(key.clone());
+        self.handles.insert(key, handle);
+        self.heap.insert(handle, priority)
+    }
+
+    /// See `IndexedHeap::decrease_key`.
+    pub fn decrease_key<Q>(&mut self, key: &Q, priority: P) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.heap.decrease_key(*self.handles.get(key)?, priority)
+    }
+
+    /// See `IndexedHeap::increase_key`.
+    pub fn increase_key<Q>(&mut self, key: &Q, priority: P) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.heap.increase_key(*self.handles.get(key)?, priority)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.handles.remove(key)?;
+        self.release(handle);
+        self.heap.remove(handle)
+    }
+
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        let (handle, priority) = self.heap.peek()?;
+        Some((self.keys[handle].as_ref()?, priority))
+    }
+
+    pub fn pop_min(&mut self) -> Option<(K, P)> {
+        let (handle, priority) = self.heap.pop_min()?;
+        let key = self.release(handle)?;
+        self.handles.remove(&key);
+        Some((key, priority))
+    }
+
+    /// Iterates over the keys and their priorities in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.heap
+            .iter()
+            .map(|(handle, p)| (self.keys[handle].as_ref().unwrap(), p))
+    }
+
+    fn release(&mut self, handle: usize) -> Option<K> {
+        self.free.push(handle);
+        self.keys[handle].take()
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Default for KeyedHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone + Debug, P: Debug, C: Compare<P>> Debug for KeyedHeap<K, P, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::heap::Reversed;
+
+    fn check_positions<P, C: Compare<P>>(heap: &IndexedHeap<P, C>) {
+        for (pos, &handle) in heap.heap.iter().enumerate() {
+            assert!(heap.slots[handle].as_ref().unwrap().0 == pos);
+            if pos > 0 {
+                assert!(!heap.less(pos, (pos - 1) / 2));
+            }
+        }
+        assert!(heap.slots.iter().flatten().count() == heap.len());
+    }
+
+    #[test]
+    fn test_random_ops_against_btree_set() {
+        let mut rng = rand::thread_rng();
+        let mut heap = IndexedHeap::new();
+        // (priority, handle) pairs, so the first is what `pop_min` returns up
+        // to ties.
+        let mut model = BTreeSet::new();
+        let mut priorities = [None; 64];
+        for _ in 0..5000 {
+            let handle = rng.gen_range(0..64);
+            let priority = rng.gen_range(0..1000);
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    assert!(heap.insert(handle, priority) == priorities[handle]);
+                    if let Some(old) = priorities[handle].replace(priority) {
+                        model.remove(&(old, handle));
+                    }
+                    model.insert((priority, handle));
+                }
+                2 => {
+                    assert!(heap.remove(handle) == priorities[handle].take());
+                    model.retain(|&(_, h)| h != handle);
+                }
+                3 => {
+                    let Some(old) = priorities[handle] else {
+                        assert!(heap.decrease_key(handle, priority).is_none());
+                        continue;
+                    };
+                    let new = old.min(priority);
+                    assert!(heap.decrease_key(handle, new) == Some(old));
+                    model.remove(&(old, handle));
+                    model.insert((new, handle));
+                    priorities[handle] = Some(new);
+                }
+                _ => {
+                    let popped = heap.pop_min();
+                    let min = model.first().map(|&(p, _)| p);
+                    assert!(popped.map(|(_, p)| p) == min);
+                    if let Some((handle, _)) = popped {
+                        model.retain(|&(_, h)| h != handle);
+                        priorities[handle] = None;
+                    }
+                }
+            }
+            check_positions(&heap);
+            assert!(heap.len() == model.len());
+            for (handle, priority) in priorities.iter().enumerate() {
+                assert!(heap.priority(handle) == priority.as_ref());
+            }
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let edges = [(0, 1, 7), (0, 2, 9), (0, 5, 14), (1, 2, 10), (1, 3, 15)]
+            .into_iter()
+            .chain([(2, 3, 11), (2, 5, 2), (3, 4, 6), (4, 5, 9)])
+            .flat_map(|(a, b, w)| [(a, b, w), (b, a, w)])
+            .collect::<Vec<_>>();
+
+        let mut dist = [u32::MAX; 6];
+        let mut heap = IndexedHeap::new();
+        heap.insert(0, 0);
+        while let Some((u, d)) = heap.pop_min() {
+            dist[u] = d;
+            for &(_, v, w) in edges.iter().filter(|&&(a, _, _)| a == u) {
+                if dist[v] != u32::MAX {
+                    continue;
+                }
+                match heap.priority(v) {
+                    Some(&old) if old <= d + w => {}
+                    Some(_) => {
+                        heap.decrease_key(v, d + w);
+                    }
+                    None => {
+                        heap.insert(v, d + w);
+                    }
+                }
+            }
+        }
+        assert!(dist == [0, 7, 9, 20, 20, 11]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decrease_key_in_wrong_direction() {
+        let mut heap = IndexedHeap::new();
+        heap.insert(3, 10);
+        heap.decrease_key(3, 11);
+    }
+
+    #[test]
+    fn test_increase_key_with_comparator() {
+        let mut heap = IndexedHeap::with_comparator(Reversed);
+        heap.insert(0, 5);
+        heap.insert(1, 3);
+        assert!(heap.peek() == Some((0, &5)));
+        // Under `Reversed`, increasing the key means a smaller number.
+        assert!(heap.increase_key(0, 1) == Some(5));
+        assert!(heap.pop_min() == Some((1, 3)));
+        assert!(heap.pop_min() == Some((0, 1)) && heap.is_empty());
+    }
+
+    #[test]
+    fn test_keyed_heap() {
+        let mut heap = KeyedHeap::new();
+        for (key, priority) in [("c", 3), ("a", 1), ("d", 4), ("b", 2)] {
+            assert!(heap.insert(key.to_string(), priority).is_none());
+        }
+        assert!(heap.contains("a") && !heap.contains("e"));
+        assert!(heap.peek() == Some((&"a".to_string(), &1)));
+
+        assert!(heap.decrease_key("d", 0) == Some(4));
+        assert!(heap.increase_key("a", 5) == Some(1));
+        assert!(heap.remove("b") == Some(2) && heap.remove("b").is_none());
+        assert!(heap.insert("e".to_string(), 2).is_none());
+        assert!(heap.insert("e".to_string(), 4) == Some(2));
+
+        let mut order = Vec::new();
+        while let Some((key, priority)) = heap.pop_min() {
+            order.push((key, priority));
+        }
+        assert!(order == [("d", 0), ("c", 3), ("e", 4), ("a", 5)].map(|(k, p)| (k.to_string(), p)));
+        assert!(heap.is_empty() && !heap.contains("d"));
+        // Every key was released, so the handles are all free for reuse.
+        assert!(heap.free.len() == heap.keys.len());
+    }
+}
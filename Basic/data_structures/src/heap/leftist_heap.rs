@@ -0,0 +1,450 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    marker::PhantomData,
+    rc::{Rc, Weak},
+};
+
+use super::{
+    handle::{self, Entry, HasEntry},
+    Compare, MeldableHeap, Natural, PriorityQueue,
+};
+use crate::owner::Owner;
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+/// A node of a `SpineHeap`.
+pub struct Node<T> {
+    entry: Entry<T>,
+    left: RefCell<Link<T>>,
+    right: RefCell<Link<T>>,
+    parent: RefCell<Weak<Node<T>>>,
+    /// Length of the shortest path down to a missing child; unused by skew
+    /// heaps.
+    rank: Cell<usize>,
+}
+
+impl<T> HasEntry for Node<T> {
+    type Value = T;
+
+    fn entry(&self) -> &Entry<T> {
+        &self.entry
+    }
+}
+
+fn rank<T>(link: &RefCell<Link<T>>) -> usize {
+    link.borrow().as_ref().map_or(0, |node| node.rank.get())
+}
+
+/// A handle to an element of a `SpineHeap`.
+pub type Handle<T> = handle::Handle<Node<T>>;
+
+/// How a `SpineHeap` keeps its right spines short.
+pub trait Balance {
+    /// The name `Debug` prints for the heap.
+    const NAME: &'static str;
+    /// Whether nodes keep a rank, which bounds right spines in the worst case
+    /// rather than amortized.
+    const RANKED: bool;
+}
+
+/// Keeps the rank of a left child at least that of the right child, so
+/// right spines are O(log n) long and `meld` is O(log n) in the worst case.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Leftist;
+
+impl Balance for Leftist {
+    const NAME: &'static str = "LeftistHeap";
+    const RANKED: bool = true;
+}
+
+/// Skips the ranks and swaps the children of every node on the meld path
+/// instead, for O(log n) amortized.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Skew;
+
+impl Balance for Skew {
+    const NAME: &'static str = "SkewHeap";
+    const RANKED: bool = false;
+}
+
+/// A leftist heap, i.e. a spine heap that keeps ranks.
+pub type LeftistHeap<T, C = Natural> = SpineHeap<T, Leftist, C>;
+/// A skew heap, i.e. a spine heap that swaps children instead.
+pub type SkewHeap<T, C = Natural> = SpineHeap<T, Skew, C>;
+
+/// A heap-ordered binary tree whose every operation is a meld of two right
+/// spines, kept short as `B` decides. Only heaps of the same `B` meld.
+pub struct SpineHeap<T, B: Balance, C = Natural> {
+    root: Link<T>,
+    len: usize,
+    owner: Rc<Owner>,
+    cmp: C,
+    balance: PhantomData<B>,
+}
+
+impl<T: Ord, B: Balance> SpineHeap<T, B> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, B: Balance, C> SpineHeap<T, B, C> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Takes the tree apart node by node, since dropping it recursively
+    /// could overflow the stack on a long path.
+    pub fn clear(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+        self.len = 0;
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_deref().map(Self::value)
+    }
+
+    fn value(node: &Node<T>) -> &T {
+        node.entry.value()
+    }
+
+    fn into_value(node: Rc<Node<T>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(node) => node.entry.into_value(),
+            Err(_) => unreachable!("the heap holds the only strong reference to a node"),
+        }
+    }
+
+    /// Restores the shape invariant of `node` after its right child changed.
+    fn fix(&self, node: &Node<T>) {
+        if B::RANKED {
+            if rank(&node.left) < rank(&node.right) {
+                node.left.swap(&node.right);
+            }
+            node.rank.set(rank(&node.right) + 1);
+        } else {
+            node.left.swap(&node.right);
+        }
+    }
+
+    /// Cuts `node` and its subtree out of the tree, then repairs the ranks
+    /// on the path above it.
+    fn detach(&self, node: &Rc<Node<T>>) {
+        let Some(parent) = node.parent.replace(Weak::new()).upgrade() else {
+            return;
+        };
+        for side in [&parent.left, &parent.right] {
+            if side
+                .borrow()
+                .as_ref()
+                .is_some_and(|child| Rc::ptr_eq(child, node))
+            {
+                side.take();
+            }
+        }
+        if !B::RANKED {
+            return;
+        }
+        let mut current = Some(parent);
+        while let Some(node) = current {
+            let old_rank = node.rank.get();
+            self.fix(&node);
+            if node.rank.get() == old_rank {
+                break;
+            }
+            current = node.parent.borrow().upgrade();
+        }
+    }
+}
+
+impl<T, B: Balance, C: Compare<T>> SpineHeap<T, B, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            owner: Rc::default(),
+            cmp,
+            balance: PhantomData,
+        }
+    }
+
+    pub fn insert(&mut self, x: T) -> Handle<T> {
+        let node = Rc::new(Node {
+            entry: Entry::new(x, &self.owner),
+            left: RefCell::new(None),
+            right: RefCell::new(None),
+            parent: RefCell::new(Weak::new()),
+            rank: Cell::new(1),
+        });
+        let handle = Handle::new(&node);
+        let root = self.root.take();
+        self.root = self.merge(root, Some(node));
+        self.len += 1;
+        handle
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        self.root = self.merge(root.left.take(), root.right.take());
+        self.len -= 1;
+        Some(Self::into_value(root))
+    }
+
+    /// Moves every element of `other` into `self`.
+    pub fn meld(&mut self, mut other: Self) {
+        Owner::adopt(&mut self.owner, &other.owner);
+        self.len += std::mem::take(&mut other.len);
+        let root = self.root.take();
+        self.root = self.merge(root, other.root.take());
+    }
+
+    /// See `MeldableHeap::decrease_key`. If the element moves above its
+    /// parent, its subtree is cut and melded back into the root.
+    pub fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        let node = handle.resolve(&self.owner)?;
+        assert!(
+            self.cmp.compare(&x, Self::value(&node)).is_le(),
+            "decrease_key would increase the element"
+        );
+        // `&mut self` rules out any reference to the old value.
+        let old = unsafe { node.entry.replace(x) };
+        let parent = node.parent.borrow().upgrade();
+        if parent.is_some_and(|parent| self.less(&node, &parent)) {
+            self.detach(&node);
+            let root = self.root.take();
+            self.root = self.merge(root, Some(node));
+        }
+        Some(old)
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        handle.get(&self.owner)
+    }
+
+    fn less(&self, a: &Node<T>, b: &Node<T>) -> bool {
+        self.cmp.compare(Self::value(a), Self::value(b)).is_lt()
+    }
+
+    /// Merges the right spines of two trees top-down, then rebuilds the
+    /// path bottom-up. Iterative, because a skew heap's right spine can be
+    /// long.
+    fn merge(&self, mut a: Link<T>, mut b: Link<T>) -> Link<T> {
+        let mut path = Vec::new();
+        while let (Some(x), Some(y)) = (&a, &b) {
+            if self.less(y, x) {
+                std::mem::swap(&mut a, &mut b);
+            }
+            let top = a.take().unwrap();
+            a = top.right.take();
+            path.push(top);
+        }
+
+        let mut merged = a.or(b);
+        while let Some(node) = path.pop() {
+            if let Some(child) = &merged {
+                *child.parent.borrow_mut() = Rc::downgrade(&node);
+            }
+            *node.right.borrow_mut() = merged;
+            self.fix(&node);
+            merged = Some(node);
+        }
+        if let Some(root) = &merged {
+            *root.parent.borrow_mut() = Weak::new();
+        }
+        merged
+    }
+}
+
+impl<T, B: Balance, C> Drop for SpineHeap<T, B, C> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T, B: Balance, C: Compare<T>> PriorityQueue<T> for SpineHeap<T, B, C> {
+    fn push(&mut self, x: T) {
+        self.insert(x);
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, B: Balance, C: Compare<T>> MeldableHeap<T> for SpineHeap<T, B, C> {
+    type Handle = Handle<T>;
+
+    fn insert(&mut self, x: T) -> Handle<T> {
+        self.insert(x)
+    }
+
+    fn meld(&mut self, other: Self) {
+        self.meld(other)
+    }
+
+    fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        self.decrease_key(handle, x)
+    }
+
+    fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.get(handle)
+    }
+}
+
+impl<T, B: Balance, C: Compare<T> + Default> Default for SpineHeap<T, B, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, B: Balance, C> Debug for SpineHeap<T, B, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(B::NAME)
+            .field("len", &self.len)
+            .field("min", &self.peek())
+            .finish()
+    }
+}
+
+impl<T, B: Balance, C: Compare<T> + Default> FromIterator<T> for SpineHeap<T, B, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, B: Balance, C: Compare<T>> Extend<T> for SpineHeap<T, B, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| {
+            self.insert(x);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use rand::Rng;
+
+    use super::*;
+
+    /// Checks heap order, parent links and, for leftist heaps, the ranks.
+    /// Returns the node count.
+    fn check_tree<T, B: Balance, C: Compare<T>>(heap: &SpineHeap<T, B, C>) -> usize {
+        let mut count = 0;
+        let mut stack: Vec<_> = heap.root.iter().cloned().collect();
+        while let Some(node) = stack.pop() {
+            count += 1;
+            if B::RANKED {
+                assert!(rank(&node.left) >= rank(&node.right));
+                assert!(node.rank.get() == rank(&node.right) + 1);
+            }
+            for child in [&node.left, &node.right] {
+                if let Some(child) = child.borrow().clone() {
+                    assert!(!heap.less(&child, &node));
+                    assert!(Rc::ptr_eq(&child.parent.borrow().upgrade().unwrap(), &node));
+                    stack.push(child);
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_leftist_meld_is_logarithmic() {
+        let mut rng = rand::thread_rng();
+        let count = Cell::new(0);
+        let cmp = |a: &u32, b: &u32| {
+            count.set(count.get() + 1);
+            a.cmp(b)
+        };
+        for n in [10, 100, 1000, 10000] {
+            let mut a = LeftistHeap::with_comparator(cmp);
+            let mut b = LeftistHeap::with_comparator(cmp);
+            for _ in 0..n {
+                a.insert(rng.gen());
+                b.insert(rng.gen());
+            }
+            let bound = 2 * (n + 1usize).ilog2() as usize + 2;
+            assert!(rank(&RefCell::new(a.root.clone())) <= bound / 2);
+
+            // A meld walks both right spines, each at most log2(n + 1) long.
+            count.set(0);
+            a.meld(b);
+            assert!(count.get() <= bound);
+            assert!(check_tree(&a) == 2 * n);
+
+            count.set(0);
+            a.pop_min();
+            assert!(count.get() <= bound);
+        }
+    }
+
+    #[test]
+    fn test_skew_amortized_comparisons() {
+        let n: usize = 1 << 12;
+        let log_n = n.ilog2() as usize;
+        let count = Cell::new(0);
+        let cmp = |a: &u64, b: &u64| {
+            count.set(count.get() + 1);
+            a.cmp(b)
+        };
+
+        // Sorted input is the bad case for a skew heap's right spine.
+        let mut heap = SkewHeap::with_comparator(cmp);
+        for i in 0..n as u64 {
+            heap.insert(i);
+        }
+        while heap.pop_min().is_some() {}
+        assert!(count.get() <= 3 * 2 * n * log_n);
+    }
+
+    #[test]
+    fn test_decrease_key_repairs_ranks() {
+        let mut rng = rand::thread_rng();
+        let mut heap = LeftistHeap::new();
+        let handles = (0..500)
+            .map(|_| heap.insert(rng.gen_range(1000..2000)))
+            .collect::<Vec<_>>();
+        for handle in &handles {
+            let x = *heap.get(handle).unwrap();
+            heap.decrease_key(handle, x - rng.gen_range(0..1000));
+            assert!(check_tree(&heap) == 500);
+        }
+
+        let mut skew = SkewHeap::new();
+        let handles = (0..500).map(|i| skew.insert(i + 500)).collect::<Vec<_>>();
+        for (i, handle) in handles.iter().enumerate().rev() {
+            skew.decrease_key(handle, i);
+            assert!(check_tree(&skew) == 500);
+        }
+        assert!(skew.into_sorted_vec() == (0..500).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_drop_long_spine() {
+        let mut heap = SkewHeap::new();
+        for i in 0..1_000_000 {
+            heap.insert(i);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::fmt::Debug;
+
+use super::{Compare, DoubleEndedPriorityQueue, Natural, PriorityQueue};
+
+/// A binary heap whose even levels are min-ordered and odd levels are
+/// max-ordered, so both the smallest and the largest element can be read in
+/// O(1) and removed in O(log n).
+///
+/// The root is the minimum and the larger of its two children is the maximum.
+#[derive(Clone)]
+pub struct MinMaxHeap<T, C = Natural> {
+    data: Vec<T>,
+    cmp: C,
+}
+
+// `u32::is_multiple_of` needs Rust 1.87.
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn is_min_level(i: usize) -> bool {
+    (i + 1).ilog2() % 2 == 0
+}
+
+fn parent(i: usize) -> usize {
+    (i - 1) / 2
+}
+
+impl<T: Ord> MinMaxHeap<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, C: Compare<T>> MinMaxHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            data: Vec::new(),
+            cmp,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear()
+    }
+
+    pub fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    /// Iterates over the elements in no particular order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.data.push(x);
+        let i = self.data.len() - 1;
+        if i == 0 {
+            return;
+        }
+        let max = !is_min_level(i);
+        // An element that belongs on the other kind of level swaps with its
+        // parent first, then bubbles up among that level kind.
+        if self.before(parent(i), i, max) {
+            self.data.swap(i, parent(i));
+            self.bubble_up(parent(i), !max);
+        } else {
+            self.bubble_up(i, max);
+        }
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|i| &self.data[i])
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
+
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.remove_at(self.max_index()?)
+    }
+
+    /// Returns the elements from smallest to largest.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(x) = self.pop_min() {
+            sorted.push(x);
+        }
+        sorted
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        match self.data.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.before(1, 2, true) { 1 } else { 2 }),
+        }
+    }
+
+    fn remove_at(&mut self, i: usize) -> Option<T> {
+        if i >= self.data.len() {
+            return None;
+        }
+        let x = self.data.swap_remove(i);
+        if i < self.data.len() {
+            self.trickle_down(i);
+        }
+        Some(x)
+    }
+
+    /// Whether element `i` should sit above element `j` on a level of the
+    /// given kind.
+    fn before(&self, i: usize, j: usize, max: bool) -> bool {
+        let order = self.cmp.compare(&self.data[i], &self.data[j]);
+        if max {
+            order.is_gt()
+        } else {
+            order.is_lt()
+        }
+    }
+
+    fn bubble_up(&mut self, mut i: usize, max: bool) {
+        while i > 2 {
+            let grandparent = parent(parent(i));
+            if !self.before(i, grandparent, max) {
+                break;
+            }
+            self.data.swap(i, grandparent);
+            i = grandparent;
+        }
+    }
+
+    fn trickle_down(&mut self, mut i: usize) {
+        let max = !is_min_level(i);
+        loop {
+            let first_child = 2 * i + 1;
+            let descendants = [
+                first_child,
+                first_child + 1,
+                2 * first_child + 1,
+                2 * first_child + 2,
+                2 * first_child + 3,
+                2 * first_child + 4,
+            ];
+            let Some(m) = descendants
+                .into_iter()
+                .filter(|&j| j < self.data.len())
+                .reduce(|m, j| if self.before(j, m, max) { j } else { m })
+            else {
+                break;
+            };
+            if !self.before(m, i, max) {
+                break;
+            }
+            self.data.swap(m, i);
+            if m <= first_child + 1 {
+                // A child has no descendants of its own kind to disturb.
+                break;
+            }
+            if self.before(parent(m), m, max) {
+                self.data.swap(m, parent(m));
+            }
+            i = m;
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for MinMaxHeap<T, C> {
+    fn push(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek_min()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, C: Compare<T>> DoubleEndedPriorityQueue<T> for MinMaxHeap<T, C> {
+    fn pop_max(&mut self) -> Option<T> {
+        self.pop_max()
+    }
+
+    fn peek_max(&self) -> Option<&T> {
+        self.peek_max()
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for MinMaxHeap<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, C> Debug for MinMaxHeap<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for MinMaxHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for MinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    /// Every element is no greater (on min levels) or no smaller (on max
+    /// levels) than all of its descendants.
+    fn check_min_max_order<T, C: Compare<T>>(heap: &MinMaxHeap<T, C>) {
+        for i in 1..heap.len() {
+            let mut ancestor = i;
+            while ancestor > 0 {
+                ancestor = parent(ancestor);
+                let max = !is_min_level(ancestor);
+                assert!(!heap.before(i, ancestor, max));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pop_both_ends() {
+        let mut rng = rand::thread_rng();
+        let mut heap = MinMaxHeap::new();
+        let mut model = Vec::new();
+        for _ in 0..3000 {
+            match rng.gen_range(0..5) {
+                0 => {
+                    model.sort_unstable();
+                    assert!(heap.pop_max() == model.pop());
+                }
+                1 => {
+                    model.sort_unstable();
+                    let min = (!model.is_empty()).then(|| model.remove(0));
+                    assert!(heap.pop_min() == min);
+                }
+                _ => {
+                    let x = rng.gen_range(0..1000);
+                    heap.push(x);
+                    model.push(x);
+                }
+            }
+            check_min_max_order(&heap);
+            assert!(heap.peek_min() == model.iter().min());
+            assert!(heap.peek_max() == model.iter().max());
+        }
+    }
+
+    #[test]
+    fn test_small_heaps() {
+        let mut heap: MinMaxHeap<_> = [2, 1].into_iter().collect();
+        assert!(heap.peek_min() == Some(&1) && heap.peek_max() == Some(&2));
+        assert!(heap.pop_max() == Some(2) && heap.peek_max() == Some(&1));
+        assert!(heap.pop_max() == Some(1) && heap.is_empty());
+
+        let heap: MinMaxHeap<_> = [4, 4, 4].into_iter().collect();
+        assert!(heap.into_sorted_vec() == [4, 4, 4]);
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    rc::{Rc, Weak},
+};
+
+use super::{
+    handle::{self, Entry, HasEntry},
+    Compare, MeldableHeap, Natural, PriorityQueue,
+};
+use crate::owner::Owner;
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+/// A node of a `PairingHeap`. Children are kept in leftmost-child,
+/// right-sibling form. `prev` points to the parent of a leftmost child and
+/// to the left sibling of any other node.
+pub struct Node<T> {
+    entry: Entry<T>,
+    child: RefCell<Link<T>>,
+    sibling: RefCell<Link<T>>,
+    prev: RefCell<Weak<Node<T>>>,
+}
+
+impl<T> HasEntry for Node<T> {
+    type Value = T;
+
+    fn entry(&self) -> &Entry<T> {
+        &self.entry
+    }
+}
+
+/// A handle to an element of a `PairingHeap`.
+pub type Handle<T> = handle::Handle<Node<T>>;
+
+/// A heap-ordered multiway tree. Inserting and melding link two roots in
+/// O(1), and `pop_min` pairs up the children of the root in two passes, for
+/// O(log n) amortized.
+pub struct PairingHeap<T, C = Natural> {
+    root: Link<T>,
+    len: usize,
+    owner: Rc<Owner>,
+    cmp: C,
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, C> PairingHeap<T, C> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Takes the tree apart node by node, since dropping it recursively
+    /// could overflow the stack on a long sibling chain.
+    pub fn clear(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            stack.extend(node.child.take());
+            stack.extend(node.sibling.take());
+        }
+        self.len = 0;
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_deref().map(Self::value)
+    }
+
+    fn value(node: &Node<T>) -> &T {
+        node.entry.value()
+    }
+
+    fn into_value(node: Rc<Node<T>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(node) => node.entry.into_value(),
+            Err(_) => unreachable!("the heap holds the only strong reference to a node"),
+        }
+    }
+
+    /// Cuts `node` and its subtree out of the tree.
+    fn detach(node: &Rc<Node<T>>) {
+        let prev = node.prev.replace(Weak::new());
+        let next = node.sibling.take();
+        if let Some(next) = &next {
+            *next.prev.borrow_mut() = prev.clone();
+        }
+        if let Some(prev) = prev.upgrade() {
+            let is_leftmost = prev
+                .child
+                .borrow()
+                .as_ref()
+                .is_some_and(|child| Rc::ptr_eq(child, node));
+            if is_leftmost {
+                *prev.child.borrow_mut() = next;
+            } else {
+                *prev.sibling.borrow_mut() = next;
+            }
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PairingHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            owner: Rc::default(),
+            cmp,
+        }
+    }
+
+    pub fn insert(&mut self, x: T) -> Handle<T> {
+        let node = Rc::new(Node {
+            entry: Entry::new(x, &self.owner),
+            child: RefCell::new(None),
+            sibling: RefCell::new(None),
+            prev: RefCell::new(Weak::new()),
+        });
+        let handle = Handle::new(&node);
+        self.root = Some(match self.root.take() {
+            Some(root) => self.link(root, node),
+            None => node,
+        });
+        self.len += 1;
+        handle
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        self.root = self.combine(root.child.take());
+        self.len -= 1;
+        Some(Self::into_value(root))
+    }
+
+    /// Moves every element of `other` into `self` in O(1).
+    pub fn meld(&mut self, mut other: Self) {
+        Owner::adopt(&mut self.owner, &other.owner);
+        self.len += std::mem::take(&mut other.len);
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(self.link(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// See `MeldableHeap::decrease_key`. Cuts the element's subtree and links
+    /// it back to the root.
+    pub fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        let node = handle.resolve(&self.owner)?;
+        assert!(
+            self.cmp.compare(&x, Self::value(&node)).is_le(),
+            "decrease_key would increase the element"
+        );
+        // `&mut self` rules out any reference to the old value.
+        let old = unsafe { node.entry.replace(x) };
+        let root = self.root.take()?;
+        if Rc::ptr_eq(&root, &node) {
+            self.root = Some(root);
+        } else {
+            Self::detach(&node);
+            self.root = Some(self.link(root, node));
+        }
+        Some(old)
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        handle.get(&self.owner)
+    }
+
+    fn less(&self, a: &Node<T>, b: &Node<T>) -> bool {
+        self.cmp.compare(Self::value(a), Self::value(b)).is_lt()
+    }
+
+    /// Makes the larger of two roots the leftmost child of the other.
+    fn link(&self, a: Rc<Node<T>>, b: Rc<Node<T>>) -> Rc<Node<T>> {
+        let (parent, child) = if self.less(&b, &a) { (b, a) } else { (a, b) };
+        let first = parent.child.take();
+        if let Some(first) = &first {
+            *first.prev.borrow_mut() = Rc::downgrade(&child);
+        }
+        *child.sibling.borrow_mut() = first;
+        *child.prev.borrow_mut() = Rc::downgrade(&parent);
+        *parent.child.borrow_mut() = Some(child);
+        parent
+    }
+
+    /// Links the siblings starting at `first` in pairs from left to right,
+    /// then links the pairs into one tree from right to left.
+    fn combine(&self, first: Link<T>) -> Link<T> {
+        let mut pairs = Vec::new();
+        let mut next = first;
+        while let Some(a) = next {
+            *a.prev.borrow_mut() = Weak::new();
+            let Some(b) = a.sibling.take() else {
+                pairs.push(a);
+                break;
+            };
+            next = b.sibling.take();
+            *b.prev.borrow_mut() = Weak::new();
+            pairs.push(self.link(a, b));
+        }
+        pairs
+            .into_iter()
+            .rev()
+            .reduce(|tree, pair| self.link(pair, tree))
+    }
+}
+
+impl<T, C> Drop for PairingHeap<T, C> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for PairingHeap<T, C> {
+    fn push(&mut self, x: T) {
+        self.insert(x);
+    }
+
+    fn pop_min(&mut self) -> Option<T> {
+        self.pop_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for PairingHeap<T, C> {
+    type Handle = Handle<T>;
+
+    fn insert(&mut self, x: T) -> Handle<T> {
+        self.insert(x)
+    }
+
+    fn meld(&mut self, other: Self) {
+        self.meld(other)
+    }
+
+    fn decrease_key(&mut self, handle: &Handle<T>, x: T) -> Option<T> {
+        self.decrease_key(handle, x)
+    }
+
+    fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.get(handle)
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for PairingHeap<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, C> Debug for PairingHeap<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PairingHeap")
+            .field("len", &self.len)
+            .field("min", &self.peek())
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for PairingHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for PairingHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| {
+            self.insert(x);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// Checks heap order and the `prev` links, and returns the node count.
+    fn check_tree<T, C: Compare<T>>(heap: &PairingHeap<T, C>) -> usize {
+        let mut count = 0;
+        let mut stack: Vec<_> = heap.root.iter().cloned().collect();
+        while let Some(node) = stack.pop() {
+            count += 1;
+            let mut prev = node.clone();
+            let mut child = node.child.borrow().clone();
+            while let Some(current) = child {
+                assert!(!heap.less(&current, &node));
+                assert!(Rc::ptr_eq(&current.prev.borrow().upgrade().unwrap(), &prev));
+                child = current.sibling.borrow().clone();
+                prev = current.clone();
+                stack.push(current);
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_amortized_comparisons() {
+        let n: usize = 1 << 12;
+        let log_n = n.ilog2() as usize;
+        let count = Cell::new(0);
+        let cmp = |a: &u64, b: &u64| {
+            count.set(count.get() + 1);
+            a.cmp(b)
+        };
+
+        // Inserting links once per element.
+        let mut heap = PairingHeap::with_comparator(cmp);
+        let handles = (0..n as u64)
+            .map(|i| heap.insert(i * 7919 % n as u64 + n as u64))
+            .collect::<Vec<_>>();
+        assert!(count.get() == n - 1);
+        assert!(check_tree(&heap) == n);
+
+        // Each decrease_key links once.
+        count.set(0);
+        for (i, handle) in handles.iter().enumerate().skip(1) {
+            let x = *heap.get(handle).unwrap() - i as u64 % 5 - 1;
+            heap.decrease_key(handle, x);
+        }
+        assert!(count.get() <= 2 * n);
+
+        // `pop_min` costs O(log n) amortized.
+        count.set(0);
+        let mut last = 0;
+        while let Some(x) = heap.pop_min() {
+            assert!(x >= last);
+            last = x;
+        }
+        assert!(count.get() <= 4 * n * log_n);
+    }
+
+    #[test]
+    fn test_decrease_key_keeps_links() {
+        let mut heap = PairingHeap::new();
+        let handles = (0..100).map(|i| heap.insert(i + 100)).collect::<Vec<_>>();
+        heap.pop_min();
+        assert!(check_tree(&heap) == 99);
+        for (i, handle) in handles.iter().enumerate().skip(1).step_by(3) {
+            assert!(heap.decrease_key(handle, i as i32) == Some(i as i32 + 100));
+            assert!(check_tree(&heap) == 99);
+        }
+        assert!(heap.get(&handles[0]).is_none());
+        assert!(heap.peek() == Some(&1));
+    }
+
+    #[test]
+    fn test_drop_long_chain() {
+        // Linking in decreasing order builds a chain of leftmost children.
+        let mut heap = PairingHeap::new();
+        for i in (0..1_000_000).rev() {
+            heap.insert(i);
+        }
+        heap.pop_min();
+    }
+}
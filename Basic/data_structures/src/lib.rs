@@ -1,4 +1,11 @@
+pub mod bounded;
+pub mod cache;
+pub mod deque;
+pub mod expression;
+pub mod heap;
 pub mod linked_list;
+mod owner;
 pub mod queue;
+pub mod skip_list;
 pub mod stack;
 pub mod tree;
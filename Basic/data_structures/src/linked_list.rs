@@ -1,6 +1,22 @@
-use std::fmt::Display;
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+pub mod algorithms;
+
+pub mod arena_list;
+pub use arena_list::{ArenaDoublyLinkedList, ArenaSingleLinkedList, NodeId};
+
+pub mod doubly_linked_list;
+pub use doubly_linked_list::DoublyLinkedList;
+
+pub mod persistent_list;
+pub use persistent_list::{ArcList, PersistentList, RcList};
+
+pub mod unrolled_linked_list;
+pub use unrolled_linked_list::UnrolledLinkedList;
 
-#[derive(Debug)]
 pub struct SingleLinkedNode<T> {
     pub value: T,
     next: SingleLinkedList<T>,
@@ -15,7 +31,6 @@ impl<T> SingleLinkedNode<T> {
     }
 }
 
-#[derive(Debug)]
 pub struct SingleLinkedList<T> {
     head: Option<Box<SingleLinkedNode<T>>>,
 }
@@ -51,6 +66,10 @@ impl<T> SingleLinkedList<T> {
         self.head = Some(node)
     }
 
+    pub fn peek_head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
     pub fn pop_head(&mut self) -> Option<T> {
         let head = self.head.take();
         head.map(|node| {
@@ -90,6 +109,13 @@ impl<T> SingleLinkedList<T> {
         Self::concat(node, list);
         value
     }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            link: Some(self),
+        }
+    }
 }
 
 impl<T> Default for SingleLinkedList<T> {
@@ -98,6 +124,73 @@ impl<T> Default for SingleLinkedList<T> {
     }
 }
 
+// The impls below walk the list in a loop. Deriving them would recurse
+// through `next` once per node and overflow the stack on long lists.
+
+impl<T> Drop for SingleLinkedList<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        while let Some(mut node) = head {
+            head = node.next.head.take();
+        }
+    }
+}
+
+impl<T: Clone> Clone for SingleLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().map(|node| node.value.clone()).collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SingleLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter()
+            .map(|node| &node.value)
+            .eq(other.iter().map(|node| &node.value))
+    }
+}
+
+impl<T: Eq> Eq for SingleLinkedList<T> {}
+
+impl<T: Hash> Hash for SingleLinkedList<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for node in self.iter() {
+            node.value.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
+    }
+}
+
+impl<T: Debug> Debug for SingleLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.iter().map(|node| &node.value))
+            .finish()
+    }
+}
+
+impl<T: Debug> Debug for SingleLinkedNode<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleLinkedNode")
+            .field("value", &self.value)
+            .field("next", &self.next)
+            .finish()
+    }
+}
+
+impl<T> FromIterator<T> for SingleLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        let mut cursor = list.cursor_front_mut();
+        for x in iter {
+            cursor.insert_before(x);
+        }
+        list
+    }
+}
+
 impl<T: PartialEq> SingleLinkedList<T> {
     pub fn find(&self, x: &T) -> Option<&SingleLinkedNode<T>> {
         let mut head = &self.head;
@@ -176,3 +269,109 @@ impl<'a, T> Iterator for Iter<'a, T> {
         })
     }
 }
+
+/// A cursor that walks a `SingleLinkedList` forward and edits it in place.
+///
+/// The cursor always sits on the link that owns the current element. Once it
+/// has walked past the last element it points at the end of the list, where
+/// `current` returns `None` and insertions append to the tail.
+pub struct CursorMut<'a, T> {
+    index: usize,
+    link: Option<&'a mut SingleLinkedList<T>>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    fn link(&mut self) -> &mut SingleLinkedList<T> {
+        unsafe { self.link.as_deref_mut().unwrap_unchecked() }
+    }
+
+    /// Position of the current element, or `None` at the end of the list.
+    pub fn index(&self) -> Option<usize> {
+        let link = unsafe { self.link.as_deref().unwrap_unchecked() };
+        link.head.as_ref().map(|_| self.index)
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.index().is_none()
+    }
+
+    /// Moves to the next element. Does nothing at the end of the list.
+    pub fn move_next(&mut self) {
+        let link = unsafe { self.link.take().unwrap_unchecked() };
+        self.link = Some(if link.is_empty() {
+            link
+        } else {
+            self.index += 1;
+            unsafe { &mut link.head.as_mut().unwrap_unchecked().next }
+        });
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.link().head.as_mut().map(|node| &mut node.value)
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let node = self.link().head.as_mut()?;
+        node.next.head.as_mut().map(|node| &mut node.value)
+    }
+
+    /// Inserts `x` before the current element; the cursor stays on the
+    /// current element. At the end of the list, `x` is appended.
+    pub fn insert_before(&mut self, x: T) {
+        self.link().push_head(x);
+        self.move_next();
+    }
+
+    /// Inserts `x` after the current element; the cursor stays on the
+    /// current element. At the end of the list, `x` is appended and becomes
+    /// the current element.
+    pub fn insert_after(&mut self, x: T) {
+        match self.link().head.as_mut() {
+            Some(node) => SingleLinkedList::insert_after(node, x),
+            None => self.link().push_head(x),
+        }
+    }
+
+    /// Removes the current element and moves the cursor onto the element
+    /// that followed it.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.link().pop_head()
+    }
+
+    /// Splits the list after the current element and returns the tail.
+    /// Returns an empty list at the end of the list.
+    pub fn split_after(&mut self) -> SingleLinkedList<T> {
+        match self.link().head.as_mut() {
+            Some(node) => SingleLinkedList::split(node),
+            None => SingleLinkedList::new(),
+        }
+    }
+
+    /// Splits the list before the current element and returns the current
+    /// element together with everything after it. The cursor is left at the
+    /// end of the remaining list.
+    pub fn split_before(&mut self) -> SingleLinkedList<T> {
+        std::mem::take(self.link())
+    }
+
+    /// Moves all elements of `list` in before the current element; the
+    /// cursor stays on the current element. Takes O(`list.len()`).
+    pub fn splice_before(&mut self, list: SingleLinkedList<T>) {
+        let rest = std::mem::replace(self.link(), list);
+        while !self.link().is_empty() {
+            self.move_next();
+        }
+        *self.link() = rest;
+    }
+
+    /// Moves all elements of `list` in after the current element; the
+    /// cursor stays on the current element. At the end of the list, the
+    /// elements are appended and the first of them becomes current.
+    pub fn splice_after(&mut self, list: SingleLinkedList<T>) {
+        let mut cursor = match self.link().head.as_mut() {
+            Some(node) => node.next.cursor_front_mut(),
+            None => self.link().cursor_front_mut(),
+        };
+        cursor.splice_before(list);
+    }
+}
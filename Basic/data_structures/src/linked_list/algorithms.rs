@@ -0,0 +1,217 @@
+//! Classic two-pointer algorithms over linked structures.
+//!
+//! The generic functions walk any sequence given by a start state and a
+//! successor function `next`, which returns `None` past the last element.
+//! That covers list nodes as well as index-based successor tables such as
+//! functional graphs `x -> f(x)`. States are compared with `PartialEq`; wrap
+//! references in `ByAddress` to compare nodes by identity instead of value.
+
+use super::{SingleLinkedList, SingleLinkedNode};
+
+/// A reference compared by address rather than by value.
+#[derive(Debug)]
+pub struct ByAddress<'a, T>(pub &'a T);
+
+impl<T> Clone for ByAddress<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ByAddress<'_, T> {}
+
+impl<T> PartialEq for ByAddress<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl<T> Eq for ByAddress<'_, T> {}
+
+/// The cycle a sequence runs into: `entry` is the first repeated state, at
+/// `start` steps from the beginning, and the cycle has `len` states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    pub entry: S,
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Steps `x` forward `n` times.
+fn advance<S, F: Fn(&S) -> Option<S>>(x: S, n: usize, next: &F) -> Option<S> {
+    (0..n).try_fold(x, |x, _| next(&x))
+}
+
+/// Finds the cycle start given a state `hare` that is `len` steps ahead of
+/// `x0`, where `len` is a multiple of the cycle length.
+fn cycle_entry<S: PartialEq, F: Fn(&S) -> Option<S>>(
+    x0: S,
+    mut hare: S,
+    next: &F,
+) -> Option<(S, usize)> {
+    let (mut tortoise, mut start) = (x0, 0);
+    while tortoise != hare {
+        tortoise = next(&tortoise)?;
+        hare = next(&hare)?;
+        start += 1;
+    }
+    Some((tortoise, start))
+}
+
+/// Floyd's tortoise and hare. Returns `None` if the sequence terminates.
+pub fn floyd_cycle<S, F>(x0: S, next: F) -> Option<Cycle<S>>
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> Option<S>,
+{
+    let mut tortoise = next(&x0)?;
+    let mut hare = next(&tortoise)?;
+    while tortoise != hare {
+        tortoise = next(&tortoise)?;
+        hare = advance(hare, 2, &next)?;
+    }
+
+    let (entry, start) = cycle_entry(x0, hare, &next)?;
+    let mut len = 1;
+    let mut hare = next(&entry)?;
+    while entry != hare {
+        hare = next(&hare)?;
+        len += 1;
+    }
+    Some(Cycle { entry, start, len })
+}
+
+/// Brent's algorithm, which finds the cycle length first with fewer calls to
+/// `next` than Floyd's. Returns `None` if the sequence terminates.
+pub fn brent_cycle<S, F>(x0: S, next: F) -> Option<Cycle<S>>
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> Option<S>,
+{
+    let (mut power, mut len) = (1, 1);
+    let mut tortoise = x0.clone();
+    let mut hare = next(&x0)?;
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = next(&hare)?;
+        len += 1;
+    }
+
+    let hare = advance(x0.clone(), len, &next)?;
+    let (entry, start) = cycle_entry(x0, hare, &next)?;
+    Some(Cycle { entry, start, len })
+}
+
+/// The element at index `len / 2` of a terminating sequence, found in one
+/// pass with a pointer moving at twice the speed.
+pub fn middle<S, F>(x0: S, next: F) -> S
+where
+    S: Clone,
+    F: Fn(&S) -> Option<S>,
+{
+    let (mut slow, mut fast) = (x0.clone(), x0);
+    while let Some(fast_next) = next(&fast) {
+        if let Some(slow_next) = next(&slow) {
+            slow = slow_next;
+        }
+        match next(&fast_next) {
+            Some(x) => fast = x,
+            None => break,
+        }
+    }
+    slow
+}
+
+/// The `k`-th element counted from the end of a terminating sequence, where
+/// `k = 0` is the last element. Returns `None` if the sequence is shorter
+/// than `k + 1`.
+pub fn kth_from_end<S, F>(x0: S, k: usize, next: F) -> Option<S>
+where
+    S: Clone,
+    F: Fn(&S) -> Option<S>,
+{
+    let mut lead = advance(x0.clone(), k, &next)?;
+    let mut trail = x0;
+    while let Some(x) = next(&lead) {
+        lead = x;
+        trail = next(&trail)?;
+    }
+    Some(trail)
+}
+
+/// The first state shared by two terminating sequences, e.g. where two
+/// lists merge into a common tail.
+pub fn intersection<S, F>(a0: S, b0: S, next: F) -> Option<S>
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> Option<S>,
+{
+    let len = |x0: &S| std::iter::successors(Some(x0.clone()), &next).count();
+    let (len_a, len_b) = (len(&a0), len(&b0));
+    let mut a = advance(a0, len_a.saturating_sub(len_b), &next)?;
+    let mut b = advance(b0, len_b.saturating_sub(len_a), &next)?;
+    while a != b {
+        a = next(&a)?;
+        b = next(&b)?;
+    }
+    Some(a)
+}
+
+impl<T> SingleLinkedNode<T> {
+    pub fn next(&self) -> Option<&SingleLinkedNode<T>> {
+        self.next.head.as_deref()
+    }
+}
+
+impl<T> SingleLinkedList<T> {
+    pub fn reverse(&mut self) {
+        let mut reversed = SingleLinkedList::new();
+        let mut head = self.head.take();
+        while let Some(mut node) = head {
+            head = std::mem::replace(&mut node.next.head, reversed.head.take());
+            reversed.head = Some(node);
+        }
+        *self = reversed;
+    }
+
+    pub fn middle(&self) -> Option<&SingleLinkedNode<T>> {
+        let head = ByAddress(self.head.as_deref()?);
+        Some(middle(head, |node| node.0.next().map(ByAddress)).0)
+    }
+
+    pub fn kth_from_end(&self, k: usize) -> Option<&SingleLinkedNode<T>> {
+        let head = ByAddress(self.head.as_deref()?);
+        kth_from_end(head, k, |node| node.0.next().map(ByAddress)).map(|node| node.0)
+    }
+}
+
+impl<T: PartialEq> SingleLinkedList<T> {
+    /// Checks whether the list reads the same both ways with O(1) extra
+    /// space, by reversing the second half in place and restoring it after.
+    pub fn is_palindrome(&mut self) -> bool {
+        let half = self.len().div_ceil(2);
+        let mut cursor = self.cursor_front_mut();
+        for _ in 0..half {
+            cursor.move_next();
+        }
+        let mut second = cursor.split_before();
+        second.reverse();
+
+        let palindrome = self
+            .iter()
+            .zip(second.iter())
+            .all(|(a, b)| a.value == b.value);
+
+        second.reverse();
+        let mut cursor = self.cursor_front_mut();
+        for _ in 0..half {
+            cursor.move_next();
+        }
+        cursor.splice_before(second);
+        palindrome
+    }
+}
@@ -0,0 +1,509 @@
+use std::fmt::Debug;
+
+/// A stable handle to a node of an arena-backed list.
+///
+/// Slots are reused after removal, so every slot carries a generation that is
+/// bumped when it is freed; a handle whose generation no longer matches is
+/// stale and resolves to nothing. A `NodeId` is only meaningful for the list
+/// that issued it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: usize,
+    generation: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Entry<N> {
+    Occupied(N),
+    /// Free slots form a singly linked free list through their indices.
+    Free(Option<usize>),
+}
+
+#[derive(Debug, Clone)]
+struct Slot<N> {
+    generation: u64,
+    entry: Entry<N>,
+}
+
+#[derive(Debug, Clone)]
+struct Arena<N> {
+    slots: Vec<Slot<N>>,
+    free: Option<usize>,
+}
+
+impl<N> Arena<N> {
+    fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: None,
+        }
+    }
+
+    fn insert(&mut self, node: N) -> usize {
+        match self.free {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                if let Entry::Free(next) = slot.entry {
+                    self.free = next;
+                }
+                slot.entry = Entry::Occupied(node);
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: Entry::Occupied(node),
+                });
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    fn remove(&mut self, index: usize) -> N {
+        let slot = &mut self.slots[index];
+        slot.generation += 1;
+        match std::mem::replace(&mut slot.entry, Entry::Free(self.free.replace(index))) {
+            Entry::Occupied(node) => node,
+            Entry::Free(_) => unreachable!("removing a free slot"),
+        }
+    }
+
+    fn clear(&mut self) {
+        for index in 0..self.slots.len() {
+            if matches!(self.slots[index].entry, Entry::Occupied(_)) {
+                self.remove(index);
+            }
+        }
+    }
+
+    fn id(&self, index: usize) -> NodeId {
+        NodeId {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    /// Resolves `id` to its slot index if the handle is not stale.
+    fn index(&self, id: NodeId) -> Option<usize> {
+        let slot = self.slots.get(id.index)?;
+        (slot.generation == id.generation && matches!(slot.entry, Entry::Occupied(_)))
+            .then_some(id.index)
+    }
+
+    fn node(&self, index: usize) -> &N {
+        match &self.slots[index].entry {
+            Entry::Occupied(node) => node,
+            Entry::Free(_) => unreachable!("linked to a free slot"),
+        }
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut N {
+        match &mut self.slots[index].entry {
+            Entry::Occupied(node) => node,
+            Entry::Free(_) => unreachable!("linked to a free slot"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SingleNode<T> {
+    value: T,
+    next: Option<usize>,
+}
+
+/// A singly linked list whose nodes live in a `Vec` arena.
+#[derive(Clone)]
+pub struct ArenaSingleLinkedList<T> {
+    arena: Arena<SingleNode<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+impl<T> ArenaSingleLinkedList<T> {
+    pub fn new() -> Self {
+        Self {
+            arena: Arena::new(),
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        (self.head, self.tail, self.len) = (None, None, 0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.indices().map(|index| &self.arena.node(index).value)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.indices().map(|index| self.arena.id(index))
+    }
+
+    fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.head, |index| self.arena.node(*index).next)
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.arena.index(id).is_some()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        let index = self.arena.index(id)?;
+        Some(&self.arena.node(index).value)
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        let index = self.arena.index(id)?;
+        Some(&mut self.arena.node_mut(index).value)
+    }
+
+    pub fn front(&self) -> Option<NodeId> {
+        self.head.map(|index| self.arena.id(index))
+    }
+
+    pub fn back(&self) -> Option<NodeId> {
+        self.tail.map(|index| self.arena.id(index))
+    }
+
+    pub fn next(&self, id: NodeId) -> Option<NodeId> {
+        let index = self.arena.index(id)?;
+        self.arena.node(index).next.map(|next| self.arena.id(next))
+    }
+
+    pub fn push_front(&mut self, x: T) -> NodeId {
+        let index = self.arena.insert(SingleNode {
+            value: x,
+            next: self.head,
+        });
+        self.head = Some(index);
+        self.tail.get_or_insert(index);
+        self.len += 1;
+        self.arena.id(index)
+    }
+
+    pub fn push_back(&mut self, x: T) -> NodeId {
+        match self.back() {
+            Some(tail) => self.insert_after(tail, x).unwrap(),
+            None => self.push_front(x),
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let index = self.head?;
+        let node = self.arena.remove(index);
+        self.head = node.next;
+        if self.head.is_none() {
+            self.tail = None;
+        }
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    /// Inserts `x` right after the node `id` in O(1). Returns `None` if `id`
+    /// is stale.
+    pub fn insert_after(&mut self, id: NodeId, x: T) -> Option<NodeId> {
+        let prev = self.arena.index(id)?;
+        let index = self.arena.insert(SingleNode {
+            value: x,
+            next: self.arena.node(prev).next,
+        });
+        self.arena.node_mut(prev).next = Some(index);
+        if self.tail == Some(prev) {
+            self.tail = Some(index);
+        }
+        self.len += 1;
+        Some(self.arena.id(index))
+    }
+
+    /// Removes the node right after `id` in O(1). Returns `None` if `id` is
+    /// stale or the last node.
+    pub fn remove_after(&mut self, id: NodeId) -> Option<T> {
+        let prev = self.arena.index(id)?;
+        let index = self.arena.node(prev).next?;
+        let node = self.arena.remove(index);
+        self.arena.node_mut(prev).next = node.next;
+        if self.tail == Some(index) {
+            self.tail = Some(prev);
+        }
+        self.len -= 1;
+        Some(node.value)
+    }
+}
+
+impl<T> Default for ArenaSingleLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for ArenaSingleLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for ArenaSingleLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        iter.into_iter().for_each(|x| {
+            list.push_back(x);
+        });
+        list
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DoublyNode<T> {
+    value: T,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+/// A doubly linked list whose nodes live in a `Vec` arena, so any node can
+/// be removed or have a neighbor inserted in O(1) given its `NodeId`.
+#[derive(Clone)]
+pub struct ArenaDoublyLinkedList<T> {
+    arena: Arena<DoublyNode<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    len: usize,
+}
+
+impl<T> ArenaDoublyLinkedList<T> {
+    pub fn new() -> Self {
+        Self {
+            arena: Arena::new(),
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        (self.head, self.tail, self.len) = (None, None, 0);
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.indices().map(|index| &self.arena.node(index).value)
+    }
+
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        self.indices().map(|index| self.arena.id(index))
+    }
+
+    fn indices(&self) -> Indices<'_, T> {
+        Indices {
+            arena: &self.arena,
+            head: self.head,
+            tail: self.tail,
+            remaining: self.len,
+        }
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.arena.index(id).is_some()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        let index = self.arena.index(id)?;
+        Some(&self.arena.node(index).value)
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        let index = self.arena.index(id)?;
+        Some(&mut self.arena.node_mut(index).value)
+    }
+
+    pub fn front(&self) -> Option<NodeId> {
+        self.head.map(|index| self.arena.id(index))
+    }
+
+    pub fn back(&self) -> Option<NodeId> {
+        self.tail.map(|index| self.arena.id(index))
+    }
+
+    pub fn next(&self, id: NodeId) -> Option<NodeId> {
+        let index = self.arena.index(id)?;
+        self.arena.node(index).next.map(|next| self.arena.id(next))
+    }
+
+    pub fn prev(&self, id: NodeId) -> Option<NodeId> {
+        let index = self.arena.index(id)?;
+        self.arena.node(index).prev.map(|prev| self.arena.id(prev))
+    }
+
+    /// Allocates a node for `x` and links it in between the adjacent slots
+    /// `prev` and `next`, where `None` stands for the list boundary.
+    fn link_between(&mut self, prev: Option<usize>, next: Option<usize>, x: T) -> NodeId {
+        let index = self.arena.insert(DoublyNode {
+            value: x,
+            prev,
+            next,
+        });
+        match prev {
+            Some(prev) => self.arena.node_mut(prev).next = Some(index),
+            None => self.head = Some(index),
+        }
+        match next {
+            Some(next) => self.arena.node_mut(next).prev = Some(index),
+            None => self.tail = Some(index),
+        }
+        self.len += 1;
+        self.arena.id(index)
+    }
+
+    fn unlink(&mut self, index: usize) -> T {
+        let node = self.arena.remove(index);
+        match node.prev {
+            Some(prev) => self.arena.node_mut(prev).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => self.arena.node_mut(next).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.len -= 1;
+        node.value
+    }
+
+    pub fn push_front(&mut self, x: T) -> NodeId {
+        self.link_between(None, self.head, x)
+    }
+
+    pub fn push_back(&mut self, x: T) -> NodeId {
+        self.link_between(self.tail, None, x)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|index| self.unlink(index))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|index| self.unlink(index))
+    }
+
+    /// Inserts `x` right before the node `id` in O(1). Returns `None` if `id`
+    /// is stale.
+    pub fn insert_before(&mut self, id: NodeId, x: T) -> Option<NodeId> {
+        let next = self.arena.index(id)?;
+        let prev = self.arena.node(next).prev;
+        Some(self.link_between(prev, Some(next), x))
+    }
+
+    /// Inserts `x` right after the node `id` in O(1). Returns `None` if `id`
+    /// is stale.
+    pub fn insert_after(&mut self, id: NodeId, x: T) -> Option<NodeId> {
+        let prev = self.arena.index(id)?;
+        let next = self.arena.node(prev).next;
+        Some(self.link_between(Some(prev), next, x))
+    }
+
+    /// Removes the node `id` in O(1). Returns `None` if `id` is stale.
+    pub fn remove(&mut self, id: NodeId) -> Option<T> {
+        let index = self.arena.index(id)?;
+        Some(self.unlink(index))
+    }
+
+    /// Moves the node `id` to the front, keeping its handle valid.
+    pub fn move_to_front(&mut self, id: NodeId) -> bool {
+        let Some(index) = self.arena.index(id) else {
+            return false;
+        };
+        if self.head != Some(index) {
+            let DoublyNode { prev, next, .. } = *self.arena.node(index);
+            match prev {
+                Some(prev) => self.arena.node_mut(prev).next = next,
+                None => self.head = next,
+            }
+            match next {
+                Some(next) => self.arena.node_mut(next).prev = prev,
+                None => self.tail = prev,
+            }
+            let head = self.head;
+            let node = self.arena.node_mut(index);
+            (node.prev, node.next) = (None, head);
+            match head {
+                Some(head) => self.arena.node_mut(head).prev = Some(index),
+                None => self.tail = Some(index),
+            }
+            self.head = Some(index);
+        }
+        true
+    }
+}
+
+impl<T> Default for ArenaDoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for ArenaDoublyLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for ArenaDoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        iter.into_iter().for_each(|x| {
+            list.push_back(x);
+        });
+        list
+    }
+}
+
+struct Indices<'a, T> {
+    arena: &'a Arena<DoublyNode<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    remaining: usize,
+}
+
+impl<T> Iterator for Indices<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let index = self.head?;
+        self.head = self.arena.node(index).next;
+        self.remaining -= 1;
+        Some(index)
+    }
+}
+
+impl<T> DoubleEndedIterator for Indices<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let index = self.tail?;
+        self.tail = self.arena.node(index).prev;
+        self.remaining -= 1;
+        Some(index)
+    }
+}
@@ -0,0 +1,770 @@
+use std::{
+    cell::{Cell, RefCell, UnsafeCell},
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ptr::NonNull,
+    rc::{Rc, Weak},
+};
+
+use crate::owner::Owner;
+
+type Link<T> = Option<NonNull<DoublyLinkedNode<T>>>;
+
+/// Nodes are reference counted so that `Handle`s can hold a `Weak` pointer
+/// and detect when their node is gone. The list holds the only strong
+/// reference of each node as a raw pointer.
+pub struct DoublyLinkedNode<T> {
+    value: UnsafeCell<T>,
+    prev: Cell<Link<T>>,
+    next: Cell<Link<T>>,
+    owner: RefCell<Rc<Owner>>,
+}
+
+/// A handle to a node of a `DoublyLinkedList`.
+///
+/// A handle never keeps its node alive: once the node is popped or removed,
+/// or its list is dropped, the handle no longer resolves. A handle moves with
+/// its node through `append`, `split_off` and the cursor splice/split
+/// operations.
+pub struct Handle<T>(Weak<DoublyLinkedNode<T>>);
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Handle(self.0.clone())
+    }
+}
+
+pub struct DoublyLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    owner: Rc<Owner>,
+    _marker: PhantomData<Box<DoublyLinkedNode<T>>>,
+}
+
+impl<T> DoublyLinkedList<T> {
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            len: 0,
+            owner: Rc::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|node| unsafe { Self::value(node) })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|node| unsafe { Self::value_mut(node) })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|node| unsafe { Self::value(node) })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.tail.map(|node| unsafe { Self::value_mut(node) })
+    }
+
+    pub fn push_front(&mut self, x: T) {
+        let node = self.alloc(x);
+        unsafe { self.link_between(None, self.head, node) }
+    }
+
+    pub fn push_back(&mut self, x: T) {
+        let node = self.alloc(x);
+        unsafe { self.link_between(self.tail, None, node) }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe {
+            self.unlink(node);
+            Self::into_value(node)
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|node| unsafe {
+            self.unlink(node);
+            Self::into_value(node)
+        })
+    }
+
+    /// Moves all elements of `other` to the back of `self` in O(1).
+    pub fn append(&mut self, other: &mut Self) {
+        let other = std::mem::take(other);
+        unsafe { self.splice_between(self.tail, None, other) }
+    }
+
+    /// Splits the list in two at `at` and returns everything from `at` on.
+    ///
+    /// Takes O(min(at, len - at)): the split point is searched from the
+    /// nearer end, and the shorter half has its nodes moved to a new owner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "cannot split off at a nonexistent index");
+        let last = if at == 0 {
+            None
+        } else if at <= self.len / 2 {
+            let mut node = self.head;
+            for _ in 1..at {
+                node = node.and_then(|node| unsafe { node.as_ref().next.get() });
+            }
+            node
+        } else {
+            let mut node = self.tail;
+            for _ in at..self.len {
+                node = node.and_then(|node| unsafe { node.as_ref().prev.get() });
+            }
+            node
+        };
+        unsafe { self.split_after_node(last, at) }
+    }
+
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.tail,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: self.tail,
+            index: self.len.saturating_sub(1),
+            list: self,
+        }
+    }
+
+    pub fn front_handle(&self) -> Option<Handle<T>> {
+        self.head.map(|node| unsafe { Self::handle(node) })
+    }
+
+    pub fn back_handle(&self) -> Option<Handle<T>> {
+        self.tail.map(|node| unsafe { Self::handle(node) })
+    }
+
+    /// Whether the node behind `handle` is alive and belongs to this list.
+    pub fn contains(&self, handle: &Handle<T>) -> bool {
+        self.resolve(handle).is_some()
+    }
+
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.resolve(handle)
+            .map(|node| unsafe { Self::value(node) })
+    }
+
+    pub fn get_mut(&mut self, handle: &Handle<T>) -> Option<&mut T> {
+        self.resolve(handle)
+            .map(|node| unsafe { Self::value_mut(node) })
+    }
+
+    /// Removes the node behind `handle` in O(1). Returns `None` if the node
+    /// is gone or belongs to another list.
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        let node = self.resolve(handle)?;
+        unsafe {
+            self.unlink(node);
+            Some(Self::into_value(node))
+        }
+    }
+
+    fn resolve(&self, handle: &Handle<T>) -> Option<NonNull<DoublyLinkedNode<T>>> {
+        let node = handle.0.upgrade()?;
+        Owner::owns(&self.owner, &node.owner)
+            .then(|| unsafe { NonNull::new_unchecked(Rc::as_ptr(&node) as *mut _) })
+    }
+
+    fn alloc(&self, x: T) -> NonNull<DoublyLinkedNode<T>> {
+        let node = Rc::new(DoublyLinkedNode {
+            value: UnsafeCell::new(x),
+            prev: Cell::new(None),
+            next: Cell::new(None),
+            owner: RefCell::new(self.owner.clone()),
+        });
+        unsafe { NonNull::new_unchecked(Rc::into_raw(node) as *mut _) }
+    }
+
+    /// # Safety
+    ///
+    /// `node` must be an unlinked node obtained from `alloc`.
+    unsafe fn into_value(node: NonNull<DoublyLinkedNode<T>>) -> T {
+        match Rc::try_unwrap(Rc::from_raw(node.as_ptr())) {
+            Ok(node) => node.value.into_inner(),
+            Err(_) => unreachable!("list nodes have a single strong reference"),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `node` must be alive for `'a`.
+    unsafe fn value<'a>(node: NonNull<DoublyLinkedNode<T>>) -> &'a T {
+        &*node.as_ref().value.get()
+    }
+
+    /// # Safety
+    ///
+    /// `node` must be alive for `'a` and not aliased.
+    unsafe fn value_mut<'a>(node: NonNull<DoublyLinkedNode<T>>) -> &'a mut T {
+        &mut *node.as_ref().value.get()
+    }
+
+    /// # Safety
+    ///
+    /// `node` must be alive.
+    unsafe fn handle(node: NonNull<DoublyLinkedNode<T>>) -> Handle<T> {
+        let node = ManuallyDrop::new(Rc::from_raw(node.as_ptr()));
+        Handle(Rc::downgrade(&node))
+    }
+
+    /// Links `node` in between the adjacent nodes `prev` and `next`, where
+    /// `None` stands for the list boundary.
+    ///
+    /// # Safety
+    ///
+    /// `prev` and `next` must be adjacent in this list and `node` unlinked.
+    unsafe fn link_between(
+        &mut self,
+        prev: Link<T>,
+        next: Link<T>,
+        node: NonNull<DoublyLinkedNode<T>>,
+    ) {
+        node.as_ref().prev.set(prev);
+        node.as_ref().next.set(next);
+        match prev {
+            Some(prev) => prev.as_ref().next.set(Some(node)),
+            None => self.head = Some(node),
+        }
+        match next {
+            Some(next) => next.as_ref().prev.set(Some(node)),
+            None => self.tail = Some(node),
+        }
+        self.len += 1;
+    }
+
+    /// # Safety
+    ///
+    /// `node` must be linked in this list.
+    unsafe fn unlink(&mut self, node: NonNull<DoublyLinkedNode<T>>) {
+        let (prev, next) = (node.as_ref().prev.take(), node.as_ref().next.take());
+        match prev {
+            Some(prev) => prev.as_ref().next.set(next),
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => next.as_ref().prev.set(prev),
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+    }
+
+    /// Moves every node of `other` in between the adjacent nodes `prev` and
+    /// `next`.
+    ///
+    /// # Safety
+    ///
+    /// `prev` and `next` must be adjacent in this list.
+    unsafe fn splice_between(&mut self, prev: Link<T>, next: Link<T>, mut other: Self) {
+        let (Some(head), Some(tail)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        Owner::adopt(&mut self.owner, &other.owner);
+
+        head.as_ref().prev.set(prev);
+        tail.as_ref().next.set(next);
+        match prev {
+            Some(prev) => prev.as_ref().next.set(Some(head)),
+            None => self.head = Some(head),
+        }
+        match next {
+            Some(next) => next.as_ref().prev.set(Some(tail)),
+            None => self.tail = Some(tail),
+        }
+        self.len += std::mem::take(&mut other.len);
+    }
+
+    /// Splits the list after `last`, which is the node at `at - 1` or `None`
+    /// when `at == 0`, and returns the nodes that follow it. The shorter of
+    /// the two halves is relabeled with a fresh owner.
+    ///
+    /// # Safety
+    ///
+    /// `last` must be linked in this list at index `at - 1`.
+    unsafe fn split_after_node(&mut self, last: Link<T>, at: usize) -> Self {
+        let first = match last {
+            Some(last) => last.as_ref().next.take(),
+            None => self.head.take(),
+        };
+        let mut other = Self::new();
+        if let Some(first) = first {
+            first.as_ref().prev.set(None);
+            other.head = Some(first);
+            other.tail = std::mem::replace(&mut self.tail, last);
+            other.len = self.len - at;
+            self.len = at;
+        }
+
+        if other.len <= self.len {
+            other.relabel();
+        } else {
+            std::mem::swap(&mut self.owner, &mut other.owner);
+            self.relabel();
+        }
+        other
+    }
+
+    fn relabel(&mut self) {
+        let mut node = self.head;
+        while let Some(current) = node {
+            let current = unsafe { current.as_ref() };
+            *current.owner.borrow_mut() = self.owner.clone();
+            node = current.next.get();
+        }
+    }
+}
+
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for DoublyLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T: Clone> Clone for DoublyLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for DoublyLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Debug> Debug for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Display> Display for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DoublyLinkedList [")?;
+        for (i, x) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            x.fmt(f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for DoublyLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push_back(x));
+    }
+}
+
+pub struct Iter<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            self.len -= 1;
+            self.head = node.as_ref().next.get();
+            DoublyLinkedList::value(node)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            self.len -= 1;
+            self.tail = node.as_ref().prev.get();
+            DoublyLinkedList::value(node)
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.head.map(|node| unsafe {
+            self.len -= 1;
+            self.head = node.as_ref().next.get();
+            DoublyLinkedList::value_mut(node)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.tail.map(|node| unsafe {
+            self.len -= 1;
+            self.tail = node.as_ref().prev.get();
+            DoublyLinkedList::value_mut(node)
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T>(DoublyLinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A read-only cursor over a `DoublyLinkedList`.
+///
+/// Like the std `LinkedList` cursors, it walks a ring where a "ghost"
+/// position sits between the back and the front of the list. At the ghost
+/// `current` and `index` return `None`.
+pub struct Cursor<'a, T> {
+    current: Link<T>,
+    index: usize,
+    list: &'a DoublyLinkedList<T>,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { node.as_ref().next.get() };
+                self.index += 1;
+            }
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(node) => {
+                self.current = unsafe { node.as_ref().prev.get() };
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            }
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.current
+            .map(|node| unsafe { DoublyLinkedList::value(node) })
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            Some(node) => unsafe { node.as_ref().next.get() },
+            None => self.list.head,
+        };
+        next.map(|node| unsafe { DoublyLinkedList::value(node) })
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            Some(node) => unsafe { node.as_ref().prev.get() },
+            None => self.list.tail,
+        };
+        prev.map(|node| unsafe { DoublyLinkedList::value(node) })
+    }
+
+    pub fn handle(&self) -> Option<Handle<T>> {
+        self.current
+            .map(|node| unsafe { DoublyLinkedList::handle(node) })
+    }
+}
+
+/// A cursor that edits a `DoublyLinkedList` in place. See `Cursor` for how
+/// positions are walked.
+pub struct CursorMut<'a, T> {
+    current: Link<T>,
+    index: usize,
+    list: &'a mut DoublyLinkedList<T>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            current: self.current,
+            index: self.index,
+            list: self.list,
+        }
+    }
+
+    pub fn move_next(&mut self) {
+        let mut cursor = self.as_cursor();
+        cursor.move_next();
+        (self.current, self.index) = (cursor.current, cursor.index);
+    }
+
+    pub fn move_prev(&mut self) {
+        let mut cursor = self.as_cursor();
+        cursor.move_prev();
+        (self.current, self.index) = (cursor.current, cursor.index);
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|node| unsafe { DoublyLinkedList::value_mut(node) })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            Some(node) => unsafe { node.as_ref().next.get() },
+            None => self.list.head,
+        };
+        next.map(|node| unsafe { DoublyLinkedList::value_mut(node) })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            Some(node) => unsafe { node.as_ref().prev.get() },
+            None => self.list.tail,
+        };
+        prev.map(|node| unsafe { DoublyLinkedList::value_mut(node) })
+    }
+
+    pub fn handle(&self) -> Option<Handle<T>> {
+        self.as_cursor().handle()
+    }
+
+    fn neighbors(&self) -> (Link<T>, Link<T>) {
+        match self.current {
+            Some(node) => unsafe { (node.as_ref().prev.get(), node.as_ref().next.get()) },
+            None => (self.list.tail, self.list.head),
+        }
+    }
+
+    /// Inserts `x` before the current element. At the ghost, `x` is pushed
+    /// to the back.
+    pub fn insert_before(&mut self, x: T) {
+        let node = self.list.alloc(x);
+        let prev = self.neighbors().0;
+        unsafe { self.list.link_between(prev, self.current, node) };
+        self.index += 1;
+    }
+
+    /// Inserts `x` after the current element. At the ghost, `x` is pushed to
+    /// the front.
+    pub fn insert_after(&mut self, x: T) {
+        let node = self.list.alloc(x);
+        let next = self.neighbors().1;
+        unsafe { self.list.link_between(self.current, next, node) };
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    /// Removes the current element and moves onto the one that followed it.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        self.current = self.neighbors().1;
+        unsafe {
+            self.list.unlink(node);
+            Some(DoublyLinkedList::into_value(node))
+        }
+    }
+
+    /// Splits off everything after the current element. At the ghost, the
+    /// whole list is split off.
+    pub fn split_after(&mut self) -> DoublyLinkedList<T> {
+        let at = self.current.map_or(0, |_| self.index + 1);
+        let other = unsafe { self.list.split_after_node(self.current, at) };
+        if self.current.is_none() {
+            self.index = 0;
+        }
+        other
+    }
+
+    /// Splits off everything before the current element. At the ghost, the
+    /// whole list is split off.
+    pub fn split_before(&mut self) -> DoublyLinkedList<T> {
+        let at = self.current.map_or(self.list.len, |_| self.index);
+        let prev = self.neighbors().0;
+        let tail = unsafe { self.list.split_after_node(prev, at) };
+        self.index = 0;
+        std::mem::replace(self.list, tail)
+    }
+
+    /// Moves all elements of `list` in before the current element in O(1).
+    /// At the ghost, they are appended to the back.
+    pub fn splice_before(&mut self, list: DoublyLinkedList<T>) {
+        let prev = self.neighbors().0;
+        self.index += list.len;
+        unsafe { self.list.splice_between(prev, self.current, list) };
+    }
+
+    /// Moves all elements of `list` in after the current element in O(1).
+    /// At the ghost, they are prepended to the front.
+    pub fn splice_after(&mut self, list: DoublyLinkedList<T>) {
+        let next = self.neighbors().1;
+        unsafe { self.list.splice_between(self.current, next, list) };
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+}
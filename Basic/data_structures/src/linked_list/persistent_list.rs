@@ -0,0 +1,207 @@
+use std::{
+    fmt::{Debug, Display},
+    ops::Deref,
+    rc::Rc,
+    sync::Arc,
+};
+
+/// Chooses the shared pointer that links the nodes of a `PersistentList`.
+pub trait PointerKind {
+    type Pointer<U>: Clone + Deref<Target = U>;
+
+    fn new<U>(x: U) -> Self::Pointer<U>;
+
+    /// Returns the pointee if `p` is its last strong reference.
+    fn into_inner<U>(p: Self::Pointer<U>) -> Option<U>;
+
+    fn ptr_eq<U>(a: &Self::Pointer<U>, b: &Self::Pointer<U>) -> bool;
+}
+
+#[derive(Debug)]
+pub struct RcPointer;
+
+impl PointerKind for RcPointer {
+    type Pointer<U> = Rc<U>;
+
+    fn new<U>(x: U) -> Rc<U> {
+        Rc::new(x)
+    }
+
+    fn into_inner<U>(p: Rc<U>) -> Option<U> {
+        Rc::into_inner(p)
+    }
+
+    fn ptr_eq<U>(a: &Rc<U>, b: &Rc<U>) -> bool {
+        Rc::ptr_eq(a, b)
+    }
+}
+
+#[derive(Debug)]
+pub struct ArcPointer;
+
+impl PointerKind for ArcPointer {
+    type Pointer<U> = Arc<U>;
+
+    fn new<U>(x: U) -> Arc<U> {
+        Arc::new(x)
+    }
+
+    fn into_inner<U>(p: Arc<U>) -> Option<U> {
+        Arc::into_inner(p)
+    }
+
+    fn ptr_eq<U>(a: &Arc<U>, b: &Arc<U>) -> bool {
+        Arc::ptr_eq(a, b)
+    }
+}
+
+struct PersistentNode<T, P: PointerKind> {
+    value: T,
+    next: PersistentList<T, P>,
+}
+
+/// An immutable cons list. Every operation leaves `self` untouched and
+/// returns a new version that shares its tail with the old one, so cloning
+/// and taking snapshots are O(1).
+pub struct PersistentList<T, P: PointerKind = RcPointer> {
+    head: Option<P::Pointer<PersistentNode<T, P>>>,
+    len: usize,
+}
+
+pub type RcList<T> = PersistentList<T, RcPointer>;
+
+/// A persistent list whose versions can be shared across threads.
+pub type ArcList<T> = PersistentList<T, ArcPointer>;
+
+impl<T, P: PointerKind> PersistentList<T, P> {
+    pub fn new() -> Self {
+        Self { head: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        Iter(self)
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    pub fn push_head(&self, x: T) -> Self {
+        Self {
+            head: Some(P::new(PersistentNode {
+                value: x,
+                next: self.clone(),
+            })),
+            len: self.len + 1,
+        }
+    }
+
+    /// The list without its first element, or `None` if the list is empty.
+    pub fn tail(&self) -> Option<Self> {
+        self.head.as_ref().map(|node| node.next.clone())
+    }
+
+    /// Whether both lists are the same version, i.e. share their first node.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => P::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Clone, P: PointerKind> PersistentList<T, P> {
+    pub fn reverse(&self) -> Self {
+        self.iter()
+            .fold(Self::new(), |list, x| list.push_head(x.clone()))
+    }
+}
+
+impl<T, P: PointerKind> Clone for PersistentList<T, P> {
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T, P: PointerKind> Default for PersistentList<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P: PointerKind> Drop for PersistentList<T, P> {
+    fn drop(&mut self) {
+        // Unlink nodes iteratively while this version is their only owner;
+        // the first shared node is left to the versions still using it.
+        let mut head = self.head.take();
+        while let Some(node) = head {
+            head = P::into_inner(node).and_then(|mut node| node.next.head.take());
+        }
+    }
+}
+
+impl<T, P: PointerKind> FromIterator<T> for PersistentList<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<_> = iter.into_iter().collect();
+        values
+            .into_iter()
+            .rev()
+            .fold(Self::new(), |list, x| list.push_head(x))
+    }
+}
+
+impl<T: PartialEq, P: PointerKind> PartialEq for PersistentList<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Debug, P: PointerKind> Debug for PersistentList<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Display, P: PointerKind> Display for PersistentList<T, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PersistentList [")?;
+        for (i, x) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            x.fmt(f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+pub struct Iter<'a, T, P: PointerKind>(&'a PersistentList<T, P>);
+
+impl<'a, T, P: PointerKind> Iterator for Iter<'a, T, P> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.head.as_ref().map(|node| {
+            self.0 = &node.next;
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T, P: PointerKind> ExactSizeIterator for Iter<'_, T, P> {}
@@ -0,0 +1,309 @@
+use std::fmt::{Debug, Display};
+
+struct UnrolledNode<T, const N: usize> {
+    items: Vec<T>,
+    next: Option<Box<UnrolledNode<T, N>>>,
+}
+
+impl<T, const N: usize> UnrolledNode<T, N> {
+    fn new() -> Self {
+        Self {
+            items: Vec::with_capacity(N),
+            next: None,
+        }
+    }
+}
+
+/// A singly linked list whose nodes each hold up to `N` elements in one
+/// contiguous buffer.
+///
+/// A full node is split in half before inserting into it, and a node that
+/// drops below half full after a removal takes elements from, or merges
+/// with, its successor. So every node but the last is at least half full.
+pub struct UnrolledLinkedList<T, const N: usize = 16> {
+    head: Option<Box<UnrolledNode<T, N>>>,
+    len: usize,
+}
+
+impl<T, const N: usize> UnrolledLinkedList<T, N> {
+    pub fn new() -> Self {
+        assert!(N >= 2, "nodes must hold at least two elements");
+        Self { head: None, len: 0 }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            node: self.head.as_deref(),
+            index: 0,
+            remaining: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut {
+            node: self.head.as_deref_mut(),
+            items: [].iter_mut(),
+            remaining: self.len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut node = self.head.as_deref();
+        while let Some(current) = node {
+            count += 1;
+            node = current.next.as_deref();
+        }
+        count
+    }
+
+    pub fn clear(&mut self) {
+        let mut head = self.head.take();
+        while let Some(mut node) = head {
+            head = node.next.take();
+        }
+        self.len = 0;
+    }
+
+    fn node_of(&self, index: usize) -> Option<(&UnrolledNode<T, N>, usize)> {
+        let (mut node, mut index) = (self.head.as_deref()?, index);
+        while index >= node.items.len() {
+            index -= node.items.len();
+            node = node.next.as_deref()?;
+        }
+        Some((node, index))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.node_of(index).map(|(node, i)| &node.items[i])
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let (mut node, mut index) = (self.head.as_deref_mut()?, index);
+        while index >= node.items.len() {
+            index -= node.items.len();
+            node = node.next.as_deref_mut()?;
+        }
+        Some(&mut node.items[index])
+    }
+
+    /// Inserts `x` at `index`, shifting later elements back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, x: T) {
+        assert!(index <= self.len, "insertion index out of bounds");
+        self.len += 1;
+
+        let mut index = index;
+        let mut node = self
+            .head
+            .get_or_insert_with(|| Box::new(UnrolledNode::new()));
+        loop {
+            let len = node.items.len();
+            if index > len || (index == len && len == N && node.next.is_some()) {
+                index -= len;
+                node = unsafe { node.next.as_mut().unwrap_unchecked() };
+            } else {
+                break;
+            }
+        }
+
+        if node.items.len() == N {
+            let mut half = UnrolledNode::new();
+            half.items.extend(node.items.drain(N / 2..));
+            half.next = node.next.take();
+            node.next = Some(Box::new(half));
+            if index > N / 2 {
+                index -= N / 2;
+                node = unsafe { node.next.as_mut().unwrap_unchecked() };
+            }
+        }
+        node.items.insert(index, x);
+    }
+
+    /// Removes and returns the element at `index`, or `None` if `index` is
+    /// out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        self.len -= 1;
+
+        let mut index = index;
+        let mut link = &mut self.head;
+        loop {
+            let len = unsafe { link.as_ref().unwrap_unchecked() }.items.len();
+            if index < len {
+                break;
+            }
+            index -= len;
+            link = unsafe { &mut link.as_mut().unwrap_unchecked().next };
+        }
+
+        let node = unsafe { link.as_mut().unwrap_unchecked() };
+        let x = node.items.remove(index);
+        if node.items.is_empty() {
+            *link = node.next.take();
+        } else if node.items.len() < N / 2 {
+            if let Some(next) = node.next.as_mut() {
+                if node.items.len() + next.items.len() <= N {
+                    node.items.append(&mut next.items);
+                    node.next = next.next.take();
+                } else {
+                    node.items.push(next.items.remove(0));
+                }
+            }
+        }
+        Some(x)
+    }
+
+    pub fn push_head(&mut self, x: T) {
+        self.insert(0, x)
+    }
+
+    pub fn push_tail(&mut self, x: T) {
+        self.insert(self.len, x)
+    }
+
+    pub fn pop_head(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    pub fn pop_tail(&mut self) -> Option<T> {
+        self.len.checked_sub(1).and_then(|i| self.remove(i))
+    }
+}
+
+impl<T, const N: usize> Default for UnrolledLinkedList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for UnrolledLinkedList<T, N> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for UnrolledLinkedList<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for UnrolledLinkedList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for UnrolledLinkedList<T, N> {
+    /// Packs the elements into full nodes.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        let mut link = &mut list.head;
+        let mut iter = iter.into_iter().peekable();
+        while iter.peek().is_some() {
+            let node = link.insert(Box::new(UnrolledNode::new()));
+            node.items.extend(iter.by_ref().take(N));
+            list.len += node.items.len();
+            link = &mut node.next;
+        }
+        list
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for UnrolledLinkedList<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Display, const N: usize> Display for UnrolledLinkedList<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UnrolledLinkedList [")?;
+        for (i, x) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            x.fmt(f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a UnrolledLinkedList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T, const N: usize> {
+    node: Option<&'a UnrolledNode<T, N>>,
+    index: usize,
+    remaining: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut node = self.node?;
+        if self.index == node.items.len() {
+            node = node.next.as_deref()?;
+            self.node = Some(node);
+            self.index = 0;
+        }
+        self.index += 1;
+        self.remaining -= 1;
+        node.items.get(self.index - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Iter<'_, T, N> {}
+
+pub struct IterMut<'a, T, const N: usize> {
+    node: Option<&'a mut UnrolledNode<T, N>>,
+    items: std::slice::IterMut<'a, T>,
+    remaining: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(x) = self.items.next() {
+                self.remaining -= 1;
+                return Some(x);
+            }
+            let node = self.node.take()?;
+            self.items = node.items.iter_mut();
+            self.node = node.next.as_deref_mut();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IterMut<'_, T, N> {}
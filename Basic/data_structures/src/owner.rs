@@ -0,0 +1,96 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+/// Identifies which container a node currently belongs to.
+///
+/// Every container owns a root `Owner`. Merging a container links one root
+/// under the other instead of relabeling every node, so looking up a node's
+/// container is a union-find walk to the root. Linking by rank and pointing
+/// every owner on a walk straight at the root keep the walks short, for a
+/// near-constant amortized cost per lookup.
+#[derive(Default)]
+pub(crate) struct Owner {
+    parent: RefCell<Option<Rc<Owner>>>,
+    /// An upper bound on the length of the longest walk ending here.
+    rank: Cell<u8>,
+}
+
+impl Owner {
+    pub(crate) fn root(owner: &Rc<Owner>) -> Rc<Owner> {
+        let mut root = owner.clone();
+        loop {
+            let parent = root.parent.borrow().clone();
+            match parent {
+                Some(parent) => root = parent,
+                None => break,
+            }
+        }
+        let mut owner = owner.clone();
+        while !Rc::ptr_eq(&owner, &root) {
+            let parent = owner.parent.replace(Some(root.clone()));
+            owner = parent.expect("a walk to the root");
+        }
+        root
+    }
+
+    /// Whether the node tagged with `tag` belongs to the container owning
+    /// the root `owner`. The tag is pointed straight at its root on the way.
+    pub(crate) fn owns(owner: &Rc<Owner>, tag: &RefCell<Rc<Owner>>) -> bool {
+        let root = Owner::root(&tag.borrow());
+        let owned = Rc::ptr_eq(&root, owner);
+        *tag.borrow_mut() = root;
+        owned
+    }
+
+    /// Makes every node owned by the root `other` belong to `owner`, which
+    /// may be replaced by `other` if that keeps the walks shorter.
+    pub(crate) fn adopt(owner: &mut Rc<Owner>, other: &Rc<Owner>) {
+        let (rank, other_rank) = (owner.rank.get(), other.rank.get());
+        if rank < other_rank {
+            *owner.parent.borrow_mut() = Some(other.clone());
+            *owner = other.clone();
+        } else {
+            *other.parent.borrow_mut() = Some(owner.clone());
+            if rank == other_rank {
+                owner.rank.set(rank + 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(owner: &Rc<Owner>) -> usize {
+        let parent = owner.parent.borrow().clone();
+        parent.map_or(0, |parent| depth(&parent) + 1)
+    }
+
+    #[test]
+    fn test_walks_stay_short() {
+        // Merge 1024 containers pairwise, keeping one node tag from each.
+        let mut owners = (0..1024)
+            .map(|_| Rc::<Owner>::default())
+            .collect::<Vec<_>>();
+        let tags = owners.clone();
+        while owners.len() > 1 {
+            let mut merged = Vec::new();
+            while let (Some(mut a), Some(b)) = (owners.pop(), owners.pop()) {
+                Owner::adopt(&mut a, &b);
+                merged.push(a);
+            }
+            owners = merged;
+        }
+        let root = &owners[0];
+        assert!(tags.iter().all(|tag| depth(tag) <= 10));
+
+        // A lookup points every owner on its walk at the root.
+        let deepest = tags.iter().max_by_key(|tag| depth(tag)).unwrap();
+        assert!(Owner::owns(root, &RefCell::new(deepest.clone())));
+        assert!(depth(deepest) == 1);
+        assert!(!Owner::owns(&Rc::default(), &RefCell::new(deepest.clone())));
+    }
+}
@@ -1,10 +1,76 @@
-use crate::{linked_list::SingleLinkedList, tree::BinaryTree};
+use std::{cell::Ref, marker::PhantomData};
+
+use crate::{
+    linked_list::{DoublyLinkedList, SingleLinkedList},
+    tree::BinaryTree,
+};
+
+pub mod monotonic_queue;
+pub use monotonic_queue::MonotonicQueue;
+pub mod mpmc;
+pub mod ring_queue;
+pub use ring_queue::RingQueue;
+pub mod spsc;
+pub mod stack_queue;
+pub use stack_queue::StackQueue;
+pub mod timer_wheel;
+pub use timer_wheel::TimerWheel;
 
 pub trait Queue<T> {
     fn enqueue(&mut self, x: T);
     fn dequeue(&mut self) -> Option<T>;
     fn size(&self) -> usize;
     fn new() -> Self;
+
+    /// The element `dequeue` would return next. `None` unless the queue
+    /// overrides it; a queue that cannot lend out a plain reference to its
+    /// front, as `BinaryTree` cannot, keeps this default.
+    fn peek(&self) -> Option<&T> {
+        None
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    fn clear(&mut self) {
+        while self.dequeue().is_some() {}
+    }
+
+    /// Dequeues elements as the returned iterator is advanced.
+    fn drain(&mut self) -> Drain<'_, T, Self>
+    where
+        Self: Sized,
+    {
+        Drain(self, PhantomData)
+    }
+}
+
+/// A queue with a fixed capacity.
+///
+/// `Queue::new` still has to build one without being told a capacity, and
+/// may return a queue that never fills up, as `Bounded` does. Code that
+/// relies on the bound should create the queue through `with_capacity`.
+pub trait BoundedQueue<T>: Queue<T> {
+    fn with_capacity(capacity: usize) -> Self;
+    fn capacity(&self) -> usize;
+
+    /// Enqueues `x`, or hands it back if the queue is full.
+    fn try_enqueue(&mut self, x: T) -> Result<(), T>;
+
+    fn is_full(&self) -> bool {
+        self.size() >= self.capacity()
+    }
+}
+
+pub struct Drain<'a, T, Q: Queue<T>>(&'a mut Q, PhantomData<T>);
+
+impl<T, Q: Queue<T>> Iterator for Drain<'_, T, Q> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.dequeue()
+    }
 }
 
 impl<T> Queue<T> for Vec<T> {
@@ -20,6 +86,10 @@ impl<T> Queue<T> for Vec<T> {
         }
     }
 
+    fn peek(&self) -> Option<&T> {
+        self.first()
+    }
+
     fn size(&self) -> usize {
         self.len()
     }
@@ -27,6 +97,10 @@ impl<T> Queue<T> for Vec<T> {
     fn new() -> Self {
         Vec::new()
     }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
 }
 
 impl<T> Queue<T> for SingleLinkedList<T> {
@@ -38,6 +112,10 @@ impl<T> Queue<T> for SingleLinkedList<T> {
         self.pop_head()
     }
 
+    fn peek(&self) -> Option<&T> {
+        self.peek_head()
+    }
+
     fn size(&self) -> usize {
         self.len()
     }
@@ -45,6 +123,36 @@ impl<T> Queue<T> for SingleLinkedList<T> {
     fn new() -> Self {
         SingleLinkedList::new()
     }
+
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T> Queue<T> for DoublyLinkedList<T> {
+    fn enqueue(&mut self, x: T) {
+        self.push_back(x)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        DoublyLinkedList::new()
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
 }
 
 #[derive(Debug)]
@@ -60,6 +168,18 @@ impl<T> PartialOrd for Node<T> {
     }
 }
 
+impl<T> BinaryTree<Node<T>> {
+    /// The front of the queue. The tree keeps it inside a `RefCell`, so it
+    /// comes back as a guard rather than through `Queue::peek`.
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        // Every index enqueued is greater than all those in the tree, so no
+        // node ever goes left of the root and the front is the root itself.
+        let root = self.root.as_ref()?;
+        debug_assert!(root.borrow().left.is_empty());
+        Some(Ref::map(root.borrow(), |node| &node.value.1))
+    }
+}
+
 impl<T: std::fmt::Debug> Queue<T> for BinaryTree<Node<T>> {
     fn enqueue(&mut self, x: T) {
         let index = self
@@ -87,6 +207,14 @@ impl<T: std::fmt::Debug> Queue<T> for BinaryTree<Node<T>> {
     fn new() -> Self {
         BinaryTree::new()
     }
+
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.clean()
+    }
 }
 
 #[cfg(test)]
@@ -94,6 +222,32 @@ mod tests {
     use rand::Rng;
 
     use super::*;
+    use crate::bounded::Bounded;
+
+    fn lent_front<Q: Queue<i32>>(queue: &Q) -> Option<i32> {
+        queue.peek().copied()
+    }
+
+    /// `front` reads the front of the queue, which not every queue can do
+    /// through `Queue::peek`.
+    fn check_queue_defaults<Q: Queue<i32>>(front: fn(&Q) -> Option<i32>) {
+        let mut queue = Q::new();
+        assert!(queue.is_empty() && front(&queue).is_none());
+        for i in 0..10 {
+            queue.enqueue(i);
+        }
+        assert!(!queue.is_empty() && front(&queue) == Some(0));
+
+        assert!(queue.drain().take(3).eq(0..3));
+        assert!(front(&queue) == Some(3) && queue.size() == 7);
+        assert!(queue.drain().eq(3..10));
+        assert!(queue.is_empty());
+
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.clear();
+        assert!(queue.is_empty() && queue.dequeue().is_none());
+    }
 
     #[test]
     fn test_vec_queue() {
@@ -109,6 +263,7 @@ mod tests {
         }
 
         assert!(queue.size() == 0);
+        check_queue_defaults::<Vec<_>>(lent_front);
     }
 
     #[test]
@@ -125,6 +280,24 @@ mod tests {
         }
 
         assert!(queue.size() == 0);
+        check_queue_defaults::<SingleLinkedList<_>>(lent_front);
+    }
+
+    #[test]
+    fn test_doubly_list_queue() {
+        let mut queue: DoublyLinkedList<_> = Queue::new();
+        for i in 0..10 {
+            queue.enqueue(i);
+        }
+
+        assert!(queue.size() == 10);
+
+        for i in 0..10 {
+            assert!(i == queue.dequeue().unwrap());
+        }
+
+        assert!(queue.size() == 0);
+        check_queue_defaults::<DoublyLinkedList<_>>(lent_front);
     }
 
     #[test]
@@ -139,11 +312,42 @@ mod tests {
         }
 
         assert!(queue.size() == 100);
+        // The tree can only lend its front as a guard, by its own `peek`.
+        assert!(Queue::peek(&queue).is_none());
 
         for i in 0..100 {
+            assert!(input[i] == *queue.peek().unwrap());
             assert!(input[i] == queue.dequeue().unwrap());
         }
 
         assert!(queue.size() == 0);
+        check_queue_defaults::<BinaryTree<_>>(|queue| queue.peek().map(|x| *x));
+    }
+
+    #[test]
+    fn test_bounded_queue() {
+        let mut queue: Bounded<SingleLinkedList<_>> = BoundedQueue::with_capacity(3);
+        for i in 0..3 {
+            assert!(queue.try_enqueue(i).is_ok());
+        }
+        assert!(queue.is_full() && queue.try_enqueue(3) == Err(3));
+        assert!(queue.dequeue() == Some(0) && !queue.is_full());
+        assert!(queue.try_enqueue(4).is_ok());
+        assert!(Queue::drain(&mut queue).eq([1, 2, 4]));
+        assert!(BoundedQueue::capacity(&queue) == 3);
+
+        // `clear` gives back the whole capacity.
+        queue.enqueue(5);
+        queue.clear();
+        assert!(queue.size() == 0 && (0..3).all(|i| queue.try_enqueue(i).is_ok()));
+        assert!(queue.is_full() && queue.into_inner().len() == 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bounded_queue_overflow() {
+        let mut queue: Bounded<Vec<_>> = BoundedQueue::with_capacity(1);
+        queue.enqueue(0);
+        queue.enqueue(1);
     }
 }
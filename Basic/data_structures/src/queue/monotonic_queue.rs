@@ -0,0 +1,271 @@
+use std::fmt::Debug;
+
+use super::{Queue, RingQueue};
+use crate::heap::{Compare, Natural, Reversed};
+
+/// A FIFO queue that also reports its smallest element in O(1).
+///
+/// Besides the elements, it keeps a deque of candidates: the positions of
+/// elements with no smaller-or-equal element behind them. Their elements
+/// never decrease from front to back, so the front candidate is the minimum,
+/// and each element enters and leaves the deque once, for O(1) amortized
+/// `enqueue` and `dequeue`.
+pub struct MonotonicQueue<T, C = Natural> {
+    items: RingQueue<T>,
+    candidates: RingQueue<usize>,
+    /// How many elements have been dequeued, so position `p` sits at
+    /// `items[p - dequeued]`.
+    dequeued: usize,
+    cmp: C,
+}
+
+impl<T: Ord> MonotonicQueue<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, C> MonotonicQueue<T, C> {
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.dequeued += self.items.len();
+        self.items.clear();
+        self.candidates.clear();
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// The smallest element according to the comparator. Of several equal
+    /// ones, the earliest enqueued is returned.
+    pub fn min(&self) -> Option<&T> {
+        let &position = self.candidates.front()?;
+        self.items.get(position - self.dequeued)
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        let x = self.items.pop_front()?;
+        if self.candidates.front() == Some(&self.dequeued) {
+            self.candidates.pop_front();
+        }
+        self.dequeued += 1;
+        Some(x)
+    }
+
+    pub fn iter(&self) -> super::ring_queue::Iter<'_, T> {
+        self.items.iter()
+    }
+}
+
+impl<T, C: Compare<T>> MonotonicQueue<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            items: RingQueue::new(),
+            candidates: RingQueue::new(),
+            dequeued: 0,
+            cmp,
+        }
+    }
+
+    pub fn enqueue(&mut self, x: T) {
+        while let Some(&last) = self.candidates.back() {
+            let last = self.items.get(last - self.dequeued);
+            if last.is_some_and(|last| self.cmp.compare(last, &x).is_le()) {
+                break;
+            }
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back(self.dequeued + self.items.len());
+        self.items.push_back(x);
+    }
+}
+
+impl<T, C: Compare<T> + Default> Queue<T> for MonotonicQueue<T, C> {
+    fn enqueue(&mut self, x: T) {
+        self.enqueue(x)
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.dequeue()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for MonotonicQueue<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, C> Debug for MonotonicQueue<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MonotonicQueue")
+            .field("items", &self.items)
+            .field("min", &self.min())
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for MonotonicQueue<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Self::default();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for MonotonicQueue<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.enqueue(x));
+    }
+}
+
+/// The minimum of every `window` consecutive elements of `iter`.
+///
+/// # Panics
+///
+/// Panics if `window` is zero.
+pub fn sliding_min<I>(iter: I, window: usize) -> SlidingWindow<I::IntoIter, Natural>
+where
+    I: IntoIterator,
+    I::Item: Ord + Clone,
+{
+    sliding_window_by(iter, window, Natural)
+}
+
+/// The maximum of every `window` consecutive elements of `iter`.
+///
+/// # Panics
+///
+/// Panics if `window` is zero.
+pub fn sliding_max<I>(iter: I, window: usize) -> SlidingWindow<I::IntoIter, Reversed>
+where
+    I: IntoIterator,
+    I::Item: Ord + Clone,
+{
+    sliding_window_by(iter, window, Reversed)
+}
+
+/// The minimum according to `cmp` of every `window` consecutive elements of
+/// `iter`, e.g. `f64::total_cmp` for samples.
+///
+/// # Panics
+///
+/// Panics if `window` is zero.
+pub fn sliding_window_by<I, C>(iter: I, window: usize, cmp: C) -> SlidingWindow<I::IntoIter, C>
+where
+    I: IntoIterator,
+    I::Item: Clone,
+    C: Compare<I::Item>,
+{
+    assert!(window > 0, "a sliding window needs a positive width");
+    SlidingWindow {
+        iter: iter.into_iter(),
+        queue: MonotonicQueue::with_comparator(cmp),
+        window,
+    }
+}
+
+/// Yields one element per full window, so `n - window + 1` of them for `n`
+/// input elements, and none if the input is shorter than the window.
+pub struct SlidingWindow<I: Iterator, C> {
+    iter: I,
+    queue: MonotonicQueue<I::Item, C>,
+    window: usize,
+}
+
+impl<I, C> Iterator for SlidingWindow<I, C>
+where
+    I: Iterator,
+    I::Item: Clone,
+    C: Compare<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.queue.len() == self.window {
+            self.queue.dequeue();
+        }
+        while self.queue.len() < self.window {
+            self.queue.enqueue(self.iter.next()?);
+        }
+        self.queue.min().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_against_scanning() {
+        let mut rng = rand::thread_rng();
+        let mut queue = MonotonicQueue::new();
+        let mut model = std::collections::VecDeque::new();
+        for _ in 0..3000 {
+            if rng.gen_bool(0.55) {
+                let x = rng.gen_range(-50..50);
+                queue.enqueue(x);
+                model.push_back(x);
+            } else {
+                assert!(queue.dequeue() == model.pop_front());
+            }
+            assert!(queue.min() == model.iter().min());
+            assert!(queue.len() == model.len() && queue.front() == model.front());
+            assert!(queue.candidates.len() <= queue.len());
+        }
+        queue.clear();
+        queue.enqueue(7);
+        assert!(queue.min() == Some(&7) && queue.dequeue() == Some(7));
+    }
+
+    #[test]
+    fn test_sliding_windows() {
+        let mut rng = rand::thread_rng();
+        let xs = (0..500)
+            .map(|_| rng.gen_range(0..100))
+            .collect::<Vec<i32>>();
+        for window in [1, 2, 7, 500, 501] {
+            let min = xs.windows(window).map(|w| *w.iter().min().unwrap());
+            assert!(sliding_min(xs.iter().copied(), window).eq(min));
+            let max = xs.windows(window).map(|w| *w.iter().max().unwrap());
+            assert!(sliding_max(&xs, window).copied().eq(max));
+        }
+    }
+
+    #[test]
+    fn test_sliding_window_over_samples() {
+        let samples = [0.5, -1.0, 2.0, 0.25, f64::INFINITY, -3.5];
+        let peaks = sliding_window_by(samples, 3, |a: &f64, b: &f64| b.total_cmp(a));
+        assert!(peaks.eq([2.0, 2.0, f64::INFINITY, f64::INFINITY]));
+        // Only the window's elements are held at any time.
+        let mut lows = sliding_window_by(samples, 2, f64::total_cmp);
+        assert!(lows.next() == Some(-1.0));
+        assert!(lows.queue.len() == 2);
+    }
+}
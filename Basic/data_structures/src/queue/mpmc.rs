@@ -0,0 +1,562 @@
+//! A bounded multi-producer, multi-consumer queue guarded by a `Mutex`, with
+//! blocking, non-blocking and timed operations.
+//!
+//! The queue disconnects when either side closes it, or when every sender or
+//! every receiver has been dropped. Receivers still drain the elements left
+//! behind by disconnected senders, while senders fail as soon as no receiver
+//! can take their elements anymore.
+
+use std::{
+    fmt::{Debug, Display},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use super::RingQueue;
+
+struct State<T> {
+    queue: RingQueue<T>,
+    senders: usize,
+    receivers: usize,
+    closed: bool,
+}
+
+impl<T> State<T> {
+    fn send_disconnected(&self) -> bool {
+        self.closed || self.receivers == 0
+    }
+
+    fn recv_disconnected(&self) -> bool {
+        self.closed || self.senders == 0
+    }
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+}
+
+impl<T> Shared<T> {
+    /// A panic while the lock is held cannot leave the state half-updated,
+    /// so a poisoned lock is still usable.
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+}
+
+/// Creates a queue holding at most `capacity` elements and returns its two
+/// ends. Both ends can be cloned to add producers or consumers.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn bounded<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "a bounded queue needs a positive capacity");
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: RingQueue::with_capacity(capacity),
+            senders: 1,
+            receivers: 1,
+            closed: false,
+        }),
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+        capacity,
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Enqueues `x`, waiting for room while the queue is full. Hands `x` back
+    /// if the queue is disconnected.
+    pub fn send(&self, x: T) -> Result<(), SendError<T>> {
+        let state = self.shared.lock();
+        let mut state = self
+            .shared
+            .not_full
+            .wait_while(state, |state| {
+                state.queue.len() == self.shared.capacity && !state.send_disconnected()
+            })
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.send_disconnected() {
+            return Err(SendError(x));
+        }
+        state.queue.push_back(x);
+        drop(state);
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    pub fn try_send(&self, x: T) -> Result<(), TrySendError<T>> {
+        let mut state = self.shared.lock();
+        if state.send_disconnected() {
+            return Err(TrySendError::Disconnected(x));
+        }
+        if state.queue.len() == self.shared.capacity {
+            return Err(TrySendError::Full(x));
+        }
+        state.queue.push_back(x);
+        drop(state);
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Like `send`, but gives up once `timeout` has passed.
+    pub fn send_timeout(&self, x: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        loop {
+            if state.send_disconnected() {
+                return Err(SendTimeoutError::Disconnected(x));
+            }
+            if state.queue.len() < self.shared.capacity {
+                break;
+            }
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return Err(SendTimeoutError::Timeout(x));
+            };
+            state = self
+                .shared
+                .not_full
+                .wait_timeout(state, left)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        state.queue.push_back(x);
+        drop(state);
+        self.shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Disconnects the queue for every sender and receiver. Elements already
+    /// in the queue can still be received.
+    pub fn close(&self) {
+        self.shared.close()
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.lock().send_disconnected()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.not_empty.notify_all();
+        }
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Dequeues an element, waiting while the queue is empty. Fails once the
+    /// queue is both empty and disconnected.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let state = self.shared.lock();
+        let mut state = self
+            .shared
+            .not_empty
+            .wait_while(state, |state| {
+                state.queue.is_empty() && !state.recv_disconnected()
+            })
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let x = state.queue.pop_front().ok_or(RecvError)?;
+        drop(state);
+        self.shared.not_full.notify_one();
+        Ok(x)
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.shared.lock();
+        let Some(x) = state.queue.pop_front() else {
+            return Err(if state.recv_disconnected() {
+                TryRecvError::Disconnected
+            } else {
+                TryRecvError::Empty
+            });
+        };
+        drop(state);
+        self.shared.not_full.notify_one();
+        Ok(x)
+    }
+
+    /// Like `recv`, but gives up once `timeout` has passed.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        let x = loop {
+            if let Some(x) = state.queue.pop_front() {
+                break x;
+            }
+            if state.recv_disconnected() {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return Err(RecvTimeoutError::Timeout);
+            };
+            state = self
+                .shared
+                .not_empty
+                .wait_timeout(state, left)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        };
+        drop(state);
+        self.shared.not_full.notify_one();
+        Ok(x)
+    }
+
+    /// Receives elements until the queue is empty and disconnected.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self)
+    }
+
+    /// Disconnects the queue for every sender and receiver. Elements already
+    /// in the queue can still be received.
+    pub fn close(&self) {
+        self.shared.close()
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.lock().recv_disconnected()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().receivers += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receivers -= 1;
+        if state.receivers == 0 {
+            drop(state);
+            self.shared.not_full.notify_all();
+        }
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+pub struct Iter<'a, T>(&'a Receiver<T>);
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The queue was disconnected; holds the element that could not be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    Full(T),
+    Disconnected(T),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendTimeoutError<T> {
+    Timeout(T),
+    Disconnected(T),
+}
+
+/// The queue is empty and disconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    Empty,
+    Disconnected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    Timeout,
+    Disconnected,
+}
+
+impl<T> Display for SendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sending on a disconnected queue")
+    }
+}
+
+impl<T> Display for TrySendError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "sending on a full queue"),
+            TrySendError::Disconnected(_) => write!(f, "sending on a disconnected queue"),
+        }
+    }
+}
+
+impl<T> Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendTimeoutError::Timeout(_) => write!(f, "timed out sending on a full queue"),
+            SendTimeoutError::Disconnected(_) => write!(f, "sending on a disconnected queue"),
+        }
+    }
+}
+
+impl Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "receiving on an empty and disconnected queue")
+    }
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "receiving on an empty queue"),
+            TryRecvError::Disconnected => {
+                write!(f, "receiving on an empty and disconnected queue")
+            }
+        }
+    }
+}
+
+impl Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => write!(f, "timed out receiving on an empty queue"),
+            RecvTimeoutError::Disconnected => {
+                write!(f, "receiving on an empty and disconnected queue")
+            }
+        }
+    }
+}
+
+impl<T: Debug> std::error::Error for SendError<T> {}
+impl<T: Debug> std::error::Error for TrySendError<T> {}
+impl<T: Debug> std::error::Error for SendTimeoutError<T> {}
+impl std::error::Error for RecvError {}
+impl std::error::Error for TryRecvError {}
+impl std::error::Error for RecvTimeoutError {}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Barrier, thread};
+
+    use super::*;
+
+    #[test]
+    fn test_many_producers_and_consumers() {
+        const PRODUCERS: usize = 8;
+        const CONSUMERS: usize = 8;
+        const PER_PRODUCER: usize = 20_000;
+
+        let (tx, rx) = bounded(16);
+        let barrier = Arc::new(Barrier::new(PRODUCERS + CONSUMERS));
+        let producers = (0..PRODUCERS)
+            .map(|p| {
+                let (tx, barrier) = (tx.clone(), barrier.clone());
+                thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..PER_PRODUCER {
+                        tx.send((p, i)).unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(tx);
+        let consumers = (0..CONSUMERS)
+            .map(|_| {
+                let (rx, barrier) = (rx.clone(), barrier.clone());
+                thread::spawn(move || {
+                    barrier.wait();
+                    // Each consumer sees every producer's elements in order.
+                    let mut last = [None; PRODUCERS];
+                    let mut received = Vec::new();
+                    for (p, i) in &rx {
+                        assert!(last[p].is_none_or(|last| last < i));
+                        last[p] = Some(i);
+                        received.push((p, i));
+                    }
+                    received
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(rx);
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let mut received = consumers
+            .into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect::<Vec<_>>();
+        received.sort_unstable();
+        let expected = (0..PRODUCERS)
+            .flat_map(|p| (0..PER_PRODUCER).map(move |i| (p, i)))
+            .collect::<Vec<_>>();
+        assert!(received == expected);
+    }
+
+    #[test]
+    fn test_try_operations() {
+        let (tx, rx) = bounded(2);
+        assert!(rx.try_recv() == Err(TryRecvError::Empty));
+        assert!(tx.try_send(1).is_ok() && tx.try_send(2).is_ok());
+        assert!(tx.try_send(3) == Err(TrySendError::Full(3)));
+        assert!(tx.len() == 2 && rx.capacity() == 2);
+        assert!(rx.try_recv() == Ok(1));
+        assert!(tx.try_send(3).is_ok());
+        assert!(rx.iter().take(2).eq([2, 3]));
+    }
+
+    #[test]
+    fn test_timeouts() {
+        let (tx, rx) = bounded(1);
+        let timeout = Duration::from_millis(20);
+        let start = Instant::now();
+        assert!(rx.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout));
+        assert!(start.elapsed() >= timeout);
+
+        tx.send(0).unwrap();
+        let start = Instant::now();
+        assert!(tx.send_timeout(1, timeout) == Err(SendTimeoutError::Timeout(1)));
+        assert!(start.elapsed() >= timeout);
+
+        // A receiver on another thread makes room before the timeout.
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            (rx.recv().unwrap(), rx)
+        });
+        assert!(tx.send_timeout(1, Duration::from_secs(10)).is_ok());
+        let (x, rx) = handle.join().unwrap();
+        assert!(x == 0 && rx.try_recv() == Ok(1));
+    }
+
+    #[test]
+    fn test_close_wakes_blocked_threads() {
+        let (tx, rx) = bounded(1);
+        tx.send(0).unwrap();
+
+        let blocked_sender = {
+            let tx = tx.clone();
+            thread::spawn(move || tx.send(1))
+        };
+        thread::sleep(Duration::from_millis(10));
+        rx.close();
+        assert!(blocked_sender.join().unwrap() == Err(SendError(1)));
+        assert!(tx.is_disconnected() && tx.try_send(2) == Err(TrySendError::Disconnected(2)));
+
+        // What was sent before closing can still be received.
+        assert!(rx.recv() == Ok(0));
+        assert!(rx.recv() == Err(RecvError));
+        assert!(rx.try_recv() == Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn test_dropping_one_side_disconnects() {
+        let (tx, rx) = bounded(4);
+        let tx2 = tx.clone();
+        tx.send(1).unwrap();
+        drop(tx);
+        assert!(!rx.is_disconnected());
+
+        let blocked_receiver = thread::spawn(move || rx.iter().collect::<Vec<_>>());
+        thread::sleep(Duration::from_millis(10));
+        tx2.send(2).unwrap();
+        drop(tx2);
+        assert!(blocked_receiver.join().unwrap() == [1, 2]);
+
+        let (tx, rx) = bounded(1);
+        drop(rx);
+        assert!(tx.send(0) == Err(SendError(0)));
+        assert!(tx.send_timeout(0, Duration::ZERO) == Err(SendTimeoutError::Disconnected(0)));
+    }
+}
@@ -0,0 +1,356 @@
+use std::fmt::Debug;
+
+use crate::{deque::Deque, queue::Queue};
+
+/// A double-ended queue stored in a ring buffer, so pushing and popping at
+/// either end is O(1).
+///
+/// A growable ring doubles its buffer when full. An overwriting ring keeps a
+/// fixed capacity and, when full, makes room by dropping the element at the
+/// opposite end, which suits sliding telemetry windows.
+#[derive(Clone)]
+pub struct RingQueue<T> {
+    buf: Vec<Option<T>>,
+    head: usize,
+    len: usize,
+    overwrite: bool,
+}
+
+impl<T> RingQueue<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: (0..capacity).map(|_| None).collect(),
+            head: 0,
+            len: 0,
+            overwrite: false,
+        }
+    }
+
+    /// Creates a ring that holds at most `capacity` elements and overwrites
+    /// the oldest one when full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn overwriting(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "an overwriting ring needs a positive capacity"
+        );
+        Self {
+            overwrite: true,
+            ..Self::with_capacity(capacity)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == self.buf.len()
+    }
+
+    pub fn is_overwriting(&self) -> bool {
+        self.overwrite
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+        self.head = 0;
+    }
+
+    fn index(&self, i: usize) -> usize {
+        (self.head + i) % self.buf.len()
+    }
+
+    /// Moves the elements into a buffer twice as large, starting at slot 0.
+    fn grow(&mut self) {
+        let capacity = (self.buf.len() * 2).max(4);
+        let mut buf: Vec<Option<T>> = Vec::with_capacity(capacity);
+        for i in 0..self.len {
+            let index = self.index(i);
+            buf.push(self.buf[index].take());
+        }
+        buf.resize_with(capacity, || None);
+        self.buf = buf;
+        self.head = 0;
+    }
+
+    /// Pushes `x` to the back. When an overwriting ring is full, the front
+    /// element is dropped to make room and returned.
+    pub fn push_back(&mut self, x: T) -> Option<T> {
+        if self.is_full() {
+            if self.overwrite {
+                let old = self.buf[self.head].replace(x);
+                self.head = self.index(1);
+                return old;
+            }
+            self.grow();
+        }
+        let index = self.index(self.len);
+        self.buf[index] = Some(x);
+        self.len += 1;
+        None
+    }
+
+    /// Pushes `x` to the front. When an overwriting ring is full, the back
+    /// element is dropped to make room and returned.
+    pub fn push_front(&mut self, x: T) -> Option<T> {
+        if self.is_full() && !self.overwrite {
+            self.grow();
+        }
+        self.head = self.index(self.buf.len() - 1);
+        if self.is_full() {
+            return self.buf[self.head].replace(x);
+        }
+        self.buf[self.head] = Some(x);
+        self.len += 1;
+        None
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let x = self.buf[self.head].take();
+        self.head = self.index(1);
+        self.len -= 1;
+        x
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        let index = self.index(self.len);
+        self.buf[index].take()
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len {
+            return None;
+        }
+        self.buf[self.index(i)].as_ref()
+    }
+
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len {
+            return None;
+        }
+        let index = self.index(i);
+        self.buf[index].as_mut()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            ring: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+}
+
+impl<T> Default for RingQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for RingQueue<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> FromIterator<T> for RingQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut ring = Self::new();
+        iter.into_iter().for_each(|x| {
+            ring.push_back(x);
+        });
+        ring
+    }
+}
+
+impl<T> Queue<T> for RingQueue<T> {
+    /// In an overwriting ring this drops the oldest element when full.
+    fn enqueue(&mut self, x: T) {
+        self.push_back(x);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn new() -> Self {
+        RingQueue::new()
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T> Deque<T> for RingQueue<T> {
+    fn push_front(&mut self, x: T) {
+        self.push_front(x);
+    }
+
+    fn push_back(&mut self, x: T) {
+        self.push_back(x);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn size(&self) -> usize {
+        self.len
+    }
+
+    fn new() -> Self {
+        RingQueue::new()
+    }
+}
+
+pub struct Iter<'a, T> {
+    ring: &'a RingQueue<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.ring.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.back - self.front, Some(self.back - self.front))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.ring.get(self.back)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_random_ops_against_vec_deque() {
+        let mut rng = rand::thread_rng();
+        let mut ring = RingQueue::new();
+        let mut model = VecDeque::new();
+
+        for i in 0..10000 {
+            match rng.gen_range(0..4) {
+                0 => {
+                    assert!(ring.push_back(i).is_none());
+                    model.push_back(i);
+                }
+                1 => {
+                    assert!(ring.push_front(i).is_none());
+                    model.push_front(i);
+                }
+                2 => assert!(ring.pop_front() == model.pop_front()),
+                _ => assert!(ring.pop_back() == model.pop_back()),
+            }
+            assert!(ring.len() == model.len() && ring.capacity() >= ring.len());
+            assert!(ring.front() == model.front() && ring.back() == model.back());
+        }
+        assert!(ring.iter().eq(model.iter()));
+        assert!(ring.iter().rev().eq(model.iter().rev()));
+    }
+
+    #[test]
+    fn test_overwriting() {
+        let mut ring = RingQueue::overwriting(3);
+        for i in 0..3 {
+            assert!(ring.push_back(i).is_none());
+        }
+        assert!(ring.is_full() && ring.capacity() == 3);
+        assert!(ring.push_back(3) == Some(0));
+        assert!(ring.push_back(4) == Some(1));
+        assert!(ring.iter().eq([2, 3, 4].iter()));
+
+        assert!(ring.push_front(1) == Some(4));
+        assert!(ring.iter().eq([1, 2, 3].iter()));
+        assert!(ring.pop_back() == Some(3));
+        assert!(ring.push_front(0).is_none());
+        assert!(ring.iter().eq([0, 1, 2].iter()) && ring.capacity() == 3);
+
+        for i in 0..100 {
+            ring.enqueue(i);
+        }
+        assert!(Queue::drain(&mut ring).eq(97..100));
+    }
+
+    #[test]
+    fn test_wrap_around_growth() {
+        let mut ring = RingQueue::with_capacity(4);
+        ring.push_back(1);
+        ring.push_back(2);
+        ring.push_front(0);
+        ring.push_front(-1);
+        assert!(ring.capacity() == 4);
+        ring.push_back(3);
+        assert!(ring.capacity() == 8);
+        *ring.get_mut(4).unwrap() = 30;
+        assert!(ring.iter().eq([-1, 0, 1, 2, 30].iter()));
+        assert!(ring.get(5).is_none());
+        assert!(format!("{ring:?}") == "[-1, 0, 1, 2, 30]");
+    }
+}
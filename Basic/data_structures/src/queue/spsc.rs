@@ -0,0 +1,275 @@
+//! A bounded single-producer, single-consumer ring buffer that needs no lock.
+//!
+//! `head` and `tail` count every pop and push since the ring was created, so
+//! `tail - head` is the number of elements. The buffer has a power of two
+//! slots, at least the capacity, and a counter's low bits pick its slot, so
+//! the mapping stays consistent when the counters wrap around.
+//! Each counter is written by one side only and published with `Release`;
+//! the other side reads it with `Acquire` before touching the slot it
+//! guards.
+
+use std::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+struct Ring<T> {
+    buf: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    capacity: usize,
+    /// Written by the consumer only.
+    head: AtomicUsize,
+    /// Written by the producer only.
+    tail: AtomicUsize,
+}
+
+// The producer and consumer never touch the same slot at the same time, so
+// sharing the ring only requires the elements to be sendable.
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    fn slot(&self, counter: usize) -> *mut MaybeUninit<T> {
+        self.buf[counter & (self.buf.len() - 1)].get()
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let (head, tail) = (*self.head.get_mut(), *self.tail.get_mut());
+        let mut counter = head;
+        while counter != tail {
+            unsafe { (*self.slot(counter)).assume_init_drop() };
+            counter = counter.wrapping_add(1);
+        }
+    }
+}
+
+/// Creates a ring holding at most `capacity` elements and returns its two
+/// ends. Neither end can be cloned.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn ring<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    ring_from(capacity, 0)
+}
+
+/// Like `ring`, with both counters starting at `start`.
+fn ring_from<T>(capacity: usize, start: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "a ring needs a positive capacity");
+    let ring = Arc::new(Ring {
+        buf: (0..capacity.next_power_of_two())
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect(),
+        capacity,
+        head: AtomicUsize::new(start),
+        tail: AtomicUsize::new(start),
+    });
+    (
+        Producer {
+            ring: ring.clone(),
+            head: start,
+        },
+        Consumer { ring, tail: start },
+    )
+}
+
+pub struct Producer<T> {
+    ring: Arc<Ring<T>>,
+    /// The last `head` seen, so a push only reloads it when the ring looks full.
+    head: usize,
+}
+
+impl<T> Producer<T> {
+    /// Enqueues `x`, or hands it back if the ring is full.
+    pub fn push(&mut self, x: T) -> Result<(), T> {
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        if tail.wrapping_sub(self.head) == self.ring.capacity() {
+            self.head = self.ring.head.load(Ordering::Acquire);
+            if tail.wrapping_sub(self.head) == self.ring.capacity() {
+                return Err(x);
+            }
+        }
+        unsafe { (*self.ring.slot(tail)).write(x) };
+        self.ring
+            .tail
+            .store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Whether the consumer has been dropped, so nothing pushed from now on
+    /// will ever be popped.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+}
+
+impl<T> Debug for Producer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Producer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+pub struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+    /// The last `tail` seen, so a pop only reloads it when the ring looks empty.
+    tail: usize,
+}
+
+impl<T> Consumer<T> {
+    pub fn pop(&mut self) -> Option<T> {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        if head == self.tail {
+            self.tail = self.ring.tail.load(Ordering::Acquire);
+            if head == self.tail {
+                return None;
+            }
+        }
+        let x = unsafe { (*self.ring.slot(head)).assume_init_read() };
+        self.ring
+            .head
+            .store(head.wrapping_add(1), Ordering::Release);
+        Some(x)
+    }
+
+    /// Whether the producer has been dropped, so once the ring is empty it
+    /// stays empty.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+}
+
+impl<T> Debug for Consumer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Consumer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, thread};
+
+    use super::*;
+
+    #[test]
+    fn test_fill_and_drain() {
+        let (mut tx, mut rx) = ring(3);
+        assert!(rx.pop().is_none());
+        for round in 0..5 {
+            for i in 0..3 {
+                assert!(tx.push(round * 3 + i).is_ok());
+            }
+            assert!(tx.push(-1) == Err(-1));
+            assert!(tx.len() == 3 && rx.len() == 3);
+            for i in 0..3 {
+                assert!(rx.pop() == Some(round * 3 + i));
+            }
+            assert!(rx.pop().is_none() && rx.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_counters_wrap_around() {
+        // With 3 slots taken modulo the capacity, the counters `usize::MAX`
+        // and 0 would share a slot while both are live.
+        let counter = Rc::new(());
+        let (mut tx, mut rx) = ring_from(3, usize::MAX - 4);
+        for i in 0..20 {
+            while tx.push((i, counter.clone())).is_err() {
+                assert!(rx.pop().is_some_and(|(x, _)| x == i - 3));
+            }
+            assert!(tx.len() <= 3);
+        }
+        assert!(rx.len() == 3 && Rc::strong_count(&counter) == 4);
+        assert!(rx.pop().is_some_and(|(x, _)| x == 17));
+        drop((tx, rx));
+        assert!(Rc::strong_count(&counter) == 1);
+    }
+
+    #[test]
+    fn test_threads_keep_order() {
+        const N: usize = 1_000_000;
+        let (mut tx, mut rx) = ring(64);
+        let producer = thread::spawn(move || {
+            for i in 0..N {
+                let mut x = i;
+                while let Err(back) = tx.push(x) {
+                    x = back;
+                    thread::yield_now();
+                }
+            }
+        });
+        let mut expected = 0;
+        while expected < N {
+            match rx.pop() {
+                Some(x) => {
+                    assert!(x == expected);
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        producer.join().unwrap();
+        assert!(rx.pop().is_none() && rx.is_abandoned());
+    }
+
+    #[test]
+    fn test_drops_remaining_elements() {
+        let counter = Rc::new(());
+        let (mut tx, mut rx) = ring(4);
+        for _ in 0..4 {
+            tx.push(counter.clone()).unwrap();
+        }
+        rx.pop();
+        tx.push(counter.clone()).unwrap();
+        drop(rx);
+        assert!(tx.is_abandoned());
+        assert!(Rc::strong_count(&counter) == 5);
+        drop(tx);
+        assert!(Rc::strong_count(&counter) == 1);
+    }
+}
@@ -0,0 +1,167 @@
+use super::Queue;
+use crate::stack::Stack;
+
+/// A queue made of two stacks: elements are pushed onto `input`, and when
+/// `output` runs dry, `input` is poured into it, which reverses the elements
+/// into FIFO order.
+///
+/// Every element is pushed and popped at most twice, so `enqueue` and
+/// `dequeue` are O(1) amortized, though a single `dequeue` may pour O(n)
+/// elements. `output` is refilled as soon as it empties, so the front of a
+/// non-empty queue is always on top of `output` and `peek` needs no `&mut`.
+#[derive(Debug, Clone)]
+pub struct StackQueue<I, O = I> {
+    input: I,
+    output: O,
+}
+
+impl<I, O> StackQueue<I, O> {
+    /// Returns the input and output stacks.
+    pub fn into_inner(self) -> (I, O) {
+        (self.input, self.output)
+    }
+}
+
+impl<T, I: Stack<T>, O: Stack<T>> Queue<T> for StackQueue<I, O> {
+    fn enqueue(&mut self, x: T) {
+        if self.output.is_empty() {
+            self.output.push(x);
+        } else {
+            self.input.push(x);
+        }
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        let x = self.output.pop()?;
+        if self.output.is_empty() {
+            while let Some(y) = self.input.pop() {
+                self.output.push(y);
+            }
+        }
+        Some(x)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.output.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.input.size() + self.output.size()
+    }
+
+    fn new() -> Self {
+        Self {
+            input: I::new(),
+            output: O::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.output.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.input.clear();
+        self.output.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::linked_list::{DoublyLinkedList, SingleLinkedList};
+
+    /// Counts the pushes and pops made on a stack.
+    struct Counted<S> {
+        inner: S,
+        ops: usize,
+    }
+
+    impl<T, S: Stack<T>> Stack<T> for Counted<S> {
+        fn push(&mut self, x: T) {
+            self.ops += 1;
+            self.inner.push(x)
+        }
+
+        fn pop(&mut self) -> Option<T> {
+            self.ops += 1;
+            self.inner.pop()
+        }
+
+        fn peek(&self) -> Option<&T> {
+            self.inner.peek()
+        }
+
+        fn size(&self) -> usize {
+            self.inner.size()
+        }
+
+        fn new() -> Self {
+            Counted {
+                inner: S::new(),
+                ops: 0,
+            }
+        }
+    }
+
+    fn check_against_model<Q: Queue<i32>>() {
+        let mut rng = rand::thread_rng();
+        let mut queue = Q::new();
+        let mut model = std::collections::VecDeque::new();
+        for _ in 0..2000 {
+            if rng.gen_bool(0.55) {
+                let x = rng.gen_range(0..1000);
+                queue.enqueue(x);
+                model.push_back(x);
+            } else {
+                assert!(queue.dequeue() == model.pop_front());
+            }
+            assert!(queue.peek().copied() == model.front().copied());
+            assert!(queue.size() == model.len());
+        }
+        assert!(queue.drain().eq(model));
+    }
+
+    #[test]
+    fn test_against_model() {
+        check_against_model::<StackQueue<Vec<_>>>();
+        check_against_model::<StackQueue<SingleLinkedList<_>>>();
+        check_against_model::<StackQueue<Vec<_>, DoublyLinkedList<_>>>();
+    }
+
+    #[test]
+    fn test_amortized_operation_count() {
+        let mut rng = rand::thread_rng();
+        let mut queue = StackQueue::<Counted<Vec<_>>>::new();
+        let mut enqueued = 0;
+        let mut last = None;
+        for i in 0..10_000 {
+            // Long runs of each kind make some dequeues pour many elements.
+            if (i / 500) % 2 == 0 || rng.gen_bool(0.2) {
+                queue.enqueue(i);
+                enqueued += 1;
+            } else if let Some(x) = queue.dequeue() {
+                assert!(last.is_none_or(|last| last < x));
+                last = Some(x);
+            }
+            // Each element costs at most two pushes and two pops.
+            let (input, output) = (&queue.input, &queue.output);
+            assert!(input.ops + output.ops <= 4 * enqueued);
+        }
+        let (input, output) = queue.into_inner();
+        assert!(input.ops > 0 && output.ops > 0);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut queue = StackQueue::<Vec<_>>::new();
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.clear();
+        assert!(queue.is_empty() && queue.peek().is_none());
+        queue.enqueue(3);
+        assert!(queue.peek() == Some(&3) && queue.dequeue() == Some(3));
+    }
+}
@@ -0,0 +1,457 @@
+use std::fmt::Debug;
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+/// Enough levels for the whole `u64` range of ticks.
+const LEVELS: usize = (u64::BITS as usize).div_ceil(SLOT_BITS as usize);
+/// The list of expired entries comes after the slots of every level.
+const EXPIRED: usize = LEVELS * SLOTS;
+
+/// Identifies an item scheduled on a `TimerWheel`. It stops resolving once
+/// the item is cancelled or handed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: usize,
+    generation: u64,
+}
+
+struct Entry<T> {
+    value: Option<T>,
+    generation: u64,
+    deadline: u64,
+    /// Which list the entry is in: a slot, or `EXPIRED`.
+    list: usize,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct List {
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+/// A hierarchical timing wheel: a delay queue that hands out items once a
+/// logical clock reaches their deadline.
+///
+/// Level `k` has 64 slots of 64^k ticks each. An item goes to the level of
+/// the highest base-64 digit in which its deadline differs from the clock,
+/// so scheduling and cancelling are O(1). When the clock reaches a slot of
+/// a higher level, its items cascade down to lower levels, each item at most
+/// once per level.
+///
+/// The clock only moves through `advance` and `poll`, which makes schedules
+/// deterministic. Items come out in deadline order, and items with the same
+/// deadline in the order they were scheduled.
+pub struct TimerWheel<T> {
+    entries: Vec<Entry<T>>,
+    free: Vec<usize>,
+    lists: Vec<List>,
+    /// One bit per non-empty slot of each level.
+    occupied: [u64; LEVELS],
+    now: u64,
+    len: usize,
+}
+
+impl<T> TimerWheel<T> {
+    pub fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    /// Creates a wheel whose clock reads `now`.
+    pub fn starting_at(now: u64) -> Self {
+        Self {
+            entries: Vec::new(),
+            free: Vec::new(),
+            lists: vec![List::default(); EXPIRED + 1],
+            occupied: [0; LEVELS],
+            now,
+            len: 0,
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Drops every item. Their handles stop resolving, even once their
+    /// entries are reused.
+    pub fn clear(&mut self) {
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            if entry.value.take().is_some() {
+                entry.generation += 1;
+                self.free.push(index);
+            }
+        }
+        self.lists.fill(List::default());
+        self.occupied = [0; LEVELS];
+        self.len = 0;
+    }
+
+    /// Schedules `value` for tick `deadline`. A deadline that has already
+    /// passed expires at once.
+    pub fn schedule(&mut self, deadline: u64, value: T) -> Handle {
+        let index = match self.free.pop() {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                entry.value = Some(value);
+                entry.deadline = deadline;
+                index
+            }
+            None => {
+                self.entries.push(Entry {
+                    value: Some(value),
+                    generation: 0,
+                    deadline,
+                    list: EXPIRED,
+                    prev: None,
+                    next: None,
+                });
+                self.entries.len() - 1
+            }
+        };
+        self.len += 1;
+        self.place(index);
+        Handle {
+            index,
+            generation: self.entries[index].generation,
+        }
+    }
+
+    /// Schedules `value` for `delay` ticks from now.
+    pub fn schedule_after(&mut self, delay: u64, value: T) -> Handle {
+        self.schedule(self.now.saturating_add(delay), value)
+    }
+
+    /// Unschedules the item behind `handle` and returns it, or `None` if the
+    /// handle no longer resolves.
+    pub fn cancel(&mut self, handle: &Handle) -> Option<T> {
+        let index = self.resolve(handle)?;
+        self.unlink(index);
+        Some(self.release(index))
+    }
+
+    /// Moves the item behind `handle` to a new deadline, returning whether
+    /// the handle resolved. The handle stays valid.
+    pub fn reschedule(&mut self, handle: &Handle, deadline: u64) -> bool {
+        let Some(index) = self.resolve(handle) else {
+            return false;
+        };
+        self.unlink(index);
+        self.entries[index].deadline = deadline;
+        self.place(index);
+        true
+    }
+
+    pub fn get(&self, handle: &Handle) -> Option<&T> {
+        let index = self.resolve(handle)?;
+        self.entries[index].value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, handle: &Handle) -> Option<&mut T> {
+        let index = self.resolve(handle)?;
+        self.entries[index].value.as_mut()
+    }
+
+    pub fn deadline(&self, handle: &Handle) -> Option<u64> {
+        let index = self.resolve(handle)?;
+        Some(self.entries[index].deadline)
+    }
+
+    /// The earliest deadline of any scheduled item, or the current tick if
+    /// some item has already expired.
+    pub fn next_deadline(&self) -> Option<u64> {
+        if self.lists[EXPIRED].head.is_some() {
+            return Some(self.now);
+        }
+        // Occupied slots of lower levels always end before those of higher
+        // levels begin, so the earliest item is in the first occupied slot.
+        let (_, list) = self.next_slot()?;
+        let mut next = self.lists[list].head;
+        let mut earliest = u64::MAX;
+        while let Some(index) = next {
+            earliest = earliest.min(self.entries[index].deadline);
+            next = self.entries[index].next;
+        }
+        Some(earliest)
+    }
+
+    /// Hands out the next item whose deadline is at most `until`, moving the
+    /// clock to that deadline. Once no such item is left, the clock is moved
+    /// to `until` and `None` is returned. The clock never moves backwards.
+    pub fn poll(&mut self, until: u64) -> Option<T> {
+        loop {
+            if let Some(index) = self.lists[EXPIRED].head {
+                self.unlink(index);
+                self.now = self.now.max(self.entries[index].deadline);
+                return Some(self.release(index));
+            }
+            match self.next_slot() {
+                Some((start, list)) if start <= until => {
+                    self.now = self.now.max(start);
+                    self.cascade(list);
+                }
+                _ => {
+                    self.now = self.now.max(until);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Moves the clock to `to` and returns every item that expired on the
+    /// way, in deadline order.
+    pub fn advance(&mut self, to: u64) -> Vec<T> {
+        std::iter::from_fn(|| self.poll(to)).collect()
+    }
+
+    /// Moves the clock `ticks` ahead; see `advance`.
+    pub fn advance_by(&mut self, ticks: u64) -> Vec<T> {
+        self.advance(self.now.saturating_add(ticks))
+    }
+
+    fn resolve(&self, handle: &Handle) -> Option<usize> {
+        let entry = self.entries.get(handle.index)?;
+        (entry.generation == handle.generation && entry.value.is_some()).then_some(handle.index)
+    }
+
+    fn release(&mut self, index: usize) -> T {
+        let entry = &mut self.entries[index];
+        entry.generation += 1;
+        self.free.push(index);
+        self.len -= 1;
+        entry.value.take().expect("a linked entry holds a value")
+    }
+
+    /// The start tick and list of the first occupied slot.
+    fn next_slot(&self) -> Option<(u64, usize)> {
+        let level = self.occupied.iter().position(|&bits| bits != 0)?;
+        let shift = SLOT_BITS * level as u32;
+        // Items in a level are ahead of the clock's digit in that level.
+        let digit = (self.now >> shift) as usize % SLOTS;
+        let ahead = self.occupied[level] & (u64::MAX << digit);
+        let slot = ahead.trailing_zeros() as usize;
+        let block = self
+            .now
+            .checked_shr(shift + SLOT_BITS)
+            .map_or(0, |high| high << (shift + SLOT_BITS));
+        Some((block + ((slot as u64) << shift), level * SLOTS + slot))
+    }
+
+    /// Puts the entry in the list its deadline calls for, relative to the
+    /// current clock.
+    fn place(&mut self, index: usize) {
+        let deadline = self.entries[index].deadline;
+        let list = if deadline <= self.now {
+            EXPIRED
+        } else {
+            let level = (u64::BITS - 1 - (deadline ^ self.now).leading_zeros()) / SLOT_BITS;
+            let slot = (deadline >> (SLOT_BITS * level)) as usize % SLOTS;
+            self.occupied[level as usize] |= 1 << slot;
+            level as usize * SLOTS + slot
+        };
+        let entry = &mut self.entries[index];
+        entry.list = list;
+        entry.prev = self.lists[list].tail;
+        entry.next = None;
+        match self.lists[list].tail {
+            Some(tail) => self.entries[tail].next = Some(index),
+            None => self.lists[list].head = Some(index),
+        }
+        self.lists[list].tail = Some(index);
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let Entry {
+            list, prev, next, ..
+        } = self.entries[index];
+        match prev {
+            Some(prev) => self.entries[prev].next = next,
+            None => self.lists[list].head = next,
+        }
+        match next {
+            Some(next) => self.entries[next].prev = prev,
+            None => self.lists[list].tail = prev,
+        }
+        if self.lists[list].head.is_none() && list != EXPIRED {
+            self.occupied[list / SLOTS] &= !(1 << (list % SLOTS));
+        }
+    }
+
+    /// Empties a slot the clock has reached, placing its entries anew.
+    fn cascade(&mut self, list: usize) {
+        let mut next = self.lists[list].head.take();
+        self.lists[list].tail = None;
+        self.occupied[list / SLOTS] &= !(1 << (list % SLOTS));
+        while let Some(index) = next {
+            next = self.entries[index].next;
+            self.place(index);
+        }
+    }
+}
+
+impl<T> Default for TimerWheel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for TimerWheel<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimerWheel")
+            .field("now", &self.now)
+            .field("len", &self.len)
+            .field("next_deadline", &self.next_deadline())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_against_model() {
+        let mut rng = rand::thread_rng();
+        let mut wheel = TimerWheel::starting_at(1000);
+        // Keyed by deadline, then by when the item was last (re)scheduled,
+        // which is the order items come out in.
+        let mut model = BTreeMap::new();
+        let mut scheduled = Vec::new();
+        for step in 0..20_000u64 {
+            let now = wheel.now();
+            match rng.gen_range(0..10) {
+                0..=4 => {
+                    let delay = match rng.gen_range(0..4) {
+                        0 => rng.gen_range(0..64),
+                        1 => rng.gen_range(0..5000),
+                        2 => rng.gen_range(0..1 << 30),
+                        _ => rng.gen_range(0..u64::MAX - now),
+                    };
+                    // Some deadlines have already passed.
+                    let deadline = (now + delay).saturating_sub(rng.gen_range(0..2) * 10);
+                    let handle = wheel.schedule(deadline, step);
+                    let key = (deadline.max(now), step);
+                    model.insert(key, step);
+                    scheduled.push((key, handle));
+                }
+                5 if !scheduled.is_empty() => {
+                    let k = rng.gen_range(0..scheduled.len());
+                    let (key, handle) = scheduled.swap_remove(k);
+                    assert!(wheel.cancel(&handle) == model.remove(&key));
+                    assert!(wheel.get(&handle).is_none());
+                }
+                6 if !scheduled.is_empty() => {
+                    let k = rng.gen_range(0..scheduled.len());
+                    let (key, handle) = scheduled[k];
+                    let deadline = now + rng.gen_range(0..100_000);
+                    let value = model.remove(&key);
+                    assert!(wheel.reschedule(&handle, deadline) == value.is_some());
+                    if let Some(value) = value {
+                        scheduled[k].0 = (deadline, step);
+                        model.insert(scheduled[k].0, value);
+                    }
+                }
+                _ => {
+                    let to = match rng.gen_range(0..3) {
+                        0 => now + rng.gen_range(0..64),
+                        1 => now + rng.gen_range(0..100_000),
+                        _ => now.saturating_add(rng.gen_range(0..1 << 40)),
+                    };
+                    let expired = wheel.advance(to);
+                    let mut expected = Vec::new();
+                    while let Some(entry) = model.first_entry() {
+                        if entry.key().0 > to {
+                            break;
+                        }
+                        expected.push(entry.remove());
+                    }
+                    assert!(expired == expected);
+                    assert!(wheel.now() == to);
+                }
+            }
+            assert!(wheel.len() == model.len());
+            let next = model.keys().next().map(|&(deadline, _)| deadline);
+            assert!(wheel.next_deadline() == next);
+        }
+        let rest = wheel.advance(u64::MAX);
+        assert!(rest.into_iter().eq(model.into_values()));
+    }
+
+    #[test]
+    fn test_logical_clock() {
+        let mut wheel = TimerWheel::new();
+        let a = wheel.schedule(5, "a");
+        wheel.schedule_after(70, "b");
+        wheel.schedule(5, "c");
+        assert!(wheel.next_deadline() == Some(5));
+        assert!(wheel.advance(4).is_empty() && wheel.now() == 4);
+        assert!(wheel.deadline(&a) == Some(5));
+
+        // `poll` moves the clock only as far as the item it hands out.
+        assert!(wheel.poll(100) == Some("a") && wheel.now() == 5);
+        assert!(wheel.get(&a).is_none() && !wheel.reschedule(&a, 9));
+        assert!(wheel.poll(100) == Some("c"));
+        assert!(wheel.poll(100) == Some("b") && wheel.now() == 70);
+        assert!(wheel.poll(100).is_none() && wheel.now() == 100);
+
+        // The clock never moves backwards, and past deadlines expire at once.
+        assert!(wheel.advance(50).is_empty() && wheel.now() == 100);
+        wheel.schedule(20, "late");
+        assert!(wheel.next_deadline() == Some(100));
+        assert!(wheel.advance_by(0) == ["late"]);
+    }
+
+    #[test]
+    fn test_cancel_and_reuse() {
+        let mut wheel = TimerWheel::new();
+        let handles = (0..100)
+            .map(|i| wheel.schedule(i * 1000, i))
+            .collect::<Vec<_>>();
+        for handle in handles.iter().step_by(2) {
+            assert!(wheel.cancel(handle).is_some());
+            assert!(wheel.cancel(handle).is_none());
+        }
+        // Reused entries don't resolve old handles.
+        let fresh = wheel.schedule(1, 1000);
+        assert!(wheel.get(&handles[0]).is_none() && wheel.get(&fresh) == Some(&1000));
+        *wheel.get_mut(&fresh).unwrap() += 1;
+
+        let expired = wheel.advance(u64::MAX);
+        let expected = std::iter::once(1001).chain((1..100).step_by(2));
+        assert!(expired.into_iter().eq(expected) && wheel.is_empty());
+    }
+
+    #[test]
+    fn test_extreme_deadlines() {
+        let mut wheel = TimerWheel::starting_at(u64::MAX - 10);
+        wheel.schedule(u64::MAX, 2);
+        wheel.schedule(u64::MAX - 1, 1);
+        assert!(wheel.advance(u64::MAX) == [1, 2]);
+
+        let mut wheel = TimerWheel::new();
+        wheel.schedule(u64::MAX, 3);
+        wheel.schedule(1 << 63, 2);
+        wheel.schedule(1, 1);
+        assert!(wheel.next_deadline() == Some(1));
+        assert!(wheel.advance(u64::MAX - 1) == [1, 2]);
+        assert!(wheel.next_deadline() == Some(u64::MAX));
+        let handle = wheel.schedule(u64::MAX, 4);
+        wheel.clear();
+        assert!(wheel.is_empty() && wheel.next_deadline().is_none());
+        wheel.schedule(u64::MAX, 5);
+        assert!(wheel.get(&handle).is_none() && wheel.advance(u64::MAX) == [5]);
+    }
+}
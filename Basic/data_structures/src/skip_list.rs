@@ -0,0 +1,540 @@
+use std::{
+    borrow::Borrow,
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+};
+
+const MAX_LEVEL: usize = 32;
+const NIL: usize = usize::MAX;
+const HEAD: usize = 0;
+const DEFAULT_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Xorshift64* generator used to draw node levels. It is deliberately tiny
+/// and seedable so a given seed always builds the same skip list.
+#[derive(Debug, Clone)]
+struct LevelRng(u64);
+
+impl LevelRng {
+    fn new(seed: u64) -> Self {
+        // Xorshift gets stuck at zero.
+        Self(if seed == 0 { DEFAULT_SEED } else { seed })
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Level `l` is drawn with probability 2^-l.
+    fn level(&mut self) -> usize {
+        (self.next_u64().trailing_ones() as usize + 1).min(MAX_LEVEL)
+    }
+}
+
+/// `span` is the number of bottom-level steps the link jumps over. A link to
+/// `NIL` jumps to the virtual position `len + 1` past the last element.
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    next: usize,
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<K, V> {
+    entry: Option<(K, V)>,
+    links: Vec<Link>,
+}
+
+/// An ordered map backed by a probabilistic skip list.
+///
+/// Nodes live in a `Vec` and link to each other by index; slot 0 holds the
+/// head sentinel with `MAX_LEVEL` links. Each link also records its span so
+/// that `rank` and `select` run in expected O(log n).
+#[derive(Clone)]
+pub struct SkipList<K, V> {
+    nodes: Vec<Node<K, V>>,
+    free: Vec<usize>,
+    len: usize,
+    rng: LevelRng,
+}
+
+impl<K, V> SkipList<K, V> {
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Creates an empty map whose node levels are drawn from `seed`, so the
+    /// same sequence of operations always builds the same structure.
+    pub fn with_seed(seed: u64) -> Self {
+        let head = Node {
+            entry: None,
+            links: vec![Link { next: NIL, span: 1 }; MAX_LEVEL],
+        };
+        Self {
+            nodes: vec![head],
+            free: Vec::new(),
+            len: 0,
+            rng: LevelRng::new(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        let rng = self.rng.clone();
+        *self = Self::new();
+        self.rng = rng;
+    }
+
+    /// Number of levels in use, i.e. the tallest node's level.
+    pub fn height(&self) -> usize {
+        self.nodes[HEAD]
+            .links
+            .iter()
+            .take_while(|link| link.next != NIL)
+            .count()
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            nodes: &self.nodes,
+            node: self.nodes[HEAD].links[0].next,
+            remaining: self.len,
+        }
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.len.checked_sub(1).and_then(|i| self.select(i))
+    }
+
+    /// The entry at index `i` in key order.
+    pub fn select(&self, i: usize) -> Option<(&K, &V)> {
+        if i >= self.len {
+            return None;
+        }
+        let (target, mut position, mut node) = (i + 1, 0, HEAD);
+        for level in (0..MAX_LEVEL).rev() {
+            loop {
+                let link = self.nodes[node].links[level];
+                if link.next == NIL || position + link.span > target {
+                    break;
+                }
+                position += link.span;
+                node = link.next;
+            }
+            if position == target {
+                break;
+            }
+        }
+        self.entry(node)
+    }
+
+    fn entry(&self, node: usize) -> Option<(&K, &V)> {
+        self.nodes[node].entry.as_ref().map(|(k, v)| (k, v))
+    }
+
+    fn alloc(&mut self, node: Node<K, V>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    /// For every level, the last node whose key is less than `key` (or not
+    /// greater, when `inclusive`) together with its position.
+    fn path<Q>(&self, key: &Q, inclusive: bool) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL])
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (mut update, mut positions) = ([HEAD; MAX_LEVEL], [0; MAX_LEVEL]);
+        let (mut node, mut position) = (HEAD, 0);
+        for level in (0..MAX_LEVEL).rev() {
+            loop {
+                let link = self.nodes[node].links[level];
+                let Some((next_key, _)) = self.nodes.get(link.next).and_then(|n| n.entry.as_ref())
+                else {
+                    break;
+                };
+                let before = match next_key.borrow().cmp(key) {
+                    std::cmp::Ordering::Less => true,
+                    std::cmp::Ordering::Equal => inclusive,
+                    std::cmp::Ordering::Greater => false,
+                };
+                if !before {
+                    break;
+                }
+                position += link.span;
+                node = link.next;
+            }
+            update[level] = node;
+            positions[level] = position;
+        }
+        (update, positions)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (update, _) = self.path(key, false);
+        let next = self.nodes[update[0]].links[0].next;
+        let (next_key, _) = self.nodes.get(next)?.entry.as_ref()?;
+        (next_key.borrow() == key).then_some(next)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key)
+            .and_then(|node| self.entry(node))
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.find(key)?;
+        self.nodes[node].entry.as_mut().map(|(_, v)| v)
+    }
+
+    /// Number of keys strictly less than `key`. For a present key this is its
+    /// index in key order.
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.path(key, false).1[0]
+    }
+
+    /// Inserts `value` under `key`, returning the previous value if the key
+    /// was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (update, positions) = self.path(&key, false);
+        let next = self.nodes[update[0]].links[0].next;
+        if let Some((next_key, next_value)) =
+            self.nodes.get_mut(next).and_then(|n| n.entry.as_mut())
+        {
+            if *next_key == key {
+                return Some(std::mem::replace(next_value, value));
+            }
+        }
+
+        let level = self.rng.level();
+        let position = positions[0] + 1;
+        let links = (0..level)
+            .map(|l| {
+                let link = self.nodes[update[l]].links[l];
+                Link {
+                    next: link.next,
+                    span: link.span - (position - positions[l]) + 1,
+                }
+            })
+            .collect();
+        let node = self.alloc(Node {
+            entry: Some((key, value)),
+            links,
+        });
+        for (l, &prev) in update.iter().enumerate() {
+            let link = &mut self.nodes[prev].links[l];
+            if l < level {
+                *link = Link {
+                    next: node,
+                    span: position - positions[l],
+                };
+            } else {
+                link.span += 1;
+            }
+        }
+        self.len += 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (update, _) = self.path(key, false);
+        let node = self.nodes[update[0]].links[0].next;
+        match self.nodes.get(node).and_then(|n| n.entry.as_ref()) {
+            Some((node_key, _)) if node_key.borrow() == key => {}
+            _ => return None,
+        }
+
+        let links = std::mem::take(&mut self.nodes[node].links);
+        for (l, &prev) in update.iter().enumerate() {
+            let link = &mut self.nodes[prev].links[l];
+            match links.get(l) {
+                Some(removed) => {
+                    link.next = removed.next;
+                    link.span += removed.span - 1;
+                }
+                None => link.span -= 1,
+            }
+        }
+        self.free.push(node);
+        self.len -= 1;
+        self.nodes[node].entry.take()
+    }
+
+    /// Iterates over the entries whose keys lie in `range`, in key order.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (update, start) = match range.start_bound() {
+            Bound::Included(key) => self.path(key, false),
+            Bound::Excluded(key) => self.path(key, true),
+            Bound::Unbounded => ([HEAD; MAX_LEVEL], [0; MAX_LEVEL]),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.path(key, true).1[0],
+            Bound::Excluded(key) => self.path(key, false).1[0],
+            Bound::Unbounded => self.len,
+        };
+        Iter {
+            nodes: &self.nodes,
+            node: self.nodes[update[0]].links[0].next,
+            remaining: end.saturating_sub(start[0]),
+        }
+    }
+}
+
+impl<K, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for SkipList<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for SkipList<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for SkipList<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipList<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    nodes: &'a [Node<K, V>],
+    node: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.nodes.get(self.node)?;
+        self.remaining -= 1;
+        self.node = node.links[0].next;
+        node.entry.as_ref().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::Rng;
+
+    use super::*;
+
+    /// Checks key order and that every span matches the distance between the
+    /// positions it links.
+    fn verify_spans<K: Ord, V>(list: &SkipList<K, V>) {
+        let mut positions = vec![NIL; list.nodes.len()];
+        positions[HEAD] = 0;
+        let mut node = list.nodes[HEAD].links[0].next;
+        let mut position = 0;
+        while node != NIL {
+            position += 1;
+            positions[node] = position;
+            node = list.nodes[node].links[0].next;
+        }
+        assert!(position == list.len);
+        assert!(list.iter().zip(list.iter().skip(1)).all(|(a, b)| a.0 < b.0));
+
+        for (i, node) in list.nodes.iter().enumerate() {
+            if positions[i] == NIL {
+                assert!(node.entry.is_none() && node.links.is_empty());
+                continue;
+            }
+            for link in &node.links {
+                let next = if link.next == NIL {
+                    list.len + 1
+                } else {
+                    positions[link.next]
+                };
+                assert!(link.span == next - positions[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_ops_against_btree_map() {
+        let mut rng = rand::thread_rng();
+        let mut list = SkipList::with_seed(rng.gen());
+        let mut model = BTreeMap::new();
+
+        for i in 0..5000 {
+            let key = rng.gen_range(0..500);
+            if rng.gen_bool(0.6) {
+                assert!(list.insert(key, i) == model.insert(key, i));
+            } else {
+                assert!(list.remove(&key) == model.remove(&key));
+            }
+            assert!(list.get(&key) == model.get(&key));
+            assert!(list.len() == model.len());
+            if i % 250 == 0 {
+                verify_spans(&list);
+            }
+        }
+        verify_spans(&list);
+        assert!(list.iter().eq(model.iter()));
+        assert!(list.first() == model.first_key_value());
+        assert!(list.last() == model.last_key_value());
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let mut rng = rand::thread_rng();
+        let list: SkipList<_, _> = (0..2000).map(|_| (rng.gen_range(0..10000), ())).collect();
+        let keys: Vec<_> = list.iter().map(|(k, _)| *k).collect();
+
+        for (i, key) in keys.iter().enumerate() {
+            assert!(list.rank(key) == i);
+            assert!(list.select(i).map(|(k, _)| k) == Some(key));
+        }
+        assert!(list.select(keys.len()).is_none());
+        for key in -5..10005 {
+            assert!(list.rank(&key) == keys.partition_point(|k| *k < key));
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        let list: SkipList<_, _> = (0..100).map(|k| (k * 2, k)).collect();
+        let model: BTreeMap<_, _> = (0..100).map(|k| (k * 2, k)).collect();
+
+        assert!(list.range(10..20).eq(model.range(10..20)));
+        assert!(list.range(11..=20).eq(model.range(11..=20)));
+        assert!(list.range(..7).eq(model.range(..7)));
+        assert!(list.range(190..).eq(model.range(190..)));
+        assert!(list.range(..).eq(model.range(..)));
+        assert!(list.range(300..400).next().is_none());
+        assert!(list
+            .range((Bound::Excluded(10), Bound::Excluded(12)))
+            .next()
+            .is_none());
+        assert!(list.range((Bound::Excluded(10), Bound::Included(14))).len() == 2);
+    }
+
+    #[test]
+    fn test_sorted_insertion_stays_shallow() {
+        let mut list = SkipList::new();
+        for k in 0..100_000 {
+            list.insert(k, k);
+        }
+        assert!(list.height() < 30);
+        assert!(list.get(&99_999) == Some(&99_999));
+        for k in 0..50_000 {
+            assert!(list.remove(&k) == Some(k));
+        }
+        verify_spans(&list);
+        assert!(list.first() == Some((&50_000, &50_000)));
+    }
+
+    #[test]
+    fn test_seed_is_reproducible() {
+        let build = |seed| {
+            let mut list = SkipList::with_seed(seed);
+            for k in 0..1000 {
+                list.insert(k, ());
+            }
+            list.nodes
+                .iter()
+                .map(|node| node.links.len())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(build(7) == build(7));
+        assert!(build(7) != build(8));
+    }
+}
@@ -1,10 +1,68 @@
-use crate::linked_list::SingleLinkedList;
+use std::marker::PhantomData;
+
+use crate::linked_list::{DoublyLinkedList, SingleLinkedList};
+
+pub mod history;
+pub use history::{Command, History, Limit};
+pub mod min_stack;
+pub use min_stack::{MaxStack, MinStack};
+pub mod monotonic;
+pub mod queue_stack;
+pub use queue_stack::QueueStack;
 
 pub trait Stack<T> {
     fn push(&mut self, x: T);
     fn pop(&mut self) -> Option<T>;
     fn size(&self) -> usize;
     fn new() -> Self;
+
+    /// The element `pop` would return next. `None` unless the stack
+    /// overrides it.
+    fn peek(&self) -> Option<&T> {
+        None
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// Pops elements as the returned iterator is advanced.
+    fn drain(&mut self) -> Drain<'_, T, Self>
+    where
+        Self: Sized,
+    {
+        Drain(self, PhantomData)
+    }
+}
+
+/// A stack with a fixed capacity.
+///
+/// As with `BoundedQueue`, `Stack::new` may return a stack that never fills
+/// up; create the stack through `with_capacity` to rely on the bound.
+pub trait BoundedStack<T>: Stack<T> {
+    fn with_capacity(capacity: usize) -> Self;
+    fn capacity(&self) -> usize;
+
+    /// Pushes `x`, or hands it back if the stack is full.
+    fn try_push(&mut self, x: T) -> Result<(), T>;
+
+    fn is_full(&self) -> bool {
+        self.size() >= self.capacity()
+    }
+}
+
+pub struct Drain<'a, T, S: Stack<T>>(&'a mut S, PhantomData<T>);
+
+impl<T, S: Stack<T>> Iterator for Drain<'_, T, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
 }
 
 impl<T> Stack<T> for Vec<T> {
@@ -16,6 +74,10 @@ impl<T> Stack<T> for Vec<T> {
         self.pop()
     }
 
+    fn peek(&self) -> Option<&T> {
+        self.last()
+    }
+
     fn size(&self) -> usize {
         self.len()
     }
@@ -23,6 +85,10 @@ impl<T> Stack<T> for Vec<T> {
     fn new() -> Self {
         Vec::new()
     }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
 }
 
 impl<T> Stack<T> for SingleLinkedList<T> {
@@ -34,6 +100,10 @@ impl<T> Stack<T> for SingleLinkedList<T> {
         self.pop_head()
     }
 
+    fn peek(&self) -> Option<&T> {
+        self.peek_head()
+    }
+
     fn size(&self) -> usize {
         self.len()
     }
@@ -41,11 +111,61 @@ impl<T> Stack<T> for SingleLinkedList<T> {
     fn new() -> Self {
         SingleLinkedList::new()
     }
+
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T> Stack<T> for DoublyLinkedList<T> {
+    fn push(&mut self, x: T) {
+        self.push_back(x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.back()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        DoublyLinkedList::new()
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bounded::Bounded;
+
+    fn check_stack_defaults<S: Stack<i32>>() {
+        let mut stack = S::new();
+        assert!(stack.is_empty() && stack.peek().is_none());
+        for i in 0..10 {
+            stack.push(i);
+        }
+        assert!(!stack.is_empty() && *stack.peek().unwrap() == 9);
+
+        assert!(stack.drain().take(3).eq((7..10).rev()));
+        assert!(*stack.peek().unwrap() == 6 && stack.size() == 7);
+        assert!(stack.drain().eq((0..7).rev()));
+        assert!(stack.is_empty());
+
+        stack.push(1);
+        stack.push(2);
+        stack.clear();
+        assert!(stack.is_empty() && stack.pop().is_none());
+    }
 
     #[test]
     fn test_vec_stack() {
@@ -61,6 +181,7 @@ mod tests {
         }
 
         assert!(stack.size() == 0);
+        check_stack_defaults::<Vec<_>>();
     }
 
     #[test]
@@ -77,5 +198,36 @@ mod tests {
         }
 
         assert!(stack.size() == 0);
+        check_stack_defaults::<SingleLinkedList<_>>();
+    }
+
+    #[test]
+    fn test_doubly_list_stack() {
+        let mut stack: DoublyLinkedList<_> = Stack::new();
+        for i in 0..10 {
+            stack.push(i);
+        }
+
+        assert!(stack.size() == 10);
+
+        for i in (0..10).rev() {
+            assert!(i == Stack::pop(&mut stack).unwrap());
+        }
+
+        assert!(stack.size() == 0);
+        check_stack_defaults::<DoublyLinkedList<_>>();
+    }
+
+    #[test]
+    fn test_bounded_stack() {
+        let mut stack: Bounded<Vec<_>> = BoundedStack::with_capacity(3);
+        for i in 0..3 {
+            assert!(stack.try_push(i).is_ok());
+        }
+        assert!(stack.is_full() && stack.try_push(3) == Err(3));
+        assert!(Stack::pop(&mut stack) == Some(2) && !stack.is_full());
+        assert!(stack.try_push(4).is_ok());
+        assert!(Stack::drain(&mut stack).eq([4, 1, 0]));
+        assert!(BoundedStack::capacity(&stack) == 3);
     }
 }
@@ -0,0 +1,422 @@
+use std::fmt::Debug;
+
+use crate::queue::RingQueue;
+
+/// A reversible change to a `T`.
+pub trait Command<T: ?Sized> {
+    fn apply(&mut self, target: &mut T);
+
+    /// Reverts `apply`. It is only called on a target in the state `apply`
+    /// left it in.
+    fn undo(&mut self, target: &mut T);
+
+    /// Roughly how many bytes the command keeps alive, which `Limit::Cost`
+    /// caps. Commands that own heap data should count it.
+    fn cost(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+}
+
+/// How much a `History` remembers before it forgets its oldest steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Unbounded,
+    /// At most this many undo steps.
+    Depth(usize),
+    /// At most this much total `Command::cost`.
+    Cost(usize),
+}
+
+/// One undo step: the commands of a transaction, in the order they ran.
+struct Group<C> {
+    commands: Vec<C>,
+    cost: usize,
+}
+
+/// Undo and redo stacks of commands.
+///
+/// Every new action clears the redo stack. Commands run between `begin` and
+/// `commit` form a transaction, which is undone and redone as one step.
+///
+/// When a `Limit` is exceeded, the oldest steps are dropped from the bottom
+/// of the undo stack, so the undo stack is kept in a `RingQueue`. Except under
+/// `Limit::Depth(0)`, the latest step is always kept, even if it exceeds the
+/// limit on its own.
+pub struct History<C> {
+    undo: RingQueue<Group<C>>,
+    redo: Vec<Group<C>>,
+    /// The commands of the transaction under way, if any.
+    open: Option<Group<C>>,
+    /// How many `begin`s are waiting for their `commit`.
+    nesting: usize,
+    limit: Limit,
+    /// The total cost of the undo and redo stacks.
+    cost: usize,
+}
+
+impl<C> History<C> {
+    pub fn new() -> Self {
+        Self::with_limit(Limit::Unbounded)
+    }
+
+    pub fn with_limit(limit: Limit) -> Self {
+        Self {
+            undo: RingQueue::new(),
+            redo: Vec::new(),
+            open: None,
+            nesting: 0,
+            limit,
+            cost: 0,
+        }
+    }
+
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// Changes the limit, dropping the oldest steps if needed.
+    pub fn set_limit(&mut self, limit: Limit) {
+        self.limit = limit;
+        self.enforce_limit();
+    }
+
+    /// How many steps can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// How many steps can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// The total cost of the steps that can be undone or redone, not counting
+    /// an open transaction.
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+
+    pub fn in_transaction(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Forgets every step. An open transaction stays open.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.cost = 0;
+    }
+
+    /// Starts a transaction, or nests one in the transaction under way. Only
+    /// the outermost `commit` turns the transaction into an undo step.
+    pub fn begin(&mut self) {
+        self.nesting += 1;
+        self.open.get_or_insert_with(|| Group {
+            commands: Vec::new(),
+            cost: 0,
+        });
+    }
+
+    /// Ends the innermost transaction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no transaction is under way.
+    pub fn commit(&mut self) {
+        assert!(self.nesting > 0, "commit without a matching begin");
+        self.nesting -= 1;
+        if self.nesting > 0 {
+            return;
+        }
+        if let Some(group) = self.open.take() {
+            if !group.commands.is_empty() {
+                self.push_undo(group);
+            }
+        }
+    }
+
+    fn push_undo(&mut self, group: Group<C>) {
+        self.cost -= self.redo.drain(..).map(|group| group.cost).sum::<usize>();
+        self.cost += group.cost;
+        self.undo.push_back(group);
+        self.enforce_limit();
+    }
+
+    fn enforce_limit(&mut self) {
+        let over = |history: &Self| match history.limit {
+            Limit::Unbounded => false,
+            Limit::Depth(depth) => history.undo.len() > depth,
+            Limit::Cost(cost) => history.cost > cost,
+        };
+        while self.undo.len() > 1 && over(self) {
+            if let Some(group) = self.undo.pop_front() {
+                self.cost -= group.cost;
+            }
+        }
+        if let Limit::Depth(0) = self.limit {
+            if let Some(group) = self.undo.pop_front() {
+                self.cost -= group.cost;
+            }
+        }
+    }
+
+    /// Applies `command` to `target` and records it, clearing the redo stack.
+    pub fn apply<T: ?Sized>(&mut self, target: &mut T, mut command: C)
+    where
+        C: Command<T>,
+    {
+        command.apply(target);
+        self.record(command);
+    }
+
+    /// Records a command that has already been applied, clearing the redo
+    /// stack.
+    pub fn record<T: ?Sized>(&mut self, command: C)
+    where
+        C: Command<T>,
+    {
+        let cost = command.cost();
+        match &mut self.open {
+            Some(group) => {
+                group.cost += cost;
+                group.commands.push(command);
+            }
+            None => self.push_undo(Group {
+                commands: vec![command],
+                cost,
+            }),
+        }
+    }
+
+    /// Undoes the latest step, returning whether there was one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a transaction is under way.
+    pub fn undo<T: ?Sized>(&mut self, target: &mut T) -> bool
+    where
+        C: Command<T>,
+    {
+        assert!(self.open.is_none(), "cannot undo during a transaction");
+        let Some(mut group) = self.undo.pop_back() else {
+            return false;
+        };
+        group
+            .commands
+            .iter_mut()
+            .rev()
+            .for_each(|command| command.undo(target));
+        self.redo.push(group);
+        true
+    }
+
+    /// Redoes the latest undone step, returning whether there was one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a transaction is under way.
+    pub fn redo<T: ?Sized>(&mut self, target: &mut T) -> bool
+    where
+        C: Command<T>,
+    {
+        assert!(self.open.is_none(), "cannot redo during a transaction");
+        let Some(mut group) = self.redo.pop() else {
+            return false;
+        };
+        group
+            .commands
+            .iter_mut()
+            .for_each(|command| command.apply(target));
+        self.undo.push_back(group);
+        true
+    }
+
+    /// Undoes the commands of the transaction under way and ends it, however
+    /// deeply it is nested.
+    pub fn rollback<T: ?Sized>(&mut self, target: &mut T)
+    where
+        C: Command<T>,
+    {
+        if let Some(mut group) = self.open.take() {
+            group
+                .commands
+                .iter_mut()
+                .rev()
+                .for_each(|command| command.undo(target));
+        }
+        self.nesting = 0;
+    }
+}
+
+impl<C> Default for History<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Debug for History<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("History")
+            .field("undo_len", &self.undo_len())
+            .field("redo_len", &self.redo_len())
+            .field("in_transaction", &self.in_transaction())
+            .field("limit", &self.limit)
+            .field("cost", &self.cost)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Edits of a text buffer.
+    #[derive(Debug)]
+    enum Edit {
+        Insert(usize, String),
+        Delete(usize, usize, Option<String>),
+    }
+
+    impl Command<String> for Edit {
+        fn apply(&mut self, text: &mut String) {
+            match self {
+                Edit::Insert(at, s) => text.insert_str(*at, s),
+                Edit::Delete(at, len, removed) => {
+                    *removed = Some(text.drain(*at..*at + *len).collect());
+                }
+            }
+        }
+
+        fn undo(&mut self, text: &mut String) {
+            match self {
+                Edit::Insert(at, s) => {
+                    text.drain(*at..*at + s.len());
+                }
+                Edit::Delete(at, _, removed) => {
+                    text.insert_str(*at, &removed.take().unwrap());
+                }
+            }
+        }
+
+        fn cost(&self) -> usize {
+            match self {
+                Edit::Insert(_, s) => s.len(),
+                Edit::Delete(_, len, _) => *len,
+            }
+        }
+    }
+
+    fn insert(at: usize, s: &str) -> Edit {
+        Edit::Insert(at, s.to_string())
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut text = String::new();
+        let mut history = History::new();
+        history.apply(&mut text, insert(0, "hello"));
+        history.apply(&mut text, insert(5, " world"));
+        history.apply(&mut text, Edit::Delete(0, 1, None));
+        assert!(text == "ello world");
+
+        assert!(history.undo(&mut text) && text == "hello world");
+        assert!(history.undo(&mut text) && text == "hello");
+        assert!(history.redo(&mut text) && text == "hello world");
+        assert!(history.undo_len() == 2 && history.redo_len() == 1);
+
+        // A new action clears the redo stack.
+        history.apply(&mut text, insert(0, ">"));
+        assert!(!history.can_redo() && !history.redo(&mut text));
+        assert!(text == ">hello world");
+
+        while history.undo(&mut text) {}
+        assert!(text.is_empty() && history.redo_len() == 3);
+        while history.redo(&mut text) {}
+        assert!(text == ">hello world");
+    }
+
+    #[test]
+    fn test_transactions() {
+        let mut text = String::from("ab");
+        let mut history = History::new();
+        history.begin();
+        history.apply(&mut text, insert(1, "1"));
+        history.begin();
+        history.apply(&mut text, insert(3, "2"));
+        history.commit();
+        assert!(history.in_transaction() && history.undo_len() == 0);
+        history.apply(&mut text, Edit::Delete(0, 1, None));
+        history.commit();
+        assert!(text == "1b2" && history.undo_len() == 1);
+
+        assert!(history.undo(&mut text) && text == "ab");
+        assert!(history.redo(&mut text) && text == "1b2");
+
+        // A rolled back transaction leaves no trace, and an empty one
+        // records no step.
+        history.begin();
+        history.apply(&mut text, insert(0, "x"));
+        history.apply(&mut text, insert(0, "y"));
+        history.rollback(&mut text);
+        history.begin();
+        history.commit();
+        assert!(text == "1b2" && history.undo_len() == 1 && !history.in_transaction());
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let mut text = String::new();
+        let mut history = History::with_limit(Limit::Depth(3));
+        for i in 0..10 {
+            history.apply(&mut text, insert(i, "x"));
+        }
+        assert!(history.undo_len() == 3);
+        while history.undo(&mut text) {}
+        assert!(text == "xxxxxxx");
+
+        history.set_limit(Limit::Depth(0));
+        history.apply(&mut text, insert(0, "y"));
+        assert!(!history.can_undo() && history.cost() == 0);
+    }
+
+    #[test]
+    fn test_cost_limit() {
+        let mut text = String::new();
+        let mut history = History::with_limit(Limit::Cost(10));
+        history.apply(&mut text, insert(0, "aaaa"));
+        history.apply(&mut text, insert(4, "bbbb"));
+        assert!(history.cost() == 8 && history.undo_len() == 2);
+        history.apply(&mut text, insert(8, "cccc"));
+        assert!(history.cost() == 8 && history.undo_len() == 2);
+
+        // Undone steps still count until a new action clears them.
+        history.undo(&mut text);
+        assert!(history.cost() == 8);
+        history.apply(&mut text, insert(0, "d"));
+        assert!(history.cost() == 5);
+
+        // The latest step is kept even when it exceeds the limit.
+        history.apply(&mut text, insert(0, "e".repeat(20).as_str()));
+        assert!(history.undo_len() == 1 && history.cost() == 20);
+        history.undo(&mut text);
+        assert!(text == "daaaabbbb");
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot undo during a transaction")]
+    fn test_undo_in_transaction() {
+        let mut text = String::new();
+        let mut history = History::new();
+        history.apply(&mut text, insert(0, "a"));
+        history.begin();
+        history.undo(&mut text);
+    }
+}
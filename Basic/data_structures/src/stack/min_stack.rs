@@ -0,0 +1,285 @@
+use std::fmt::Debug;
+
+use super::Stack;
+use crate::heap::{Compare, Natural, Reversed};
+
+/// A stack that also reports its smallest element in O(1).
+///
+/// Besides the elements, it keeps the positions of the running minima: an
+/// element is recorded only when it is strictly smaller than the current
+/// minimum, so a stack pushed in ascending order needs no extra memory.
+pub struct MinStack<T, C = Natural> {
+    items: Vec<T>,
+    mins: Vec<usize>,
+    cmp: C,
+}
+
+impl<T: Ord> MinStack<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T, C> MinStack<T, C> {
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.mins.clear();
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.items.last()
+    }
+
+    /// The smallest element according to the comparator. Of several equal
+    /// ones, the earliest pushed is returned.
+    pub fn min(&self) -> Option<&T> {
+        self.mins.last().map(|&i| &self.items[i])
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let x = self.items.pop()?;
+        if self.mins.last() == Some(&self.items.len()) {
+            self.mins.pop();
+        }
+        Some(x)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+}
+
+impl<T, C: Compare<T>> MinStack<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            items: Vec::new(),
+            mins: Vec::new(),
+            cmp,
+        }
+    }
+
+    pub fn push(&mut self, x: T) {
+        let is_min = self
+            .min()
+            .is_none_or(|min| self.cmp.compare(&x, min).is_lt());
+        if is_min {
+            self.mins.push(self.items.len());
+        }
+        self.items.push(x);
+    }
+}
+
+impl<T, C: Compare<T> + Default> Stack<T> for MinStack<T, C> {
+    fn push(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for MinStack<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T: Debug, C> Debug for MinStack<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MinStack")
+            .field("items", &self.items)
+            .field("min", &self.min())
+            .finish()
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for MinStack<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Self::default();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for MinStack<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+/// A stack that also reports its largest element in O(1); see `MinStack`.
+pub struct MaxStack<T>(MinStack<T, Reversed>);
+
+impl<T: Ord> MaxStack<T> {
+    pub fn new() -> Self {
+        MaxStack(MinStack::with_comparator(Reversed))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.0.peek()
+    }
+
+    /// The largest element. Of several equal ones, the earliest pushed is
+    /// returned.
+    pub fn max(&self) -> Option<&T> {
+        self.0.min()
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.0.push(x)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+}
+
+impl<T: Ord> Stack<T> for MaxStack<T> {
+    fn push(&mut self, x: T) {
+        self.push(x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+
+    fn new() -> Self {
+        Self::new()
+    }
+
+    fn clear(&mut self) {
+        self.clear()
+    }
+}
+
+impl<T: Ord> Default for MaxStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Debug> Debug for MaxStack<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MaxStack")
+            .field("items", &self.0.items)
+            .field("max", &self.0.min())
+            .finish()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MaxStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        MaxStack(iter.into_iter().collect())
+    }
+}
+
+impl<T: Ord> Extend<T> for MaxStack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_against_scanning() {
+        let mut rng = rand::thread_rng();
+        let mut min_stack = MinStack::new();
+        let mut max_stack = MaxStack::new();
+        let mut model = Vec::new();
+        for _ in 0..3000 {
+            if rng.gen_bool(0.55) {
+                let x = rng.gen_range(-50..50);
+                min_stack.push(x);
+                max_stack.push(x);
+                model.push(x);
+            } else {
+                let expected = model.pop();
+                assert!(min_stack.pop() == expected && max_stack.pop() == expected);
+            }
+            assert!(min_stack.min() == model.iter().min());
+            assert!(max_stack.max() == model.iter().max());
+            assert!(min_stack.len() == model.len() && min_stack.peek() == model.last());
+        }
+    }
+
+    #[test]
+    fn test_only_new_minima_are_recorded() {
+        let mut stack: MinStack<_> = (0..100).collect();
+        assert!(stack.mins.len() == 1);
+        stack.extend((0..100).rev());
+        assert!(stack.mins.len() == 1 && stack.min() == Some(&0));
+
+        // Equal minima are not recorded either; the earliest one is reported.
+        let mut stack = MinStack::with_comparator(|a: &(i32, char), b: &(i32, char)| a.0.cmp(&b.0));
+        stack.extend([(2, 'a'), (1, 'b'), (1, 'c'), (3, 'd')]);
+        assert!(stack.mins.len() == 2 && stack.min() == Some(&(1, 'b')));
+        stack.pop();
+        stack.pop();
+        assert!(stack.min() == Some(&(1, 'b')));
+        stack.pop();
+        assert!(stack.min() == Some(&(2, 'a')));
+    }
+
+    #[test]
+    fn test_floats_by_total_order() {
+        let mut stack = MinStack::with_comparator(f64::total_cmp);
+        stack.extend([0.5, -1.25, 3.0]);
+        assert!(stack.min() == Some(&-1.25));
+        stack.clear();
+        assert!(stack.is_empty() && stack.min().is_none());
+    }
+}
@@ -0,0 +1,133 @@
+//! "Next greater element" style queries, answered in O(n) with a stack of
+//! indices whose elements stay monotonic from bottom to top.
+//!
+//! Each query returns, for every position, the index of the nearest element
+//! in one direction that beats it strictly, or `None` if there is none.
+
+use super::Stack;
+use crate::heap::{Compare, Natural, Reversed};
+
+/// For each element, the index of the first later element that is strictly
+/// greater.
+pub fn next_greater<T: Ord>(xs: &[T]) -> Vec<Option<usize>> {
+    next_less_by(xs, Reversed)
+}
+
+/// For each element, the index of the first later element that is strictly
+/// smaller.
+pub fn next_smaller<T: Ord>(xs: &[T]) -> Vec<Option<usize>> {
+    next_less_by(xs, Natural)
+}
+
+/// For each element, the index of the last earlier element that is strictly
+/// greater.
+pub fn previous_greater<T: Ord>(xs: &[T]) -> Vec<Option<usize>> {
+    previous_less_by(xs, Reversed)
+}
+
+/// For each element, the index of the last earlier element that is strictly
+/// smaller.
+pub fn previous_smaller<T: Ord>(xs: &[T]) -> Vec<Option<usize>> {
+    previous_less_by(xs, Natural)
+}
+
+/// For each element, the index of the first later element that compares
+/// `Less` than it.
+pub fn next_less_by<T, C: Compare<T>>(xs: &[T], cmp: C) -> Vec<Option<usize>> {
+    next_less_with::<_, _, Vec<_>>(xs, cmp)
+}
+
+/// For each element, the index of the last earlier element that compares
+/// `Less` than it.
+pub fn previous_less_by<T, C: Compare<T>>(xs: &[T], cmp: C) -> Vec<Option<usize>> {
+    previous_less_with::<_, _, Vec<_>>(xs, cmp)
+}
+
+/// Keeps the indices still waiting for an answer. Their elements never
+/// decrease from bottom to top, so a new element answers a run at the top.
+fn next_less_with<T, C: Compare<T>, S: Stack<usize>>(xs: &[T], cmp: C) -> Vec<Option<usize>> {
+    let mut next = vec![None; xs.len()];
+    let mut waiting = S::new();
+    for (i, x) in xs.iter().enumerate() {
+        while let Some(top) = waiting.peek().copied() {
+            if cmp.compare(x, &xs[top]).is_ge() {
+                break;
+            }
+            next[top] = Some(i);
+            waiting.pop();
+        }
+        waiting.push(i);
+    }
+    next
+}
+
+/// Keeps the indices that may still answer a later element: those with no
+/// later element that is less than or equal to them.
+fn previous_less_with<T, C: Compare<T>, S: Stack<usize>>(xs: &[T], cmp: C) -> Vec<Option<usize>> {
+    let mut previous = Vec::with_capacity(xs.len());
+    let mut candidates = S::new();
+    for (i, x) in xs.iter().enumerate() {
+        while let Some(top) = candidates.peek().copied() {
+            if cmp.compare(&xs[top], x).is_lt() {
+                break;
+            }
+            candidates.pop();
+        }
+        previous.push(candidates.peek().copied());
+        candidates.push(i);
+    }
+    previous
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::linked_list::SingleLinkedList;
+
+    fn next_by_scanning(xs: &[i32], beats: impl Fn(i32, i32) -> bool) -> Vec<Option<usize>> {
+        (0..xs.len())
+            .map(|i| (i + 1..xs.len()).find(|&j| beats(xs[j], xs[i])))
+            .collect()
+    }
+
+    fn previous_by_scanning(xs: &[i32], beats: impl Fn(i32, i32) -> bool) -> Vec<Option<usize>> {
+        (0..xs.len())
+            .map(|i| (0..i).rev().find(|&j| beats(xs[j], xs[i])))
+            .collect()
+    }
+
+    #[test]
+    fn test_against_scanning() {
+        let mut rng = rand::thread_rng();
+        for len in [0, 1, 2, 10, 300] {
+            let xs = (0..len).map(|_| rng.gen_range(0..20)).collect::<Vec<i32>>();
+            assert!(next_greater(&xs) == next_by_scanning(&xs, |a, b| a > b));
+            assert!(next_smaller(&xs) == next_by_scanning(&xs, |a, b| a < b));
+            assert!(previous_greater(&xs) == previous_by_scanning(&xs, |a, b| a > b));
+            assert!(previous_smaller(&xs) == previous_by_scanning(&xs, |a, b| a < b));
+
+            let on_list = next_less_with::<_, _, SingleLinkedList<_>>(&xs, Reversed);
+            assert!(on_list == next_greater(&xs));
+            let on_list = previous_less_with::<_, _, SingleLinkedList<_>>(&xs, Natural);
+            assert!(on_list == previous_smaller(&xs));
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        let xs = [2, 1, 2, 4, 3, 1];
+        assert!(next_greater(&xs) == [Some(3), Some(2), Some(3), None, None, None]);
+        assert!(previous_greater(&xs) == [None, Some(0), None, None, Some(3), Some(4)]);
+
+        // Spans of stock prices: days since the last strictly higher price.
+        let prices = [100.0, 80.0, 60.0, 70.0, 60.0, 75.0, 85.0];
+        let spans = previous_less_by(&prices, |a: &f64, b: &f64| b.total_cmp(a))
+            .into_iter()
+            .enumerate()
+            .map(|(i, previous)| previous.map_or(i + 1, |j| i - j))
+            .collect::<Vec<_>>();
+        assert!(spans == [1, 1, 1, 2, 1, 4, 6]);
+    }
+}
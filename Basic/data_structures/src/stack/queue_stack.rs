@@ -0,0 +1,135 @@
+use super::Stack;
+use crate::queue::Queue;
+
+/// A stack made of a single queue: after enqueueing a new element, the
+/// elements in front of it are rotated behind it, so the queue's front is
+/// always the top of the stack.
+///
+/// `pop` and `peek` are O(1), while `push` dequeues and re-enqueues every
+/// element already on the stack, for O(n).
+#[derive(Debug, Clone)]
+pub struct QueueStack<Q> {
+    inner: Q,
+}
+
+impl<Q> QueueStack<Q> {
+    pub fn into_inner(self) -> Q {
+        self.inner
+    }
+}
+
+impl<T, Q: Queue<T>> Stack<T> for QueueStack<Q> {
+    fn push(&mut self, x: T) {
+        self.inner.enqueue(x);
+        for _ in 1..self.inner.size() {
+            if let Some(y) = self.inner.dequeue() {
+                self.inner.enqueue(y);
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.inner.dequeue()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.inner.peek()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    fn new() -> Self {
+        Self { inner: Q::new() }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{linked_list::DoublyLinkedList, queue::RingQueue};
+
+    /// Counts the enqueues and dequeues made on a queue.
+    struct Counted<Q> {
+        inner: Q,
+        ops: usize,
+    }
+
+    impl<T, Q: Queue<T>> Queue<T> for Counted<Q> {
+        fn enqueue(&mut self, x: T) {
+            self.ops += 1;
+            self.inner.enqueue(x)
+        }
+
+        fn dequeue(&mut self) -> Option<T> {
+            self.ops += 1;
+            self.inner.dequeue()
+        }
+
+        fn peek(&self) -> Option<&T> {
+            self.inner.peek()
+        }
+
+        fn size(&self) -> usize {
+            self.inner.size()
+        }
+
+        fn new() -> Self {
+            Counted {
+                inner: Q::new(),
+                ops: 0,
+            }
+        }
+    }
+
+    fn check_against_model<S: Stack<i32>>() {
+        let mut rng = rand::thread_rng();
+        let mut stack = S::new();
+        let mut model = Vec::new();
+        for _ in 0..2000 {
+            if rng.gen_bool(0.55) {
+                let x = rng.gen_range(0..1000);
+                stack.push(x);
+                model.push(x);
+            } else {
+                assert!(stack.pop() == model.pop());
+            }
+            assert!(stack.peek().copied() == model.last().copied());
+            assert!(stack.size() == model.len());
+        }
+        assert!(stack.drain().eq(model.into_iter().rev()));
+    }
+
+    #[test]
+    fn test_against_model() {
+        check_against_model::<QueueStack<RingQueue<_>>>();
+        check_against_model::<QueueStack<DoublyLinkedList<_>>>();
+    }
+
+    #[test]
+    fn test_operation_count() {
+        let mut stack = QueueStack::<Counted<RingQueue<_>>>::new();
+        for n in 1..=100 {
+            let before = stack.inner.ops;
+            stack.push(n);
+            // One enqueue, then one dequeue and enqueue per older element.
+            assert!(stack.inner.ops - before == 2 * n - 1);
+        }
+        for n in (1..=100).rev() {
+            let before = stack.inner.ops;
+            assert!(stack.pop() == Some(n));
+            assert!(stack.inner.ops - before == 1);
+        }
+    }
+}
@@ -4,6 +4,18 @@ use std::{
     rc::{Rc, Weak},
 };
 
+pub mod avl_tree;
+pub use avl_tree::{AvlNode, AvlTree};
+pub mod b_plus_tree;
+pub use b_plus_tree::BPlusTree;
+pub mod b_tree;
+pub use b_tree::BTree;
+pub mod policy_tree;
+pub use policy_tree::{Counted, Duplicates, PolicyTree};
+pub mod red_black_tree;
+pub mod tree_map;
+pub use tree_map::TreeMap;
+
 #[derive(Debug)]
 pub struct TreeNode<T> {
     pub value: T,
@@ -115,14 +127,14 @@ impl<T: PartialEq + PartialOrd> BinaryTree<T> {
                 if x < cursor.borrow().value {
                     if cursor.borrow().left.is_empty() {
                         cursor.borrow_mut().left.root =
-                            TreeNode::new_leaf(x, Rc::downgrade(&cursor)).to_root();
+                            TreeNode::new_leaf(x, Rc::downgrade(cursor)).to_root();
                         return;
                     } else if let Some(next) = cursor.clone().borrow().left.root.clone() {
                         *cursor = next;
                     }
                 } else if cursor.borrow().right.is_empty() {
                     cursor.borrow_mut().right.root =
-                        TreeNode::new_leaf(x, Rc::downgrade(&cursor)).to_root();
+                        TreeNode::new_leaf(x, Rc::downgrade(cursor)).to_root();
                     return;
                 } else if let Some(next) = cursor.clone().borrow().right.root.clone() {
                     *cursor = next;
@@ -138,7 +150,15 @@ impl<T: PartialEq + PartialOrd> BinaryTree<T> {
             if let Some(new_node) = &new {
                 new_node.borrow_mut().parent = Rc::downgrade(&parent);
             }
-            if node.as_ref() < parent.as_ref() {
+            // Tell the side by identity, which holds whatever the values
+            // compare as, equal or not comparable at all.
+            let is_left = parent
+                .borrow()
+                .left
+                .root
+                .as_ref()
+                .is_some_and(|left| Rc::ptr_eq(left, &node));
+            if is_left {
                 parent.borrow_mut().left.root = new;
             } else {
                 parent.borrow_mut().right.root = new;
@@ -182,6 +202,26 @@ impl<T: PartialEq + PartialOrd> BinaryTree<T> {
         }
     }
 
+    /// Removes the node of one value equal to `x` and returns the value, or
+    /// `Ok(None)` if there is none.
+    ///
+    /// If a `TreeNodePtr` to the node is still held outside the tree, from
+    /// `find` or `iter`, the value cannot be moved out of it. The node is
+    /// removed all the same and handed back as the error.
+    pub fn remove_value(&mut self, x: &T) -> Result<Option<T>, TreeNodePtr<T>> {
+        let Some(node) = self.find(x) else {
+            return Ok(None);
+        };
+        self.take(node).map(Some)
+    }
+
+    /// Removes `node` and moves its value out, or hands the node back if it
+    /// is still held elsewhere.
+    pub(crate) fn take(&mut self, node: TreeNodePtr<T>) -> Result<T, TreeNodePtr<T>> {
+        self.remove(node.clone());
+        Rc::try_unwrap(node).map(|node| node.into_inner().value)
+    }
+
     pub fn find(&self, x: &T) -> Option<TreeNodePtr<T>> {
         if let Some(root) = &self.root {
             let mut cursor = root.clone();
@@ -197,7 +237,7 @@ impl<T: PartialEq + PartialOrd> BinaryTree<T> {
                 }
             }
         } else {
-            return None;
+            None
         }
     }
 
@@ -222,11 +262,16 @@ impl<T: PartialEq + PartialOrd> BinaryTree<T> {
     }
 }
 
-fn fmt_with_count<T: Display>(
-    tree: &BinaryTree<T>,
+/// Draws the tree under `node`, one arm of indentation per level. `parts`
+/// writes a node's value and returns its left and right children.
+pub(crate) fn fmt_with_count<N>(
+    node: Option<N>,
+    parts: &impl Fn(&N, &mut std::fmt::Formatter<'_>) -> Result<(Option<N>, Option<N>), std::fmt::Error>,
     f: &mut std::fmt::Formatter<'_>,
     count: usize,
 ) -> std::fmt::Result {
+    // `usize::is_multiple_of` needs Rust 1.87.
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
     fn write_space(i: usize, div: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if i % div == 0 {
             write!(f, "|")?;
@@ -255,19 +300,19 @@ fn fmt_with_count<T: Display>(
         Ok(())
     }
 
-    if let Some(node) = tree.root.as_ref() {
+    if let Some(node) = node {
         let left_arm = "--l--";
         let right_arm = "--r--";
         let arm_len = left_arm.len();
         let space_count = count * arm_len;
-        node.borrow().value.fmt(f)?;
-        if !node.borrow().left.is_empty() {
+        let (left, right) = parts(&node, f)?;
+        if left.is_some() {
             write_line(space_count, left_arm, f)?;
-            fmt_with_count(&node.borrow().left, f, count + 1)?;
+            fmt_with_count(left, parts, f, count + 1)?;
         }
-        if !node.borrow().right.is_empty() {
+        if right.is_some() {
             write_line(space_count, right_arm, f)?;
-            fmt_with_count(&node.borrow().right, f, count + 1)?;
+            fmt_with_count(right, parts, f, count + 1)?;
         }
     };
 
@@ -276,7 +321,12 @@ fn fmt_with_count<T: Display>(
 
 impl<T: Display> Display for BinaryTree<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        fmt_with_count(self, f, 0)
+        let parts = |node: &TreeNodePtr<T>, f: &mut std::fmt::Formatter<'_>| {
+            let node = node.borrow();
+            node.value.fmt(f)?;
+            Ok((node.left.root.clone(), node.right.root.clone()))
+        };
+        fmt_with_count(self.root.clone(), &parts, f, 0)
     }
 }
 
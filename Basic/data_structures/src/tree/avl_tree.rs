@@ -0,0 +1,326 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    rc::{Rc, Weak},
+};
+
+use super::fmt_with_count;
+
+#[derive(Debug)]
+pub struct AvlNode<T> {
+    pub value: T,
+    /// The number of nodes on the longest path down to a leaf, counting
+    /// this one.
+    pub height: usize,
+    pub parent: Weak<RefCell<AvlNode<T>>>,
+    pub left: AvlTree<T>,
+    pub right: AvlTree<T>,
+}
+type AvlNodePtr<T> = Rc<RefCell<AvlNode<T>>>;
+
+impl<T> AvlNode<T> {
+    pub fn new(x: T) -> Self {
+        Self::new_leaf(x, Weak::new())
+    }
+
+    pub fn new_leaf(x: T, parent: Weak<RefCell<AvlNode<T>>>) -> Self {
+        Self {
+            value: x,
+            height: 1,
+            parent,
+            left: AvlTree::default(),
+            right: AvlTree::default(),
+        }
+    }
+
+    pub fn to_root(self) -> Option<Rc<RefCell<Self>>> {
+        Some(Rc::new(RefCell::new(self)))
+    }
+
+    /// Follows the links rather than comparing values, so equal values are
+    /// told apart.
+    pub fn predecessor(node: AvlNodePtr<T>) -> Option<AvlNodePtr<T>> {
+        if !node.borrow().left.is_empty() {
+            return node.borrow().left.max();
+        }
+        let mut node = node;
+        loop {
+            let parent = node.borrow().parent.upgrade()?;
+            if !is_left_child(&parent, &node) {
+                return Some(parent);
+            }
+            node = parent;
+        }
+    }
+
+    pub fn successor(node: AvlNodePtr<T>) -> Option<AvlNodePtr<T>> {
+        if !node.borrow().right.is_empty() {
+            return node.borrow().right.min();
+        }
+        let mut node = node;
+        loop {
+            let parent = node.borrow().parent.upgrade()?;
+            if is_left_child(&parent, &node) {
+                return Some(parent);
+            }
+            node = parent;
+        }
+    }
+}
+
+fn is_left_child<T>(parent: &AvlNodePtr<T>, node: &AvlNodePtr<T>) -> bool {
+    parent
+        .borrow()
+        .left
+        .root
+        .as_ref()
+        .is_some_and(|left| Rc::ptr_eq(left, node))
+}
+
+/// A binary search tree that rebalances itself, so the heights of the two
+/// subtrees of any node differ by at most one and `insert`, `remove` and
+/// `find` take O(log n) even on sorted input.
+///
+/// It has the surface of `BinaryTree`, and keeps equal values in insertion
+/// order like it.
+#[derive(Debug)]
+pub struct AvlTree<T> {
+    pub root: Option<AvlNodePtr<T>>,
+}
+
+impl<T> Default for AvlTree<T> {
+    fn default() -> Self {
+        AvlTree { root: None }
+    }
+}
+
+impl<T> AvlTree<T> {
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clean(&mut self) {
+        self.root.take();
+    }
+
+    pub fn height(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.borrow().height)
+    }
+
+    pub fn iter(&self) -> Iter<T> {
+        Iter(self.min())
+    }
+
+    pub fn min(&self) -> Option<AvlNodePtr<T>> {
+        let mut cursor = self.root.clone()?;
+        while let Some(left) = cursor.clone().borrow().left.root.clone() {
+            cursor = left;
+        }
+        Some(cursor)
+    }
+
+    pub fn max(&self) -> Option<AvlNodePtr<T>> {
+        let mut cursor = self.root.clone()?;
+        while let Some(right) = cursor.clone().borrow().right.root.clone() {
+            cursor = right;
+        }
+        Some(cursor)
+    }
+
+    /// Removes `node`, which must belong to this tree. Other nodes are
+    /// relinked rather than having their values moved, so pointers to them
+    /// stay valid.
+    pub fn remove(&mut self, node: AvlNodePtr<T>) {
+        let left = node.borrow_mut().left.root.take();
+        let right = node.borrow_mut().right.root.take();
+        let parent = node.borrow().parent.upgrade();
+
+        let (replacement, rebalance_from) = match (left, right) {
+            (None, None) => (None, parent.clone()),
+            (Some(child), None) | (None, Some(child)) => (Some(child), parent.clone()),
+            (Some(left), Some(right)) => {
+                let mut right_tree = AvlTree { root: Some(right) };
+                let next = right_tree.min().expect("the right subtree is not empty");
+                let next_parent = next.borrow().parent.upgrade();
+                let rebalance_from = match next_parent {
+                    // `next` is the root of the right subtree.
+                    Some(next_parent) if Rc::ptr_eq(&next_parent, &node) => next.clone(),
+                    Some(next_parent) => {
+                        let next_right = next.borrow_mut().right.root.take();
+                        Self::set_child(&next_parent, true, next_right);
+                        Self::set_child(&next, false, right_tree.root.take());
+                        next_parent
+                    }
+                    None => unreachable!("`next` has a parent inside the tree"),
+                };
+                Self::set_child(&next, true, Some(left));
+                (Some(next), Some(rebalance_from))
+            }
+        };
+        self.replace(&node, parent.as_ref(), replacement);
+        node.borrow_mut().parent = Weak::new();
+        node.borrow_mut().height = 1;
+        self.rebalance_up(rebalance_from);
+    }
+
+    /// Puts `new` where `old` hangs from `parent`, or at the root.
+    fn replace(
+        &mut self,
+        old: &AvlNodePtr<T>,
+        parent: Option<&AvlNodePtr<T>>,
+        new: Option<AvlNodePtr<T>>,
+    ) {
+        match parent {
+            Some(parent) => {
+                let is_left = is_left_child(parent, old);
+                Self::set_child(parent, is_left, new);
+            }
+            None => {
+                if let Some(new) = &new {
+                    new.borrow_mut().parent = Weak::new();
+                }
+                self.root = new;
+            }
+        }
+    }
+
+    fn set_child(parent: &AvlNodePtr<T>, left: bool, child: Option<AvlNodePtr<T>>) {
+        if let Some(child) = &child {
+            child.borrow_mut().parent = Rc::downgrade(parent);
+        }
+        let mut parent = parent.borrow_mut();
+        if left {
+            parent.left.root = child;
+        } else {
+            parent.right.root = child;
+        }
+    }
+
+    /// The left height minus the right height, after refreshing `node`'s
+    /// own height from its children.
+    fn update(node: &AvlNodePtr<T>) -> isize {
+        let mut node = node.borrow_mut();
+        let (left, right) = (node.left.height(), node.right.height());
+        node.height = 1 + left.max(right);
+        left as isize - right as isize
+    }
+
+    /// Lifts the right child of `node` above it and returns that child.
+    fn rotate_left(&mut self, node: &AvlNodePtr<T>) -> AvlNodePtr<T> {
+        let parent = node.borrow().parent.upgrade();
+        let pivot = node.borrow_mut().right.root.take().expect("a right child");
+        let inner = pivot.borrow_mut().left.root.take();
+        Self::set_child(node, false, inner);
+        self.replace(node, parent.as_ref(), Some(pivot.clone()));
+        Self::set_child(&pivot, true, Some(node.clone()));
+        Self::update(node);
+        Self::update(&pivot);
+        pivot
+    }
+
+    /// Lifts the left child of `node` above it and returns that child.
+    fn rotate_right(&mut self, node: &AvlNodePtr<T>) -> AvlNodePtr<T> {
+        let parent = node.borrow().parent.upgrade();
+        let pivot = node.borrow_mut().left.root.take().expect("a left child");
+        let inner = pivot.borrow_mut().right.root.take();
+        Self::set_child(node, true, inner);
+        self.replace(node, parent.as_ref(), Some(pivot.clone()));
+        Self::set_child(&pivot, false, Some(node.clone()));
+        Self::update(node);
+        Self::update(&pivot);
+        pivot
+    }
+
+    /// Refreshes heights from `node` up to the root, rotating wherever the
+    /// subtrees of a node differ in height by two.
+    fn rebalance_up(&mut self, node: Option<AvlNodePtr<T>>) {
+        let mut cursor = node;
+        while let Some(mut node) = cursor {
+            let balance = Self::update(&node);
+            if balance > 1 {
+                let left = node.borrow().left.root.clone().expect("a left child");
+                if Self::update(&left) < 0 {
+                    self.rotate_left(&left);
+                }
+                node = self.rotate_right(&node);
+            } else if balance < -1 {
+                let right = node.borrow().right.root.clone().expect("a right child");
+                if Self::update(&right) > 0 {
+                    self.rotate_right(&right);
+                }
+                node = self.rotate_left(&node);
+            }
+            cursor = node.borrow().parent.upgrade();
+        }
+    }
+}
+
+impl<T: PartialEq + PartialOrd> AvlTree<T> {
+    pub fn insert(&mut self, x: T) {
+        let Some(mut cursor) = self.root.clone() else {
+            self.root = AvlNode::new(x).to_root();
+            return;
+        };
+        loop {
+            let go_left = x < cursor.borrow().value;
+            let next = {
+                let node = cursor.borrow();
+                if go_left {
+                    node.left.root.clone()
+                } else {
+                    node.right.root.clone()
+                }
+            };
+            match next {
+                Some(next) => cursor = next,
+                None => {
+                    let leaf = AvlNode::new_leaf(x, Rc::downgrade(&cursor)).to_root();
+                    Self::set_child(&cursor, go_left, leaf);
+                    self.rebalance_up(Some(cursor));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Finds a node holding a value equal to `x`.
+    pub fn find(&self, x: &T) -> Option<AvlNodePtr<T>> {
+        let mut cursor = self.root.clone()?;
+        loop {
+            if &cursor.borrow().value == x {
+                return Some(cursor);
+            }
+            cursor = if &cursor.borrow().value < x {
+                cursor.borrow().right.root.clone()
+            } else {
+                cursor.borrow().left.root.clone()
+            }?;
+        }
+    }
+}
+
+impl<T: Display> Display for AvlTree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = |node: &AvlNodePtr<T>, f: &mut std::fmt::Formatter<'_>| {
+            let node = node.borrow();
+            node.value.fmt(f)?;
+            Ok((node.left.root.clone(), node.right.root.clone()))
+        };
+        fmt_with_count(self.root.clone(), &parts, f, 0)
+    }
+}
+
+pub struct Iter<T>(Option<AvlNodePtr<T>>);
+
+impl<T> Iterator for Iter<T> {
+    type Item = AvlNodePtr<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = AvlNode::successor(self.0.clone()?);
+        std::mem::replace(&mut self.0, next)
+    }
+}
@@ -0,0 +1,648 @@
+use std::{
+    fmt::Debug,
+    mem,
+    ops::{Bound, RangeBounds},
+};
+
+use super::b_tree::DEFAULT_MIN_DEGREE;
+
+enum Node<K, V> {
+    /// `keys[i]` separates `children[i]`, whose keys are less, from
+    /// `children[i + 1]`, whose keys are greater or equal.
+    Internal { keys: Vec<K>, children: Vec<usize> },
+    Leaf {
+        keys: Vec<K>,
+        values: Vec<V>,
+        /// The leaf to the right, on the same level.
+        next: Option<usize>,
+    },
+}
+
+impl<K, V> Node<K, V> {
+    fn empty() -> Self {
+        Node::Leaf {
+            keys: Vec::new(),
+            values: Vec::new(),
+            next: None,
+        }
+    }
+
+    fn keys(&self) -> &[K] {
+        match self {
+            Node::Internal { keys, .. } | Node::Leaf { keys, .. } => keys,
+        }
+    }
+}
+
+/// Which child of an internal node with separators `keys` holds `key`.
+fn route<K: Ord>(keys: &[K], key: &K) -> usize {
+    keys.partition_point(|k| k <= key)
+}
+
+/// An ordered map kept in a B+ tree of minimum degree `t`. Entries live only
+/// in the leaves, which are linked left to right, so a range scan walks
+/// along the leaves without going back up the tree. Internal nodes hold
+/// copies of keys as separators.
+///
+/// Every node but the root holds between `t - 1` and `2t - 1` keys, as in
+/// `BTree`. Nodes live in a `Vec` and link to each other by index.
+pub struct BPlusTree<K, V> {
+    nodes: Vec<Node<K, V>>,
+    free: Vec<usize>,
+    root: usize,
+    t: usize,
+    len: usize,
+}
+
+impl<K, V> BPlusTree<K, V> {
+    pub fn new() -> Self {
+        Self::with_min_degree(DEFAULT_MIN_DEGREE)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `t` is less than 2.
+    pub fn with_min_degree(t: usize) -> Self {
+        assert!(t >= 2, "the minimum degree of a B+ tree is at least 2");
+        Self {
+            nodes: vec![Node::empty()],
+            free: Vec::new(),
+            root: 0,
+            t,
+            len: 0,
+        }
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.t
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes = vec![Node::empty()];
+        self.free.clear();
+        self.root = 0;
+        self.len = 0;
+    }
+
+    /// The number of levels, which is 1 for a tree that is a single leaf.
+    pub fn height(&self) -> usize {
+        let (mut id, mut height) = (self.root, 1);
+        while let Node::Internal { children, .. } = &self.nodes[id] {
+            id = children[0];
+            height += 1;
+        }
+        height
+    }
+
+    /// The leftmost or rightmost leaf.
+    fn edge_leaf(&self, rightmost: bool) -> usize {
+        let mut id = self.root;
+        while let Node::Internal { children, .. } = &self.nodes[id] {
+            id = if rightmost {
+                children[children.len() - 1]
+            } else {
+                children[0]
+            };
+        }
+        id
+    }
+
+    fn leaf(&self, id: usize) -> (&[K], &[V], Option<usize>) {
+        match &self.nodes[id] {
+            Node::Leaf { keys, values, next } => (keys, values, *next),
+            Node::Internal { .. } => unreachable!("not a leaf"),
+        }
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        let (keys, values, _) = self.leaf(self.edge_leaf(false));
+        Some((keys.first()?, values.first()?))
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let (keys, values, _) = self.leaf(self.edge_leaf(true));
+        Some((keys.last()?, values.last()?))
+    }
+
+    pub fn iter(&self) -> Range<'_, K, V> {
+        Range {
+            tree: self,
+            leaf: Some(self.edge_leaf(false)),
+            index: 0,
+            end: Bound::Unbounded,
+        }
+    }
+
+    fn alloc(&mut self, node: Node<K, V>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, id: usize) {
+        self.nodes[id] = Node::empty();
+        self.free.push(id);
+    }
+}
+
+impl<K: Ord + Clone, V> BPlusTree<K, V> {
+    /// Builds a tree from entries sorted by strictly increasing keys in O(n),
+    /// filling the leaves left to right and then each level above them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys are not strictly increasing.
+    pub fn from_sorted(entries: impl IntoIterator<Item = (K, V)>) -> Self {
+        Self::from_sorted_with_min_degree(DEFAULT_MIN_DEGREE, entries)
+    }
+
+    pub fn from_sorted_with_min_degree(
+        t: usize,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        let mut tree = Self::with_min_degree(t);
+        let entries = entries.into_iter().collect::<Vec<_>>();
+        assert!(
+            entries.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "the keys are not strictly increasing"
+        );
+        let n = entries.len();
+        if n == 0 {
+            return tree;
+        }
+        tree.nodes.clear();
+        tree.len = n;
+
+        // Spreading the entries evenly keeps every leaf at least half full.
+        let leaves = n.div_ceil(2 * t - 1);
+        let mut entries = entries.into_iter();
+        let mut level = Vec::with_capacity(leaves);
+        for i in 0..leaves {
+            let size = n * (i + 1) / leaves - n * i / leaves;
+            let (keys, values): (Vec<_>, Vec<_>) = entries.by_ref().take(size).unzip();
+            let min = keys[0].clone();
+            let id = tree.alloc(Node::Leaf {
+                keys,
+                values,
+                next: None,
+            });
+            if let Some(&(_, prev)) = level.last() {
+                if let Node::Leaf { next, .. } = &mut tree.nodes[prev] {
+                    *next = Some(id);
+                }
+            }
+            level.push((min, id));
+        }
+
+        // Each level groups the one below it, with the least key of every
+        // child but the first as a separator.
+        while level.len() > 1 {
+            let m = level.len();
+            let parents = m.div_ceil(2 * t);
+            let mut below = level.into_iter();
+            level = Vec::with_capacity(parents);
+            for i in 0..parents {
+                let size = m * (i + 1) / parents - m * i / parents;
+                let mut group = below.by_ref().take(size);
+                let (min, first) = group.next().expect("a non-empty group");
+                let (keys, mut children): (Vec<_>, Vec<_>) = group.unzip();
+                children.insert(0, first);
+                level.push((min, tree.alloc(Node::Internal { keys, children })));
+            }
+        }
+        tree.root = level[0].1;
+        tree
+    }
+
+    fn leaf_for(&self, key: &K) -> usize {
+        let mut id = self.root;
+        while let Node::Internal { keys, children } = &self.nodes[id] {
+            id = children[route(keys, key)];
+        }
+        id
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let (keys, values, _) = self.leaf(self.leaf_for(key));
+        keys.binary_search(key).ok().map(|i| &values[i])
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let id = self.leaf_for(key);
+        match &mut self.nodes[id] {
+            Node::Leaf { keys, values, .. } => {
+                let i = keys.binary_search(key).ok()?;
+                Some(&mut values[i])
+            }
+            Node::Internal { .. } => unreachable!("not a leaf"),
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Iterates in order over the entries whose keys lie in `range`. After
+    /// one descent to the first leaf, it follows the leaf links.
+    pub fn range(&self, range: impl RangeBounds<K>) -> Range<'_, K, V> {
+        let (leaf, index) = match range.start_bound() {
+            Bound::Included(start) => {
+                let leaf = self.leaf_for(start);
+                (leaf, self.leaf(leaf).0.partition_point(|k| k < start))
+            }
+            Bound::Excluded(start) => {
+                let leaf = self.leaf_for(start);
+                (leaf, self.leaf(leaf).0.partition_point(|k| k <= start))
+            }
+            Bound::Unbounded => (self.edge_leaf(false), 0),
+        };
+        Range {
+            tree: self,
+            leaf: Some(leaf),
+            index,
+            end: range.end_bound().cloned(),
+        }
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (old, split) = self.insert_at(self.root, key, value);
+        if let Some((separator, right)) = split {
+            let left = self.root;
+            self.root = self.alloc(Node::Internal {
+                keys: vec![separator],
+                children: vec![left, right],
+            });
+        }
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Inserts into the subtree at `id`. If the node overflows, it is split
+    /// and the separator and the new right node are returned for the parent.
+    fn insert_at(&mut self, id: usize, key: K, value: V) -> (Option<V>, Option<(K, usize)>) {
+        let max = 2 * self.t - 1;
+        match &mut self.nodes[id] {
+            Node::Leaf { keys, values, .. } => {
+                match keys.binary_search(&key) {
+                    Ok(i) => return (Some(mem::replace(&mut values[i], value)), None),
+                    Err(i) => {
+                        keys.insert(i, key);
+                        values.insert(i, value);
+                    }
+                }
+                if keys.len() <= max {
+                    return (None, None);
+                }
+                (None, Some(self.split_leaf(id)))
+            }
+            Node::Internal { keys, children } => {
+                let i = route(keys, &key);
+                let child = children[i];
+                let (old, split) = self.insert_at(child, key, value);
+                let Some((separator, right)) = split else {
+                    return (old, None);
+                };
+                let Node::Internal { keys, children } = &mut self.nodes[id] else {
+                    unreachable!("not an internal node");
+                };
+                keys.insert(i, separator);
+                children.insert(i + 1, right);
+                if keys.len() <= max {
+                    return (old, None);
+                }
+                (old, Some(self.split_internal(id)))
+            }
+        }
+    }
+
+    /// Moves the upper half of an overflowing leaf into a new leaf linked
+    /// after it. The separator is a copy of the new leaf's least key.
+    fn split_leaf(&mut self, id: usize) -> (K, usize) {
+        let t = self.t;
+        let Node::Leaf { keys, values, next } = &mut self.nodes[id] else {
+            unreachable!("not a leaf");
+        };
+        let keys = keys.split_off(t);
+        let values = values.split_off(t);
+        let separator = keys[0].clone();
+        let old_next = *next;
+        let right = self.alloc(Node::Leaf {
+            keys,
+            values,
+            next: old_next,
+        });
+        if let Node::Leaf { next, .. } = &mut self.nodes[id] {
+            *next = Some(right);
+        }
+        (separator, right)
+    }
+
+    /// Moves the upper half of an overflowing internal node into a new one.
+    /// The middle separator moves up rather than being copied.
+    fn split_internal(&mut self, id: usize) -> (K, usize) {
+        let t = self.t;
+        let Node::Internal { keys, children } = &mut self.nodes[id] else {
+            unreachable!("not an internal node");
+        };
+        let right_keys = keys.split_off(t + 1);
+        let right_children = children.split_off(t + 1);
+        let separator = keys.pop().expect("an overflowing node");
+        let right = self.alloc(Node::Internal {
+            keys: right_keys,
+            children: right_children,
+        });
+        (separator, right)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        let entry = self.remove_at(self.root, key)?;
+        self.len -= 1;
+        if let Node::Internal { keys, children } = &self.nodes[self.root] {
+            if keys.is_empty() {
+                let child = children[0];
+                self.release(self.root);
+                self.root = child;
+            }
+        }
+        Some(entry)
+    }
+
+    /// Removes from the subtree at `id`, refilling any child left with too
+    /// few keys on the way back up. Separators may go stale, which is fine
+    /// as long as they still separate.
+    fn remove_at(&mut self, id: usize, key: &K) -> Option<(K, V)> {
+        match &mut self.nodes[id] {
+            Node::Leaf { keys, values, .. } => {
+                let i = keys.binary_search(key).ok()?;
+                Some((keys.remove(i), values.remove(i)))
+            }
+            Node::Internal { keys, children } => {
+                let i = route(keys, key);
+                let child = children[i];
+                let entry = self.remove_at(child, key)?;
+                if self.nodes[child].keys().len() < self.t - 1 {
+                    self.refill(id, i);
+                }
+                Some(entry)
+            }
+        }
+    }
+
+    /// Gives child `i` of `parent`, which has one key too few, a key from a
+    /// sibling, or merges it with one if the sibling has none to spare.
+    fn refill(&mut self, parent: usize, i: usize) {
+        let max = 2 * self.t - 1;
+        let l = i.saturating_sub(1);
+        let Node::Internal { children, .. } = &self.nodes[parent] else {
+            unreachable!("not an internal node");
+        };
+        let (left, right) = (children[l], children[l + 1]);
+        let mut a = mem::replace(&mut self.nodes[left], Node::empty());
+        let mut b = mem::replace(&mut self.nodes[right], Node::empty());
+        let Node::Internal {
+            keys: separators,
+            children,
+        } = &mut self.nodes[parent]
+        else {
+            unreachable!("not an internal node");
+        };
+
+        let merged = match (&mut a, &mut b) {
+            (
+                Node::Leaf {
+                    keys: a_keys,
+                    values: a_values,
+                    next: a_next,
+                },
+                Node::Leaf {
+                    keys: b_keys,
+                    values: b_values,
+                    next: b_next,
+                },
+            ) => {
+                if a_keys.len() + b_keys.len() <= max {
+                    a_keys.append(b_keys);
+                    a_values.append(b_values);
+                    *a_next = *b_next;
+                    separators.remove(l);
+                    children.remove(l + 1);
+                    true
+                } else {
+                    if a_keys.len() < b_keys.len() {
+                        a_keys.push(b_keys.remove(0));
+                        a_values.push(b_values.remove(0));
+                    } else {
+                        b_keys.insert(0, a_keys.pop().expect("a key to lend"));
+                        b_values.insert(0, a_values.pop().expect("a key to lend"));
+                    }
+                    separators[l] = b_keys[0].clone();
+                    false
+                }
+            }
+            (
+                Node::Internal {
+                    keys: a_keys,
+                    children: a_children,
+                },
+                Node::Internal {
+                    keys: b_keys,
+                    children: b_children,
+                },
+            ) => {
+                if a_keys.len() + b_keys.len() < max {
+                    a_keys.push(separators.remove(l));
+                    a_keys.append(b_keys);
+                    a_children.append(b_children);
+                    children.remove(l + 1);
+                    true
+                } else {
+                    if a_keys.len() < b_keys.len() {
+                        a_keys.push(mem::replace(&mut separators[l], b_keys.remove(0)));
+                        a_children.push(b_children.remove(0));
+                    } else {
+                        let key = a_keys.pop().expect("a key to lend");
+                        b_keys.insert(0, mem::replace(&mut separators[l], key));
+                        b_children.insert(0, a_children.pop().expect("a child to lend"));
+                    }
+                    false
+                }
+            }
+            _ => unreachable!("siblings are on the same level"),
+        };
+
+        self.nodes[left] = a;
+        if merged {
+            self.release(right);
+        } else {
+            self.nodes[right] = b;
+        }
+    }
+
+    /// Checks the B+ tree properties and the leaf links, and returns the
+    /// height. Meant for tests.
+    pub fn validate(&self) -> Result<usize, &'static str> {
+        let mut leaves = Vec::new();
+        let height = self.validate_node(self.root, None, None, true, &mut leaves)?;
+
+        let mut linked = Vec::new();
+        let mut leaf = Some(self.edge_leaf(false));
+        while let Some(id) = leaf {
+            if linked.len() > leaves.len() {
+                return Err("the leaf links loop");
+            }
+            linked.push(id);
+            leaf = self.leaf(id).2;
+        }
+        if linked != leaves {
+            return Err("the leaf links skip or reorder leaves");
+        }
+        let count = leaves
+            .iter()
+            .map(|&id| self.leaf(id).0.len())
+            .sum::<usize>();
+        if count != self.len {
+            return Err("the length is off");
+        }
+        Ok(height)
+    }
+
+    /// Checks the subtree at `id`, whose keys must lie in `lo..hi`, collects
+    /// its leaves in order, and returns its height.
+    fn validate_node(
+        &self,
+        id: usize,
+        lo: Option<&K>,
+        hi: Option<&K>,
+        is_root: bool,
+        leaves: &mut Vec<usize>,
+    ) -> Result<usize, &'static str> {
+        let keys = self.nodes[id].keys();
+        let min = match (is_root, &self.nodes[id]) {
+            (true, Node::Leaf { .. }) => 0,
+            (true, Node::Internal { .. }) => 1,
+            (false, _) => self.t - 1,
+        };
+        if keys.len() < min || keys.len() > 2 * self.t - 1 {
+            return Err("a node has too few or too many keys");
+        }
+        if keys.windows(2).any(|pair| pair[0] >= pair[1])
+            || lo.is_some_and(|lo| keys.first().is_some_and(|k| k < lo))
+            || hi.is_some_and(|hi| keys.last().is_some_and(|k| k >= hi))
+        {
+            return Err("keys are out of order");
+        }
+        match &self.nodes[id] {
+            Node::Leaf { values, .. } => {
+                if values.len() != keys.len() {
+                    return Err("keys and values differ in number");
+                }
+                leaves.push(id);
+                Ok(1)
+            }
+            Node::Internal { children, .. } => {
+                if children.len() != keys.len() + 1 {
+                    return Err("an internal node has the wrong number of children");
+                }
+                let mut height = None;
+                for (i, &child) in children.iter().enumerate() {
+                    let lo = if i == 0 { lo } else { Some(&keys[i - 1]) };
+                    let hi = keys.get(i).or(hi);
+                    let h = self.validate_node(child, lo, hi, false, leaves)?;
+                    if height.is_some_and(|height| height != h) {
+                        return Err("leaves are at different depths");
+                    }
+                    height = Some(h);
+                }
+                Ok(1 + height.unwrap_or(0))
+            }
+        }
+    }
+}
+
+impl<K, V> Default for BPlusTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> Debug for BPlusTree<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(K, V)> for BPlusTree<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for (k, v) in iter {
+            tree.insert(k, v);
+        }
+        tree
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a BPlusTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Range<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An in-order iterator that walks along the linked leaves.
+pub struct Range<'a, K, V> {
+    tree: &'a BPlusTree<K, V>,
+    leaf: Option<usize>,
+    index: usize,
+    end: Bound<K>,
+}
+
+impl<'a, K: Ord, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = self.tree;
+        loop {
+            let (keys, values, next) = tree.leaf(self.leaf?);
+            let Some(key) = keys.get(self.index) else {
+                self.leaf = next;
+                self.index = 0;
+                continue;
+            };
+            let past_end = match &self.end {
+                Bound::Included(end) => key > end,
+                Bound::Excluded(end) => key >= end,
+                Bound::Unbounded => false,
+            };
+            if past_end {
+                self.leaf = None;
+                return None;
+            }
+            self.index += 1;
+            return Some((key, &values[self.index - 1]));
+        }
+    }
+}
@@ -0,0 +1,530 @@
+use std::{cmp::Ordering, fmt::Debug, mem};
+
+/// The minimum degree `BTree::new` uses, the same branching factor as
+/// std's `BTreeMap`.
+pub const DEFAULT_MIN_DEGREE: usize = 6;
+
+struct Node<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    /// Empty for leaves, and one longer than `keys` otherwise.
+    children: Vec<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V> {
+    fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Splits the full child `i` around its median key, which moves up into
+    /// this node.
+    fn split_child(&mut self, i: usize, t: usize) {
+        let child = &mut self.children[i];
+        let keys = child.keys.split_off(t);
+        let values = child.values.split_off(t);
+        let children = if child.is_leaf() {
+            Vec::new()
+        } else {
+            child.children.split_off(t)
+        };
+        let key = child.keys.pop().expect("a full child");
+        let value = child.values.pop().expect("a full child");
+        self.keys.insert(i, key);
+        self.values.insert(i, value);
+        self.children.insert(
+            i + 1,
+            Node {
+                keys,
+                values,
+                children,
+            },
+        );
+    }
+
+    /// Moves key `i` and everything in child `i + 1` into child `i`.
+    fn merge(&mut self, i: usize) {
+        let right = self.children.remove(i + 1);
+        let key = self.keys.remove(i);
+        let value = self.values.remove(i);
+        let left = &mut self.children[i];
+        left.keys.push(key);
+        left.values.push(value);
+        left.keys.extend(right.keys);
+        left.values.extend(right.values);
+        left.children.extend(right.children);
+    }
+
+    /// Makes sure child `i` has at least `t` keys before a removal descends
+    /// into it, by borrowing from a sibling or merging with one. Returns the
+    /// index the child ends up at.
+    fn fill(&mut self, i: usize, t: usize) -> usize {
+        if self.children[i].keys.len() >= t {
+            return i;
+        }
+        if i > 0 && self.children[i - 1].keys.len() >= t {
+            let (left, right) = self.children.split_at_mut(i);
+            let (left, child) = (&mut left[i - 1], &mut right[0]);
+            let key = left.keys.pop().expect("a key to lend");
+            let value = left.values.pop().expect("a key to lend");
+            child
+                .keys
+                .insert(0, mem::replace(&mut self.keys[i - 1], key));
+            child
+                .values
+                .insert(0, mem::replace(&mut self.values[i - 1], value));
+            if let Some(grandchild) = left.children.pop() {
+                child.children.insert(0, grandchild);
+            }
+            i
+        } else if i + 1 < self.children.len() && self.children[i + 1].keys.len() >= t {
+            let (left, right) = self.children.split_at_mut(i + 1);
+            let (child, right) = (&mut left[i], &mut right[0]);
+            let key = right.keys.remove(0);
+            let value = right.values.remove(0);
+            child.keys.push(mem::replace(&mut self.keys[i], key));
+            child.values.push(mem::replace(&mut self.values[i], value));
+            if !right.is_leaf() {
+                child.children.push(right.children.remove(0));
+            }
+            i
+        } else if i + 1 < self.children.len() {
+            self.merge(i);
+            i
+        } else {
+            self.merge(i - 1);
+            i - 1
+        }
+    }
+
+    fn pop_first(&mut self, t: usize) -> (K, V) {
+        if self.is_leaf() {
+            return (self.keys.remove(0), self.values.remove(0));
+        }
+        let i = self.fill(0, t);
+        self.children[i].pop_first(t)
+    }
+
+    fn pop_last(&mut self, t: usize) -> (K, V) {
+        if self.is_leaf() {
+            let key = self.keys.pop().expect("a non-empty leaf");
+            let value = self.values.pop().expect("a non-empty leaf");
+            return (key, value);
+        }
+        let i = self.fill(self.children.len() - 1, t);
+        self.children[i].pop_last(t)
+    }
+}
+
+impl<K: Ord, V> Node<K, V> {
+    /// Inserts into a node that is not full, splitting full children on the
+    /// way down so a split never has to climb back up.
+    fn insert(&mut self, key: K, value: V, t: usize) -> Option<V> {
+        let mut node = self;
+        loop {
+            let mut i = match node.keys.binary_search(&key) {
+                Ok(i) => return Some(mem::replace(&mut node.values[i], value)),
+                Err(i) => i,
+            };
+            if node.is_leaf() {
+                node.keys.insert(i, key);
+                node.values.insert(i, value);
+                return None;
+            }
+            if node.children[i].keys.len() == 2 * t - 1 {
+                node.split_child(i, t);
+                match key.cmp(&node.keys[i]) {
+                    Ordering::Less => {}
+                    Ordering::Equal => return Some(mem::replace(&mut node.values[i], value)),
+                    Ordering::Greater => i += 1,
+                }
+            }
+            node = &mut node.children[i];
+        }
+    }
+
+    /// Removes `key` from the subtree, which has at least `t` keys unless it
+    /// is the root, in one pass down.
+    fn remove(&mut self, key: &K, t: usize) -> Option<(K, V)> {
+        match self.keys.binary_search(key) {
+            Ok(i) if self.is_leaf() => Some((self.keys.remove(i), self.values.remove(i))),
+            Ok(i) => {
+                let (key, value) = if self.children[i].keys.len() >= t {
+                    self.children[i].pop_last(t)
+                } else if self.children[i + 1].keys.len() >= t {
+                    self.children[i + 1].pop_first(t)
+                } else {
+                    self.merge(i);
+                    return self.children[i].remove(key, t);
+                };
+                Some((
+                    mem::replace(&mut self.keys[i], key),
+                    mem::replace(&mut self.values[i], value),
+                ))
+            }
+            Err(_) if self.is_leaf() => None,
+            Err(i) => {
+                let i = self.fill(i, t);
+                self.children[i].remove(key, t)
+            }
+        }
+    }
+
+    /// Checks the subtree whose keys must lie strictly between `lo` and `hi`,
+    /// and returns its height.
+    fn validate(
+        &self,
+        lo: Option<&K>,
+        hi: Option<&K>,
+        t: usize,
+        is_root: bool,
+        count: &mut usize,
+    ) -> Result<usize, &'static str> {
+        let min = if is_root { 0 } else { t - 1 };
+        if self.keys.len() < min || self.keys.len() > 2 * t - 1 {
+            return Err("a node has too few or too many keys");
+        }
+        if self.keys.len() != self.values.len() {
+            return Err("keys and values differ in number");
+        }
+        if self.keys.windows(2).any(|pair| pair[0] >= pair[1])
+            || lo.is_some_and(|lo| self.keys.first().is_some_and(|k| k <= lo))
+            || hi.is_some_and(|hi| self.keys.last().is_some_and(|k| k >= hi))
+        {
+            return Err("keys are out of order");
+        }
+        *count += self.keys.len();
+        if self.is_leaf() {
+            return Ok(1);
+        }
+        if self.children.len() != self.keys.len() + 1 {
+            return Err("an internal node has the wrong number of children");
+        }
+        let mut height = None;
+        for (i, child) in self.children.iter().enumerate() {
+            let lo = if i == 0 { lo } else { Some(&self.keys[i - 1]) };
+            let hi = self.keys.get(i).or(hi);
+            let h = child.validate(lo, hi, t, false, count)?;
+            if height.is_some_and(|height| height != h) {
+                return Err("leaves are at different depths");
+            }
+            height = Some(h);
+        }
+        Ok(1 + height.unwrap_or(0))
+    }
+}
+
+/// An ordered map kept in a B-tree of minimum degree `t`, following CLRS:
+/// every node but the root holds between `t - 1` and `2t - 1` keys, and all
+/// leaves are at the same depth. Inserts split full nodes and removals refill
+/// thin ones on the way down, so neither ever walks back up.
+///
+/// `t` is picked at runtime with `with_min_degree`.
+pub struct BTree<K, V> {
+    root: Node<K, V>,
+    t: usize,
+    len: usize,
+}
+
+impl<K, V> BTree<K, V> {
+    pub fn new() -> Self {
+        Self::with_min_degree(DEFAULT_MIN_DEGREE)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `t` is less than 2.
+    pub fn with_min_degree(t: usize) -> Self {
+        assert!(t >= 2, "the minimum degree of a B-tree is at least 2");
+        Self {
+            root: Node::new(),
+            t,
+            len: 0,
+        }
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.t
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.root = Node::new();
+        self.len = 0;
+    }
+
+    /// The number of levels, which is 1 for a tree that is a single leaf.
+    pub fn height(&self) -> usize {
+        let mut node = &self.root;
+        let mut height = 1;
+        while let Some(child) = node.children.first() {
+            node = child;
+            height += 1;
+        }
+        height
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        let mut node = &self.root;
+        while let Some(child) = node.children.first() {
+            node = child;
+        }
+        Some((node.keys.first()?, node.values.first()?))
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let mut node = &self.root;
+        while let Some(child) = node.children.last() {
+            node = child;
+        }
+        Some((node.keys.last()?, node.values.last()?))
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        let entry = self.root.pop_first(self.t);
+        self.len -= 1;
+        self.collapse_root();
+        Some(entry)
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        let entry = self.root.pop_last(self.t);
+        self.len -= 1;
+        self.collapse_root();
+        Some(entry)
+    }
+
+    /// Drops the root if merges below it took its last key. Removals merge
+    /// on the way down even when the key turns out to be missing.
+    fn collapse_root(&mut self) {
+        if self.root.keys.is_empty() {
+            if let Some(child) = self.root.children.pop() {
+                self.root = child;
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.descend(&self.root);
+        iter
+    }
+}
+
+impl<K: Ord, V> BTree<K, V> {
+    /// Builds a tree from entries sorted by strictly increasing keys in O(n),
+    /// without a single split.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys are not strictly increasing.
+    pub fn from_sorted(entries: impl IntoIterator<Item = (K, V)>) -> Self {
+        Self::from_sorted_with_min_degree(DEFAULT_MIN_DEGREE, entries)
+    }
+
+    pub fn from_sorted_with_min_degree(
+        t: usize,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        let mut tree = Self::with_min_degree(t);
+        let entries = entries.into_iter().collect::<Vec<_>>();
+        assert!(
+            entries.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "the keys are not strictly increasing"
+        );
+        let n = entries.len();
+        let mut height = 1;
+        while capacity(t, height) < n + 1 {
+            height += 1;
+        }
+        tree.root = build(&mut entries.into_iter(), n, height, 2, t);
+        tree.len = n;
+        tree
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut node = &self.root;
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(i) => return Some(&node.values[i]),
+                Err(i) => node = node.children.get(i)?,
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(i) => return Some(&mut node.values[i]),
+                Err(i) => node = node.children.get_mut(i)?,
+            }
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.root.keys.len() == 2 * self.t - 1 {
+            let old_root = mem::replace(&mut self.root, Node::new());
+            self.root.children.push(old_root);
+            self.root.split_child(0, self.t);
+        }
+        let old = self.root.insert(key, value, self.t);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        let entry = self.root.remove(key, self.t);
+        if entry.is_some() {
+            self.len -= 1;
+        }
+        self.collapse_root();
+        entry
+    }
+
+    /// Checks the B-tree properties and returns the height. Meant for tests.
+    pub fn validate(&self) -> Result<usize, &'static str> {
+        let mut count = 0;
+        let height = self.root.validate(None, None, self.t, true, &mut count)?;
+        if !self.root.is_leaf() && self.root.keys.is_empty() {
+            return Err("an internal root has no keys");
+        }
+        if count != self.len {
+            return Err("the length is off");
+        }
+        Ok(height)
+    }
+}
+
+/// How many keys plus one a subtree of `height` levels holds at most.
+fn capacity(t: usize, height: usize) -> usize {
+    (2 * t).saturating_pow(height as u32)
+}
+
+/// Builds a subtree of `height` levels from the next `n` entries, spreading
+/// them evenly over at least `min_children` children per internal node.
+fn build<K, V>(
+    entries: &mut impl Iterator<Item = (K, V)>,
+    n: usize,
+    height: usize,
+    min_children: usize,
+    t: usize,
+) -> Node<K, V> {
+    let mut node = Node::new();
+    if height == 1 {
+        (node.keys, node.values) = entries.take(n).unzip();
+        return node;
+    }
+    let children = (n + 1).div_ceil(capacity(t, height - 1)).max(min_children);
+    for i in 0..children {
+        let size = (n + 1) * (i + 1) / children - (n + 1) * i / children - 1;
+        node.children.push(build(entries, size, height - 1, t, t));
+        if i + 1 < children {
+            let (key, value) = entries.next().expect("enough entries");
+            node.keys.push(key);
+            node.values.push(value);
+        }
+    }
+    node
+}
+
+impl<K, V> Default for BTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for BTree<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BTree<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for (k, v) in iter {
+            tree.insert(k, v);
+        }
+        tree
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a BTree<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An in-order iterator, keeping the path to the next entry on a stack.
+pub struct Iter<'a, K, V> {
+    /// Nodes on the path, each with the index of its next key.
+    stack: Vec<(&'a Node<K, V>, usize)>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn descend(&mut self, mut node: &'a Node<K, V>) {
+        loop {
+            self.stack.push((node, 0));
+            match node.children.first() {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, i) = self.stack.last_mut()?;
+            let node: &'a Node<K, V> = node;
+            if *i == node.keys.len() {
+                self.stack.pop();
+                continue;
+            }
+            let index = *i;
+            *i += 1;
+            if let Some(child) = node.children.get(index + 1) {
+                self.descend(child);
+            }
+            return Some((&node.keys[index], &node.values[index]));
+        }
+    }
+}
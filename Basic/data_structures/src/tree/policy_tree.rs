@@ -0,0 +1,174 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use super::{BinaryTree, Iter, TreeNodePtr};
+
+/// What `PolicyTree::insert` does with a value equal to one already in the
+/// tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Duplicates {
+    /// Keeps every value in its own node, after the equal ones, as
+    /// `BinaryTree` does.
+    #[default]
+    Allow,
+    /// Keeps the value already in the tree and hands back the new one.
+    Reject,
+    /// Puts the new value in place of the old one and hands back the old one.
+    Replace,
+    /// Keeps one node per distinct value and counts the insertions in
+    /// `Counted::count`, as a multiset.
+    Count,
+}
+
+/// A value of a `PolicyTree` and how many times it was inserted, ordered by
+/// the value alone. The count is always 1 unless the policy is
+/// `Duplicates::Count`.
+#[derive(Debug)]
+pub struct Counted<T> {
+    pub value: T,
+    pub count: usize,
+}
+
+impl<T: PartialEq> PartialEq for Counted<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Counted<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Display> Display for Counted<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)?;
+        if self.count > 1 {
+            write!(f, " x{}", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+/// A `BinaryTree` with a policy for values equal to one already in it.
+///
+/// The policy belongs to the whole tree, so it is kept here rather than in
+/// every subtree, and the counts of a multiset are kept next to each value
+/// in a `Counted`.
+#[derive(Debug)]
+pub struct PolicyTree<T> {
+    tree: BinaryTree<Counted<T>>,
+    duplicates: Duplicates,
+}
+
+impl<T> PolicyTree<T> {
+    pub fn new() -> Self {
+        Self::with_duplicates(Duplicates::default())
+    }
+
+    pub fn with_duplicates(duplicates: Duplicates) -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            duplicates,
+        }
+    }
+
+    pub fn duplicates(&self) -> Duplicates {
+        self.duplicates
+    }
+
+    /// The tree underneath, one `Counted` per node.
+    pub fn tree(&self) -> &BinaryTree<Counted<T>> {
+        &self.tree
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    pub fn clean(&mut self) {
+        self.tree.clean()
+    }
+}
+
+impl<T: PartialEq + PartialOrd> PolicyTree<T> {
+    pub fn iter(&self) -> Iter<Counted<T>> {
+        self.tree.iter()
+    }
+
+    /// Inserts `x`, following the tree's `Duplicates` policy if it equals a
+    /// value already in the tree. Returns the value the policy turned away:
+    /// `x` itself under `Reject`, or the old value under `Replace`.
+    pub fn insert(&mut self, x: T) -> Option<T> {
+        if self.duplicates != Duplicates::Allow {
+            if let Some(node) = self.find(&x) {
+                let mut node = node.borrow_mut();
+                return match self.duplicates {
+                    Duplicates::Reject => Some(x),
+                    Duplicates::Replace => Some(std::mem::replace(&mut node.value.value, x)),
+                    Duplicates::Count => {
+                        node.value.count += 1;
+                        None
+                    }
+                    Duplicates::Allow => unreachable!(),
+                };
+            }
+        }
+        self.tree.insert(Counted { value: x, count: 1 });
+        None
+    }
+
+    /// The node of a value equal to `x`.
+    pub fn find(&self, x: &T) -> Option<TreeNodePtr<Counted<T>>> {
+        let mut cursor = self.tree.root.clone()?;
+        loop {
+            if &cursor.borrow().value.value == x {
+                return Some(cursor);
+            }
+            cursor = if &cursor.borrow().value.value < x {
+                cursor.borrow().right.root.clone()
+            } else {
+                cursor.borrow().left.root.clone()
+            }?;
+        }
+    }
+
+    /// Removes the node of one value equal to `x` with all its counts, as
+    /// `BinaryTree::remove_value` does.
+    pub fn remove_value(&mut self, x: &T) -> Result<Option<T>, TreeNodePtr<Counted<T>>> {
+        let Some(node) = self.find(x) else {
+            return Ok(None);
+        };
+        self.tree.take(node).map(|counted| Some(counted.value))
+    }
+
+    /// Removes one value equal to `x` and returns it. A value inserted
+    /// several times under `Duplicates::Count` loses one count and a copy of
+    /// it is returned; otherwise this is `remove_value`.
+    pub fn remove_one(&mut self, x: &T) -> Result<Option<T>, TreeNodePtr<Counted<T>>>
+    where
+        T: Clone,
+    {
+        let Some(node) = self.find(x) else {
+            return Ok(None);
+        };
+        if node.borrow().value.count > 1 {
+            let mut node = node.borrow_mut();
+            node.value.count -= 1;
+            return Ok(Some(node.value.value.clone()));
+        }
+        self.tree.take(node).map(|counted| Some(counted.value))
+    }
+}
+
+impl<T> Default for PolicyTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Display> Display for PolicyTree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tree.fmt(f)
+    }
+}
@@ -0,0 +1,548 @@
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+};
+
+/// The index of the sentinel that stands for every leaf and for the parent of
+/// the root, as in CLRS. It is always black.
+const NIL: usize = 0;
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Black,
+}
+
+struct Node<K, V> {
+    /// `None` for the sentinel and for freed slots.
+    entry: Option<(K, V)>,
+    color: Color,
+    parent: usize,
+    children: [usize; 2],
+}
+
+impl<K, V> Node<K, V> {
+    fn sentinel() -> Self {
+        Self {
+            entry: None,
+            color: Color::Black,
+            parent: NIL,
+            children: [NIL; 2],
+        }
+    }
+}
+
+/// An ordered map kept in a red-black tree, following CLRS: a black sentinel
+/// stands for the leaves, inserts are fixed up by recoloring and at most two
+/// rotations, and removals by at most three.
+///
+/// Nodes live in a `Vec` and link to each other by index, and slots of
+/// removed nodes are reused.
+pub struct Map<K, V> {
+    nodes: Vec<Node<K, V>>,
+    root: usize,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<K, V> Map<K, V> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::sentinel()],
+            root: NIL,
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.truncate(1);
+        self.nodes[NIL] = Node::sentinel();
+        self.root = NIL;
+        self.free.clear();
+        self.len = 0;
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.entry(self.extreme(self.root, LEFT))
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entry(self.extreme(self.root, RIGHT))
+    }
+
+    pub fn iter(&self) -> Range<'_, K, V> {
+        Range {
+            map: self,
+            front: self.extreme(self.root, LEFT),
+            back: self.extreme(self.root, RIGHT),
+        }
+    }
+
+    fn entry(&self, x: usize) -> Option<(&K, &V)> {
+        self.nodes[x].entry.as_ref().map(|(k, v)| (k, v))
+    }
+
+    fn key(&self, x: usize) -> &K {
+        &self.nodes[x].entry.as_ref().expect("not the sentinel").0
+    }
+
+    fn color(&self, x: usize) -> Color {
+        self.nodes[x].color
+    }
+
+    fn parent(&self, x: usize) -> usize {
+        self.nodes[x].parent
+    }
+
+    fn child(&self, x: usize, side: usize) -> usize {
+        self.nodes[x].children[side]
+    }
+
+    /// Which child of its parent `x` is. `x` may be the sentinel standing in
+    /// for a removed node, whose sibling is then never the sentinel.
+    fn side(&self, x: usize) -> usize {
+        if x == self.child(self.parent(x), LEFT) {
+            LEFT
+        } else {
+            RIGHT
+        }
+    }
+
+    /// The leftmost or rightmost node under `x`.
+    fn extreme(&self, mut x: usize, side: usize) -> usize {
+        if x != NIL {
+            while self.child(x, side) != NIL {
+                x = self.child(x, side);
+            }
+        }
+        x
+    }
+
+    /// The next node in order towards `side`: the successor for `RIGHT` and
+    /// the predecessor for `LEFT`.
+    fn step(&self, mut x: usize, side: usize) -> usize {
+        if self.child(x, side) != NIL {
+            return self.extreme(self.child(x, side), 1 - side);
+        }
+        let mut y = self.parent(x);
+        while y != NIL && x == self.child(y, side) {
+            x = y;
+            y = self.parent(y);
+        }
+        y
+    }
+
+    /// Rotates `x` down towards `side`, lifting its other child above it.
+    /// `rotate(x, LEFT)` is CLRS's LEFT-ROTATE.
+    fn rotate(&mut self, x: usize, side: usize) {
+        let y = self.child(x, 1 - side);
+        let inner = self.child(y, side);
+        self.nodes[x].children[1 - side] = inner;
+        if inner != NIL {
+            self.nodes[inner].parent = x;
+        }
+        self.transplant(x, y);
+        self.nodes[y].children[side] = x;
+        self.nodes[x].parent = y;
+    }
+
+    /// Puts `v` where `u` hangs from its parent. `v` may be the sentinel,
+    /// whose parent is still set so `remove_fixup` can climb from it.
+    fn transplant(&mut self, u: usize, v: usize) {
+        let parent = self.parent(u);
+        if parent == NIL {
+            self.root = v;
+        } else {
+            let side = self.side(u);
+            self.nodes[parent].children[side] = v;
+        }
+        self.nodes[v].parent = parent;
+    }
+
+    fn alloc(&mut self, key: K, value: V, parent: usize) -> usize {
+        let node = Node {
+            entry: Some((key, value)),
+            color: Color::Red,
+            parent,
+            children: [NIL; 2],
+        };
+        match self.free.pop() {
+            Some(x) => {
+                self.nodes[x] = node;
+                x
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn insert_fixup(&mut self, mut z: usize) {
+        while self.color(self.parent(z)) == Color::Red {
+            let p = self.parent(z);
+            let g = self.parent(p);
+            let side = self.side(p);
+            let uncle = self.child(g, 1 - side);
+            if self.color(uncle) == Color::Red {
+                self.nodes[p].color = Color::Black;
+                self.nodes[uncle].color = Color::Black;
+                self.nodes[g].color = Color::Red;
+                z = g;
+            } else {
+                if z == self.child(p, 1 - side) {
+                    z = p;
+                    self.rotate(z, side);
+                }
+                let p = self.parent(z);
+                let g = self.parent(p);
+                self.nodes[p].color = Color::Black;
+                self.nodes[g].color = Color::Red;
+                self.rotate(g, 1 - side);
+            }
+        }
+        let root = self.root;
+        self.nodes[root].color = Color::Black;
+    }
+
+    fn remove_node(&mut self, z: usize) -> (K, V) {
+        let mut removed_color = self.color(z);
+        let x;
+        if self.child(z, LEFT) == NIL {
+            x = self.child(z, RIGHT);
+            self.transplant(z, x);
+        } else if self.child(z, RIGHT) == NIL {
+            x = self.child(z, LEFT);
+            self.transplant(z, x);
+        } else {
+            let y = self.extreme(self.child(z, RIGHT), LEFT);
+            removed_color = self.color(y);
+            x = self.child(y, RIGHT);
+            if self.parent(y) == z {
+                self.nodes[x].parent = y;
+            } else {
+                self.transplant(y, x);
+                let right = self.child(z, RIGHT);
+                self.nodes[y].children[RIGHT] = right;
+                self.nodes[right].parent = y;
+            }
+            self.transplant(z, y);
+            let left = self.child(z, LEFT);
+            self.nodes[y].children[LEFT] = left;
+            self.nodes[left].parent = y;
+            self.nodes[y].color = self.color(z);
+        }
+        if removed_color == Color::Black {
+            self.remove_fixup(x);
+        }
+
+        self.len -= 1;
+        self.free.push(z);
+        let node = std::mem::replace(&mut self.nodes[z], Node::sentinel());
+        node.entry.expect("not the sentinel")
+    }
+
+    fn remove_fixup(&mut self, mut x: usize) {
+        while x != self.root && self.color(x) == Color::Black {
+            let p = self.parent(x);
+            let side = self.side(x);
+            let mut w = self.child(p, 1 - side);
+            if self.color(w) == Color::Red {
+                self.nodes[w].color = Color::Black;
+                self.nodes[p].color = Color::Red;
+                self.rotate(p, side);
+                w = self.child(p, 1 - side);
+            }
+            let near = self.child(w, side);
+            let far = self.child(w, 1 - side);
+            if self.color(near) == Color::Black && self.color(far) == Color::Black {
+                self.nodes[w].color = Color::Red;
+                x = p;
+            } else {
+                if self.color(far) == Color::Black {
+                    self.nodes[near].color = Color::Black;
+                    self.nodes[w].color = Color::Red;
+                    self.rotate(w, 1 - side);
+                    w = self.child(p, 1 - side);
+                }
+                self.nodes[w].color = self.color(p);
+                self.nodes[p].color = Color::Black;
+                let far = self.child(w, 1 - side);
+                self.nodes[far].color = Color::Black;
+                self.rotate(p, side);
+                x = self.root;
+            }
+        }
+        self.nodes[x].color = Color::Black;
+    }
+}
+
+impl<K: Ord, V> Map<K, V> {
+    fn find(&self, key: &K) -> usize {
+        let mut x = self.root;
+        while x != NIL {
+            x = match key.cmp(self.key(x)) {
+                Ordering::Less => self.child(x, LEFT),
+                Ordering::Greater => self.child(x, RIGHT),
+                Ordering::Equal => return x,
+            };
+        }
+        NIL
+    }
+
+    /// The first node whose key satisfies `past`, which must be false for
+    /// some prefix of the keys and true for the rest.
+    fn first_where(&self, past: impl Fn(&K) -> bool) -> usize {
+        let (mut x, mut found) = (self.root, NIL);
+        while x != NIL {
+            if past(self.key(x)) {
+                found = x;
+                x = self.child(x, LEFT);
+            } else {
+                x = self.child(x, RIGHT);
+            }
+        }
+        found
+    }
+
+    /// The last node whose key satisfies `before`, which must be true for
+    /// some prefix of the keys and false for the rest.
+    fn last_where(&self, before: impl Fn(&K) -> bool) -> usize {
+        let (mut x, mut found) = (self.root, NIL);
+        while x != NIL {
+            if before(self.key(x)) {
+                found = x;
+                x = self.child(x, RIGHT);
+            } else {
+                x = self.child(x, LEFT);
+            }
+        }
+        found
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entry(self.find(key)).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let x = self.find(key);
+        self.nodes[x].entry.as_mut().map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key) != NIL
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaced. The key
+    /// already in the map is kept.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (mut parent, mut x, mut side) = (NIL, self.root, LEFT);
+        while x != NIL {
+            parent = x;
+            side = match key.cmp(self.key(x)) {
+                Ordering::Less => LEFT,
+                Ordering::Greater => RIGHT,
+                Ordering::Equal => {
+                    let (_, old) = self.nodes[x].entry.as_mut().expect("not the sentinel");
+                    return Some(std::mem::replace(old, value));
+                }
+            };
+            x = self.child(x, side);
+        }
+        let z = self.alloc(key, value, parent);
+        if parent == NIL {
+            self.root = z;
+        } else {
+            self.nodes[parent].children[side] = z;
+        }
+        self.len += 1;
+        self.insert_fixup(z);
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        match self.find(key) {
+            NIL => None,
+            z => Some(self.remove_node(z)),
+        }
+    }
+
+    /// The entry with the greatest key less than `key`, which need not be in
+    /// the map.
+    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        self.entry(self.last_where(|k| k < key))
+    }
+
+    /// The entry with the least key greater than `key`, which need not be in
+    /// the map.
+    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        self.entry(self.first_where(|k| k > key))
+    }
+
+    /// Iterates in order over the entries whose keys lie in `range`.
+    pub fn range(&self, range: impl RangeBounds<K>) -> Range<'_, K, V> {
+        let front = match range.start_bound() {
+            Bound::Included(start) => self.first_where(|k| k >= start),
+            Bound::Excluded(start) => self.first_where(|k| k > start),
+            Bound::Unbounded => self.extreme(self.root, LEFT),
+        };
+        let back = match range.end_bound() {
+            Bound::Included(end) => self.last_where(|k| k <= end),
+            Bound::Excluded(end) => self.last_where(|k| k < end),
+            Bound::Unbounded => self.extreme(self.root, RIGHT),
+        };
+        if front == NIL || back == NIL || self.key(front) > self.key(back) {
+            return Range {
+                map: self,
+                front: NIL,
+                back: NIL,
+            };
+        }
+        Range {
+            map: self,
+            front,
+            back,
+        }
+    }
+
+    /// Checks the red-black properties and the links of every node, and
+    /// returns the black height of the tree. Meant for tests.
+    pub fn validate(&self) -> Result<usize, &'static str> {
+        if self.color(NIL) != Color::Black || self.nodes[NIL].entry.is_some() {
+            return Err("the sentinel changed");
+        }
+        if self.color(self.root) != Color::Black {
+            return Err("the root is red");
+        }
+        if self.root != NIL && self.parent(self.root) != NIL {
+            return Err("the root has a parent");
+        }
+        let mut count = 0;
+        let black_height = self.validate_node(self.root, &mut count)?;
+        if count != self.len {
+            return Err("the length is off");
+        }
+        if self.nodes.len() != 1 + self.len + self.free.len() {
+            return Err("slots leaked");
+        }
+        Ok(black_height)
+    }
+
+    fn validate_node(&self, x: usize, count: &mut usize) -> Result<usize, &'static str> {
+        if x == NIL {
+            return Ok(0);
+        }
+        *count += 1;
+        let mut heights = [0; 2];
+        for side in [LEFT, RIGHT] {
+            let child = self.child(x, side);
+            if child == NIL {
+                continue;
+            }
+            if self.parent(child) != x {
+                return Err("a child does not point back at its parent");
+            }
+            if self.color(x) == Color::Red && self.color(child) == Color::Red {
+                return Err("a red node has a red child");
+            }
+            if (side == LEFT) != (self.key(child) < self.key(x)) {
+                return Err("keys are out of order");
+            }
+            heights[side] = self.validate_node(child, count)?;
+        }
+        if heights[LEFT] != heights[RIGHT] {
+            return Err("black heights differ");
+        }
+        Ok(heights[LEFT] + usize::from(self.color(x) == Color::Black))
+    }
+}
+
+impl<K, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for Map<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for Map<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+        map
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Map<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Range<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An in-order iterator over the entries from `front` to `back`, both
+/// included.
+pub struct Range<'a, K, V> {
+    map: &'a Map<K, V>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, K, V> Range<'a, K, V> {
+    /// Yields `x`, moving `front` or `back` on from it.
+    fn take(&mut self, x: usize, side: usize) -> Option<(&'a K, &'a V)> {
+        if self.front == self.back {
+            self.front = NIL;
+            self.back = NIL;
+        } else if side == RIGHT {
+            self.front = self.map.step(x, RIGHT);
+        } else {
+            self.back = self.map.step(x, LEFT);
+        }
+        self.map.entry(x)
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.front {
+            NIL => None,
+            x => self.take(x, RIGHT),
+        }
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Range<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.back {
+            NIL => None,
+            x => self.take(x, LEFT),
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use std::{cmp::Ordering, fmt::Debug, rc::Rc};
+
+use super::{BinaryTree, TreeNodePtr};
+
+/// A key and the slab slot of its value, ordered by the key alone.
+struct Pair<K> {
+    key: Rc<K>,
+    slot: usize,
+}
+
+impl<K: Ord> PartialEq for Pair<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord> PartialOrd for Pair<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.key.cmp(&other.key))
+    }
+}
+
+/// An ordered map on a `BinaryTree` of keys, with at most one entry per key.
+///
+/// The tree orders the keys, and each tree node points at a slot of a slab
+/// that holds the entry's value, next to a second `Rc` of the key. Reads and
+/// writes go through the slab, so the map hands out plain `&K`, `&V` and
+/// `&mut V` without borrowing a tree node from its `RefCell`.
+pub struct TreeMap<K, V> {
+    tree: BinaryTree<Pair<K>>,
+    slab: Vec<Option<(Rc<K>, V)>>,
+    /// Empty slots of `slab`, to reuse before growing it.
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<K, V> TreeMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            tree: BinaryTree::new(),
+            slab: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.tree.clean();
+        self.slab.clear();
+        self.free.clear();
+        self.len = 0;
+    }
+
+    fn entry_at(&self, slot: usize) -> (&K, &V) {
+        let (key, value) = self.slab[slot].as_ref().expect("an occupied slot");
+        (key, value)
+    }
+
+    fn value_mut(&mut self, slot: usize) -> &mut V {
+        &mut self.slab[slot].as_mut().expect("an occupied slot").1
+    }
+}
+
+fn slot<K>(node: &TreeNodePtr<Pair<K>>) -> usize {
+    node.borrow().value.slot
+}
+
+impl<K: Ord, V> TreeMap<K, V> {
+    fn find(&self, key: &K) -> Option<TreeNodePtr<Pair<K>>> {
+        let mut cursor = self.tree.root.clone()?;
+        loop {
+            let ordering = key.cmp(cursor.borrow().value.key.as_ref());
+            let next = match ordering {
+                Ordering::Less => cursor.borrow().left.root.clone(),
+                Ordering::Greater => cursor.borrow().right.root.clone(),
+                Ordering::Equal => return Some(cursor),
+            };
+            cursor = next?;
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let slot = slot(&self.find(key)?);
+        Some(self.entry_at(slot).1)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let slot = slot(&self.find(key)?);
+        Some(self.value_mut(slot))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaced. The key
+    /// already in the map is kept.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        let node = self.find(key)?;
+        Some(self.remove_node(node))
+    }
+
+    fn remove_node(&mut self, node: TreeNodePtr<Pair<K>>) -> (K, V) {
+        let slot = slot(&node);
+        self.tree.remove(node);
+        self.len -= 1;
+        self.free.push(slot);
+        let (key, value) = self.slab[slot].take().expect("an occupied slot");
+        // The tree dropped the node, and with it the other `Rc` of the key.
+        let key = Rc::try_unwrap(key)
+            .ok()
+            .expect("a removed key has no other owner");
+        (key, value)
+    }
+
+    /// The entry for `key`, to read, change, insert or remove its value.
+    /// An occupied entry reuses the node found here; inserting into a vacant
+    /// one walks the tree a second time.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.find(&key) {
+            Some(node) => Entry::Occupied(OccupiedEntry {
+                slot: slot(&node),
+                map: self,
+                node,
+            }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        Some(self.entry_at(slot(&self.tree.min()?)))
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        Some(self.entry_at(slot(&self.tree.max()?)))
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.tree.iter(),
+            map: self,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> Default for TreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Debug, V: Debug> Debug for TreeMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for TreeMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+        map
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a TreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    inner: super::Iter<Pair<K>>,
+    map: &'a TreeMap<K, V>,
+}
+
+impl<'a, K: Ord, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let map = self.map;
+        self.inner.next().map(|node| map.entry_at(slot(&node)))
+    }
+}
+
+/// A view into one key of a `TreeMap`, from `TreeMap::entry`.
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut TreeMap<K, V>,
+    node: TreeNodePtr<Pair<K>>,
+    slot: usize,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut TreeMap<K, V>,
+    key: K,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if there is one.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        self.map.entry_at(self.slot).0
+    }
+
+    pub fn get(&self) -> &V {
+        self.map.entry_at(self.slot).1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.map.value_mut(self.slot)
+    }
+
+    /// Turns the entry into a reference to its value that lives as long as
+    /// the map's borrow.
+    pub fn into_mut(self) -> &'a mut V {
+        self.map.value_mut(self.slot)
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_node(self.node)
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        let (map, key) = (self.map, Rc::new(self.key));
+        let entry = Some((key.clone(), value));
+        let slot = match map.free.pop() {
+            Some(slot) => {
+                map.slab[slot] = entry;
+                slot
+            }
+            None => {
+                map.slab.push(entry);
+                map.slab.len() - 1
+            }
+        };
+        map.tree.insert(Pair { key, slot });
+        map.len += 1;
+        map.value_mut(slot)
+    }
+}
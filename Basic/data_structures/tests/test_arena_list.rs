@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use data_structures::linked_list::{ArenaDoublyLinkedList, ArenaSingleLinkedList};
+
+use rand::Rng;
+
+fn verify_doubly(list: &ArenaDoublyLinkedList<i32>, expect: &[i32]) {
+    assert!(list.len() == expect.len() && list.is_empty() == expect.is_empty());
+    assert!(list.iter().eq(expect.iter()));
+    assert!(list.iter().rev().eq(expect.iter().rev()));
+    let ids: Vec<_> = list.ids().collect();
+    for (i, id) in ids.iter().enumerate() {
+        assert!(list.get(*id) == Some(&expect[i]));
+        assert!(list.prev(*id) == i.checked_sub(1).map(|j| ids[j]));
+        assert!(list.next(*id) == ids.get(i + 1).copied());
+    }
+    assert!(list.front() == ids.first().copied() && list.back() == ids.last().copied());
+}
+
+#[test]
+fn test_single_list() {
+    let mut list: ArenaSingleLinkedList<_> = (0..5).collect();
+    let ids: Vec<_> = list.ids().collect();
+
+    assert!(list.insert_after(ids[2], 20).is_some());
+    assert!(list.remove_after(ids[0]) == Some(1));
+    assert!(!list.contains(ids[1]) && list.get(ids[1]).is_none());
+    assert!(list.insert_after(ids[1], -1).is_none());
+    assert!(list.remove_after(ids[4]).is_none());
+
+    let reused = list.push_front(-5);
+    assert!(!list.contains(ids[1]) && list.get(reused) == Some(&-5));
+    assert!(list.remove_after(ids[3]) == Some(4));
+    assert!(list.back() == Some(ids[3]));
+    list.push_back(5);
+    *list.get_mut(ids[0]).unwrap() = 100;
+
+    assert!(list.iter().eq([-5, 100, 2, 20, 3, 5].iter()));
+    assert!(list.len() == 6 && list.next(ids[2]).and_then(|id| list.get(id)) == Some(&20));
+
+    while list.pop_front().is_some() {}
+    assert!(list.is_empty() && list.front().is_none() && list.back().is_none());
+    assert!(ids.iter().all(|id| !list.contains(*id)));
+}
+
+#[test]
+fn test_doubly_list() {
+    let mut list: ArenaDoublyLinkedList<_> = (0..5).collect();
+    let ids: Vec<_> = list.ids().collect();
+    verify_doubly(&list, &[0, 1, 2, 3, 4]);
+
+    assert!(list.remove(ids[2]) == Some(2));
+    assert!(list.remove(ids[2]).is_none());
+    let a = list.insert_before(ids[0], -1).unwrap();
+    let b = list.insert_after(ids[4], 5).unwrap();
+    assert!(list.insert_after(ids[2], 0).is_none());
+    verify_doubly(&list, &[-1, 0, 1, 3, 4, 5]);
+
+    assert!(list.move_to_front(ids[3]));
+    assert!(list.move_to_front(ids[3]));
+    assert!(list.move_to_front(b));
+    assert!(!list.move_to_front(ids[2]));
+    verify_doubly(&list, &[5, 3, -1, 0, 1, 4]);
+
+    assert!(list.pop_back() == Some(4) && list.pop_front() == Some(5));
+    assert!(!list.contains(b) && list.contains(a));
+    list.clear();
+    verify_doubly(&list, &[]);
+    assert!(!list.contains(a));
+}
+
+#[test]
+fn test_random_ops_keep_handles_stable() {
+    let mut rng = rand::thread_rng();
+    let mut list = ArenaDoublyLinkedList::new();
+    let mut model = VecDeque::new();
+    let mut live = Vec::new();
+    let mut dead = Vec::new();
+
+    for i in 0..5000 {
+        match rng.gen_range(0..4) {
+            0 => {
+                live.push((list.push_back(i), i));
+                model.push_back(i);
+            }
+            1 if !live.is_empty() => {
+                let (id, x) = live[rng.gen_range(0..live.len())];
+                live.push((list.insert_before(id, i).unwrap(), i));
+                let at = model.iter().position(|y| *y == x).unwrap();
+                model.insert(at, i);
+            }
+            2 if !live.is_empty() => {
+                let (id, x) = live.swap_remove(rng.gen_range(0..live.len()));
+                assert!(list.remove(id) == Some(x));
+                model.retain(|y| *y != x);
+                dead.push(id);
+            }
+            _ => {
+                live.push((list.push_front(i), i));
+                model.push_front(i);
+            }
+        }
+        if i % 500 == 0 {
+            verify_doubly(&list, model.make_contiguous());
+        }
+    }
+    verify_doubly(&list, model.make_contiguous());
+    assert!(live.iter().all(|(id, x)| list.get(*id) == Some(x)));
+    assert!(dead.iter().all(|id| !list.contains(*id)));
+}
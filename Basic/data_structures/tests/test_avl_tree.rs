@@ -0,0 +1,186 @@
+use std::{cell::RefCell, rc::Rc};
+
+use data_structures::tree::{AvlNode, AvlTree};
+
+use rand::Rng;
+
+/// Checks the AVL invariants of the subtree under `node` and returns its
+/// height: stored heights are right, sibling heights differ by at most one,
+/// children point back at their parent, and values are ordered. Rotations
+/// can move a value equal to a node into its left subtree.
+fn verify_node<T: PartialOrd>(node: &Option<Rc<RefCell<AvlNode<T>>>>) -> usize {
+    let Some(node) = node else {
+        return 0;
+    };
+    let left = verify_node(&node.borrow().left.root);
+    let right = verify_node(&node.borrow().right.root);
+    assert!(left.abs_diff(right) <= 1, "unbalanced: {left} vs {right}");
+    assert!(node.borrow().height == 1 + left.max(right));
+
+    let n = node.borrow();
+    if let Some(child) = &n.left.root {
+        assert!(Rc::ptr_eq(&child.borrow().parent.upgrade().unwrap(), node));
+        assert!(child.borrow().value <= n.value);
+    }
+    if let Some(child) = &n.right.root {
+        assert!(Rc::ptr_eq(&child.borrow().parent.upgrade().unwrap(), node));
+        assert!(child.borrow().value >= n.value);
+    }
+    n.height
+}
+
+fn verify_tree<T: PartialOrd>(tree: &AvlTree<T>) {
+    if let Some(root) = &tree.root {
+        assert!(root.borrow().parent.upgrade().is_none());
+    }
+    assert!(verify_node(&tree.root) == tree.height());
+}
+
+/// The largest height an AVL tree of `n` nodes can have.
+fn max_height(n: usize) -> usize {
+    (1.4405 * ((n + 2) as f64).log2() - 0.3277).floor() as usize
+}
+
+#[test]
+fn test_display() {
+    let mut rng = rand::thread_rng();
+
+    let mut tree = AvlTree::new();
+    for _ in 0..15 {
+        tree.insert(rng.gen_range(0..100));
+    }
+
+    println!("Tree = \n{tree}");
+}
+
+#[test]
+fn test_sorted_insert() {
+    let size = 4095;
+    let mut tree = AvlTree::new();
+    for x in 0..size {
+        tree.insert(x);
+        verify_tree(&tree);
+    }
+    // Sorted input fills an AVL tree up perfectly.
+    assert!(tree.height() == 12);
+
+    let mut tree = AvlTree::new();
+    for x in (0..size).rev() {
+        tree.insert(x);
+    }
+    verify_tree(&tree);
+    assert!(tree.height() <= max_height(size));
+    assert!(tree.iter().map(|node| node.borrow().value).eq(0..size));
+}
+
+#[test]
+fn test_find_min_max() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..10 {
+        let mut input = (0..200).map(|_| rng.gen_range(0..1000)).collect::<Vec<_>>();
+
+        let mut tree = AvlTree::new();
+        for x in &input {
+            tree.insert(*x);
+        }
+        verify_tree(&tree);
+
+        for x in &input {
+            assert!(tree.find(x).unwrap().borrow().value == *x);
+        }
+        assert!(tree.find(&1000).is_none() && tree.find(&-1).is_none());
+
+        input.sort();
+        assert!(input.first().unwrap() == &tree.min().unwrap().borrow().value);
+        assert!(input.last().unwrap() == &tree.max().unwrap().borrow().value);
+        assert!(tree
+            .iter()
+            .map(|node| node.borrow().value)
+            .eq(input.iter().copied()));
+    }
+}
+
+#[test]
+fn test_remove() {
+    let mut rng = rand::thread_rng();
+
+    // Few distinct values, so many nodes hold equal values.
+    let mut model = (0..3000).map(|_| rng.gen_range(0..300)).collect::<Vec<_>>();
+    let mut tree = AvlTree::new();
+    for x in &model {
+        tree.insert(*x);
+    }
+    model.sort();
+    verify_tree(&tree);
+
+    while !model.is_empty() {
+        let x = model[rng.gen_range(0..model.len())];
+        let node = tree.find(&x).unwrap();
+        tree.remove(node.clone());
+        assert!(node.borrow().parent.upgrade().is_none());
+        let i = model.binary_search(&x).unwrap();
+        model.remove(i);
+
+        verify_tree(&tree);
+        assert!(tree.height() <= max_height(model.len()));
+        if model.len() % 100 == 0 {
+            assert!(tree
+                .iter()
+                .map(|node| node.borrow().value)
+                .eq(model.iter().copied()));
+        }
+    }
+    assert!(tree.is_empty() && tree.height() == 0);
+}
+
+#[test]
+fn test_mixed_insert_remove() {
+    let mut rng = rand::thread_rng();
+    let mut model = Vec::new();
+    let mut tree = AvlTree::new();
+
+    for _ in 0..5000 {
+        if model.is_empty() || rng.gen_bool(0.6) {
+            let x = rng.gen_range(0..1000);
+            tree.insert(x);
+            let i = model.partition_point(|y| *y <= x);
+            model.insert(i, x);
+        } else {
+            let x = model.remove(rng.gen_range(0..model.len()));
+            tree.remove(tree.find(&x).unwrap());
+        }
+        verify_tree(&tree);
+    }
+    assert!(tree
+        .iter()
+        .map(|node| node.borrow().value)
+        .eq(model.iter().copied()));
+}
+
+#[test]
+fn test_predecessor_successor() {
+    let mut rng = rand::thread_rng();
+    let input = (0..2000).map(|_| rng.gen_range(0..500)).collect::<Vec<_>>();
+
+    let mut tree = AvlTree::new();
+    for x in &input {
+        tree.insert(*x);
+    }
+
+    // Walking by links visits every node, equal values included.
+    let nodes = tree.iter().collect::<Vec<_>>();
+    assert!(nodes.len() == input.len());
+    for (i, node) in nodes.iter().enumerate() {
+        let pre = AvlNode::predecessor(node.clone());
+        let suc = AvlNode::successor(node.clone());
+        match i.checked_sub(1) {
+            Some(j) => assert!(Rc::ptr_eq(&pre.unwrap(), &nodes[j])),
+            None => assert!(pre.is_none()),
+        }
+        match nodes.get(i + 1) {
+            Some(next) => assert!(Rc::ptr_eq(&suc.unwrap(), next)),
+            None => assert!(suc.is_none()),
+        }
+    }
+}
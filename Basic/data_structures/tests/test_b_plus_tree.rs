@@ -0,0 +1,113 @@
+use std::{collections::BTreeMap, ops::Bound};
+
+use data_structures::tree::BPlusTree;
+
+use rand::Rng;
+
+#[test]
+fn test_insert_get() {
+    let mut tree = BPlusTree::with_min_degree(2);
+    assert!(tree.is_empty() && tree.first().is_none() && tree.validate() == Ok(1));
+
+    for x in [10, 20, 5, 6, 12, 30, 7, 17, 3, 1] {
+        assert!(tree.insert(x, x * 10).is_none());
+        tree.validate().unwrap();
+    }
+    assert!(tree.insert(12, 0) == Some(120) && tree.len() == 10);
+    assert!(tree.get(&12) == Some(&0) && tree.get(&11).is_none());
+    *tree.get_mut(&7).unwrap() += 1;
+    assert!(tree.get(&7) == Some(&71) && tree.contains_key(&30));
+    assert!(tree.first() == Some((&1, &10)) && tree.last() == Some((&30, &300)));
+    assert!(tree.height() > 1);
+    println!("tree = {tree:?}");
+
+    tree.clear();
+    assert!(tree.is_empty() && tree.height() == 1);
+}
+
+#[test]
+fn test_random_operations() {
+    let mut rng = rand::thread_rng();
+
+    for t in [2, 3, 6] {
+        let mut tree = BPlusTree::with_min_degree(t);
+        let mut model = BTreeMap::new();
+        for _ in 0..5000 {
+            let key = rng.gen_range(0..500);
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let value = rng.gen::<u32>();
+                    assert!(tree.insert(key, value) == model.insert(key, value));
+                }
+                2 => assert!(tree.remove(&key) == model.remove(&key)),
+                _ => assert!(tree.get(&key) == model.get(&key)),
+            }
+            tree.validate().unwrap();
+        }
+        assert!(tree.len() == model.len());
+        assert!(tree.iter().eq(model.iter()));
+
+        let mut keys = model.keys().copied().collect::<Vec<_>>();
+        while !keys.is_empty() {
+            let key = keys.swap_remove(rng.gen_range(0..keys.len()));
+            assert!(tree.remove_entry(&key) == model.remove_entry(&key));
+            tree.validate().unwrap();
+        }
+        assert!(tree.is_empty() && tree.height() == 1);
+    }
+}
+
+#[test]
+fn test_range() {
+    let mut rng = rand::thread_rng();
+    let model = (0..1000)
+        .map(|_| (rng.gen_range(0..3000), rng.gen::<u16>()))
+        .collect::<BTreeMap<_, _>>();
+    let tree = BPlusTree::from_sorted_with_min_degree(3, model.iter().map(|(k, v)| (*k, *v)));
+
+    for _ in 0..500 {
+        let a = rng.gen_range(-10..3009);
+        let b = rng.gen_range(a + 1..3010);
+        assert!(tree.range(a..b).eq(model.range(a..b)));
+        assert!(tree.range(a..=b).eq(model.range(a..=b)));
+        assert!(tree.range(..b).eq(model.range(..b)));
+        assert!(tree.range(a..).eq(model.range(a..)));
+        let bounds = (Bound::Excluded(a), Bound::Excluded(b));
+        assert!(tree.range(bounds).eq(model.range(bounds)));
+    }
+    assert!(tree.range(..).count() == model.len());
+    assert!(tree.range(5..5).next().is_none());
+}
+
+#[test]
+fn test_from_sorted() {
+    for t in [2, 3, 6] {
+        for n in (0..300).chain([1000, 4095, 10000]) {
+            let tree = BPlusTree::from_sorted_with_min_degree(t, (0..n).map(|x| (x, x * 2)));
+            tree.validate().unwrap();
+            assert!(tree.len() == n);
+            assert!(tree
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .eq((0..n).map(|x| (x, x * 2))));
+        }
+    }
+
+    // A bulk loaded tree takes further inserts and removals.
+    let mut tree = BPlusTree::from_sorted((0..1000).map(|x| (x * 2, ())));
+    for x in 0..1000 {
+        tree.insert(x * 2 + 1, ());
+        tree.remove(&(x * 2));
+    }
+    tree.validate().unwrap();
+    assert!(tree
+        .iter()
+        .map(|(k, _)| *k)
+        .eq((0..1000).map(|x| x * 2 + 1)));
+}
+
+#[test]
+#[should_panic(expected = "the keys are not strictly increasing")]
+fn test_from_unsorted() {
+    BPlusTree::from_sorted([(3, ()), (1, ())]);
+}
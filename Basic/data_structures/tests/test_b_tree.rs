@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use data_structures::tree::BTree;
+
+use rand::Rng;
+
+#[test]
+fn test_insert_get() {
+    let mut tree = BTree::with_min_degree(2);
+    assert!(tree.is_empty() && tree.first().is_none() && tree.validate() == Ok(1));
+
+    for x in [10, 20, 5, 6, 12, 30, 7, 17, 3, 1] {
+        assert!(tree.insert(x, x * 10).is_none());
+        tree.validate().unwrap();
+    }
+    assert!(tree.insert(12, 0) == Some(120) && tree.len() == 10);
+    assert!(tree.get(&12) == Some(&0) && tree.get(&11).is_none());
+    *tree.get_mut(&7).unwrap() += 1;
+    assert!(tree.get(&7) == Some(&71) && tree.contains_key(&30));
+    assert!(tree.first() == Some((&1, &10)) && tree.last() == Some((&30, &300)));
+    assert!(tree.height() > 1);
+    println!("tree = {tree:?}");
+
+    tree.clear();
+    assert!(tree.is_empty() && tree.height() == 1);
+}
+
+#[test]
+fn test_random_operations() {
+    let mut rng = rand::thread_rng();
+
+    for t in [2, 3, 6] {
+        let mut tree = BTree::with_min_degree(t);
+        let mut model = BTreeMap::new();
+        for _ in 0..5000 {
+            let key = rng.gen_range(0..500);
+            match rng.gen_range(0..5) {
+                0 | 1 => {
+                    let value = rng.gen::<u32>();
+                    assert!(tree.insert(key, value) == model.insert(key, value));
+                }
+                2 => assert!(tree.remove(&key) == model.remove(&key)),
+                3 => assert!(tree.pop_first() == model.pop_first()),
+                _ => assert!(tree.get(&key) == model.get(&key)),
+            }
+            tree.validate().unwrap();
+        }
+        assert!(tree.len() == model.len());
+        assert!(tree.iter().eq(model.iter()));
+
+        while let Some(entry) = tree.pop_last() {
+            assert!(Some(entry) == model.pop_last());
+            tree.validate().unwrap();
+        }
+        assert!(model.is_empty() && tree.remove(&0).is_none());
+    }
+}
+
+#[test]
+fn test_from_sorted() {
+    for t in [2, 3, 6] {
+        for n in (0..300).chain([1000, 4095, 10000]) {
+            let tree = BTree::from_sorted_with_min_degree(t, (0..n).map(|x| (x, x * 2)));
+            let height = tree.validate().unwrap();
+            assert!(tree.len() == n);
+            assert!(tree
+                .iter()
+                .map(|(k, v)| (*k, *v))
+                .eq((0..n).map(|x| (x, x * 2))));
+
+            // Bulk loading builds as shallow a tree as inserting would.
+            let mut inserted = BTree::with_min_degree(t);
+            for x in 0..n {
+                inserted.insert(x, ());
+            }
+            assert!(height <= inserted.height());
+        }
+    }
+
+    // A bulk loaded tree takes further inserts and removals.
+    let mut tree = BTree::from_sorted((0..1000).map(|x| (x * 2, ())));
+    for x in 0..1000 {
+        tree.insert(x * 2 + 1, ());
+        tree.remove(&(x * 2));
+    }
+    tree.validate().unwrap();
+    assert!(tree
+        .iter()
+        .map(|(k, _)| *k)
+        .eq((0..1000).map(|x| x * 2 + 1)));
+}
+
+#[test]
+#[should_panic(expected = "the keys are not strictly increasing")]
+fn test_from_unsorted() {
+    BTree::from_sorted([(1, ()), (3, ()), (3, ())]);
+}
@@ -0,0 +1,260 @@
+use std::{cell::Cell, collections::VecDeque, rc::Rc};
+
+use data_structures::linked_list::{doubly_linked_list::Handle, DoublyLinkedList};
+
+use rand::Rng;
+
+/// Walks the list forward and backward and checks both agree with `expect`
+/// and with `len`, which catches any broken `prev`/`next` link.
+fn verify_links<T: PartialEq + std::fmt::Debug>(list: &DoublyLinkedList<T>, expect: &[T]) {
+    assert!(list.len() == expect.len());
+    assert!(list.is_empty() == expect.is_empty());
+    assert!(list.iter().eq(expect.iter()), "{list:?} != {expect:?}");
+    assert!(list.iter().rev().eq(expect.iter().rev()));
+    assert!(list.front() == expect.first() && list.back() == expect.last());
+
+    let mut cursor = list.cursor_back();
+    for (i, x) in expect.iter().enumerate().rev() {
+        assert!(cursor.index() == Some(i) && cursor.current() == Some(x));
+        cursor.move_prev();
+    }
+    assert!(cursor.index().is_none() && cursor.current().is_none());
+}
+
+struct DropCounter(Rc<Cell<usize>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1)
+    }
+}
+
+#[test]
+fn test_push_pop() {
+    let mut list = DoublyLinkedList::new();
+    verify_links(&list, &[]);
+
+    for i in 0..50 {
+        list.push_back(i);
+        list.push_front(-i - 1);
+    }
+    let mut expect: Vec<_> = (-50..50).collect();
+    verify_links(&list, &expect);
+
+    for _ in 0..10 {
+        assert!(list.pop_front() == Some(expect.remove(0)));
+        assert!(list.pop_back() == expect.pop());
+    }
+    verify_links(&list, &expect);
+
+    list.iter_mut().for_each(|x| *x *= 2);
+    *list.front_mut().unwrap() = 0;
+    *list.back_mut().unwrap() = 0;
+    expect.iter_mut().for_each(|x| *x *= 2);
+    *expect.first_mut().unwrap() = 0;
+    *expect.last_mut().unwrap() = 0;
+    verify_links(&list, &expect);
+
+    assert!(list.clone() == list);
+    assert!(list.into_iter().rev().eq(expect.into_iter().rev()));
+}
+
+#[test]
+fn test_random_ops_against_vec_deque() {
+    let mut rng = rand::thread_rng();
+    let mut list = DoublyLinkedList::new();
+    let mut model = VecDeque::new();
+
+    for i in 0..5000 {
+        match rng.gen_range(0..6) {
+            0 => {
+                list.push_front(i);
+                model.push_front(i);
+            }
+            1 => {
+                list.push_back(i);
+                model.push_back(i);
+            }
+            2 => assert!(list.pop_front() == model.pop_front()),
+            3 => assert!(list.pop_back() == model.pop_back()),
+            4 => {
+                let at = rng.gen_range(0..=model.len());
+                let mut tail = list.split_off(at);
+                let mut model_tail = model.split_off(at);
+                verify_links(&tail, model_tail.make_contiguous());
+                list.append(&mut tail);
+                model.append(&mut model_tail);
+                assert!(tail.is_empty());
+            }
+            _ => {
+                let at = rng.gen_range(0..=model.len());
+                let mut cursor = list.cursor_front_mut();
+                for _ in 0..at {
+                    cursor.move_next();
+                }
+                if rng.gen_bool(0.5) {
+                    cursor.insert_before(i);
+                    model.insert(at, i);
+                } else if let Some(x) = cursor.remove_current() {
+                    assert!(model.remove(at) == Some(x));
+                }
+            }
+        }
+        if i % 100 == 0 {
+            verify_links(&list, model.make_contiguous());
+        }
+    }
+    verify_links(&list, model.make_contiguous());
+}
+
+#[test]
+fn test_append_split_off() {
+    let mut list: DoublyLinkedList<_> = (0..10).collect();
+    let mut other: DoublyLinkedList<_> = (10..20).collect();
+    list.append(&mut other);
+    verify_links(&other, &[]);
+    verify_links(&list, &(0..20).collect::<Vec<_>>());
+
+    for at in [0, 1, 5, 15, 19, 20] {
+        let mut list: DoublyLinkedList<_> = (0..20).collect();
+        let tail = list.split_off(at);
+        verify_links(&list, &(0..at).collect::<Vec<_>>());
+        verify_links(&tail, &(at..20).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_split_off_out_of_bounds() {
+    let mut list: DoublyLinkedList<_> = (0..3).collect();
+    list.split_off(4);
+}
+
+#[test]
+fn test_cursor() {
+    let mut list: DoublyLinkedList<_> = (0..5).collect();
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_prev();
+    assert!(cursor.index().is_none() && cursor.current().is_none());
+    assert!(*cursor.peek_next().unwrap() == 0 && *cursor.peek_prev().unwrap() == 4);
+    cursor.insert_after(-1);
+    cursor.insert_before(5);
+    cursor.move_next();
+    assert!(cursor.index() == Some(0) && *cursor.current().unwrap() == -1);
+    cursor.move_prev();
+    cursor.move_prev();
+    assert!(cursor.index() == Some(6) && *cursor.current().unwrap() == 5);
+    verify_links(&list, &[-1, 0, 1, 2, 3, 4, 5]);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    cursor.move_next();
+    assert!(cursor.remove_current() == Some(1));
+    assert!(cursor.index() == Some(2) && *cursor.current().unwrap() == 2);
+    cursor.splice_before((10..12).collect());
+    cursor.splice_after((20..22).collect());
+    assert!(cursor.index() == Some(4) && *cursor.current().unwrap() == 2);
+    verify_links(&list, &[-1, 0, 10, 11, 2, 20, 21, 3, 4, 5]);
+
+    let mut cursor = list.cursor_front_mut();
+    for _ in 0..4 {
+        cursor.move_next();
+    }
+    let after = cursor.split_after();
+    let before = cursor.split_before();
+    assert!(cursor.index() == Some(0));
+    verify_links(&before, &[-1, 0, 10, 11]);
+    verify_links(&after, &[20, 21, 3, 4, 5]);
+    verify_links(&list, &[2]);
+
+    let mut cursor = list.cursor_back_mut();
+    assert!(cursor.remove_current() == Some(2));
+    assert!(cursor.index().is_none() && cursor.remove_current().is_none());
+    cursor.splice_after(after);
+    cursor.splice_before(before);
+    verify_links(&list, &[20, 21, 3, 4, 5, -1, 0, 10, 11]);
+}
+
+#[test]
+fn test_handles() {
+    let mut list: DoublyLinkedList<_> = (0..10).collect();
+    let handles: Vec<Handle<_>> = {
+        let mut cursor = list.cursor_front();
+        let mut handles = Vec::new();
+        while let Some(handle) = cursor.handle() {
+            handles.push(handle);
+            cursor.move_next();
+        }
+        handles
+    };
+
+    assert!(list.remove(&handles[3]) == Some(3));
+    assert!(list.remove(&handles[3]).is_none() && !list.contains(&handles[3]));
+    *list.get_mut(&handles[4]).unwrap() = 40;
+    assert!(list.get(&handles[4]) == Some(&40));
+    assert!(list.pop_front() == Some(0) && list.get(&handles[0]).is_none());
+    assert!(list
+        .back_handle()
+        .is_some_and(|handle| list.contains(&handle)));
+    verify_links(&list, &[1, 2, 40, 5, 6, 7, 8, 9]);
+
+    let mut tail = list.split_off(4);
+    assert!(list.contains(&handles[2]) && !tail.contains(&handles[2]));
+    assert!(tail.contains(&handles[7]) && !list.contains(&handles[7]));
+    assert!(list.remove(&handles[7]).is_none());
+    assert!(tail.remove(&handles[7]) == Some(7));
+
+    let mut other: DoublyLinkedList<_> = (0..3).collect();
+    let other_handle = other.front_handle().unwrap();
+    other.append(&mut tail);
+    assert!(!tail.contains(&handles[8]) && other.contains(&handles[8]));
+    tail.push_back(100);
+    let tail_handle = tail.front_handle().unwrap();
+    assert!(other.remove(&tail_handle).is_none());
+    list.append(&mut other);
+    assert!(list.remove(&handles[8]) == Some(8));
+    assert!(list.remove(&other_handle) == Some(0));
+    verify_links(&list, &[1, 2, 40, 5, 1, 2, 6, 9]);
+
+    drop(list);
+    assert!(handles.iter().all(|handle| !tail.contains(handle)));
+    assert!(tail.remove(&tail_handle) == Some(100));
+}
+
+#[test]
+fn test_drop_each_value_once() {
+    let count = Rc::new(Cell::new(0));
+    let mut list = DoublyLinkedList::new();
+    for _ in 0..100 {
+        list.push_back(DropCounter(count.clone()));
+    }
+
+    let handle = list.front_handle().unwrap();
+    drop(list.remove(&handle));
+    drop(list.pop_back());
+    assert!(count.get() == 2);
+
+    let tail = list.split_off(50);
+    let mut iter = tail.into_iter();
+    drop(iter.next());
+    drop(iter.next_back());
+    assert!(count.get() == 4);
+    drop(iter);
+    assert!(count.get() == 50);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    drop(cursor.remove_current());
+    assert!(count.get() == 51);
+
+    drop(list);
+    assert!(count.get() == 100);
+}
+
+#[test]
+fn test_display() {
+    let list: DoublyLinkedList<_> = (0..3).collect();
+    assert!(format!("{list}") == "DoublyLinkedList [0, 1, 2]");
+    assert!(format!("{list:?}") == "[0, 1, 2]");
+}
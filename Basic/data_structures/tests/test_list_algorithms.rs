@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use data_structures::linked_list::{
+    algorithms::{brent_cycle, floyd_cycle, intersection, kth_from_end, middle, ByAddress, Cycle},
+    SingleLinkedList,
+};
+
+use rand::Rng;
+
+/// Finds the cycle by remembering the step each state was first seen at.
+fn brute_force_cycle(x0: usize, f: &[usize]) -> Cycle<usize> {
+    let mut seen = HashMap::new();
+    let mut x = x0;
+    for step in 0.. {
+        if let Some(start) = seen.insert(x, step) {
+            return Cycle {
+                entry: x,
+                start,
+                len: step - start,
+            };
+        }
+        x = f[x];
+    }
+    unreachable!()
+}
+
+#[test]
+fn test_cycle_on_functional_graphs() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..200 {
+        let n = rng.gen_range(1..300);
+        let f: Vec<_> = (0..n).map(|_| rng.gen_range(0..n)).collect();
+        let x0 = rng.gen_range(0..n);
+        let expect = brute_force_cycle(x0, &f);
+
+        assert!(floyd_cycle(x0, |x| Some(f[*x])) == Some(expect.clone()));
+        assert!(brent_cycle(x0, |x| Some(f[*x])) == Some(expect));
+    }
+}
+
+#[test]
+fn test_cycle_shapes() {
+    // A rho: 0 -> 1 -> 2 -> 3 -> 4 -> 2.
+    let f = [1, 2, 3, 4, 2];
+    let expect = Cycle {
+        entry: 2,
+        start: 2,
+        len: 3,
+    };
+    assert!(floyd_cycle(0, |x| Some(f[*x])) == Some(expect.clone()));
+    assert!(brent_cycle(0, |x| Some(f[*x])) == Some(expect));
+
+    // A fixed point.
+    let fixed = Cycle {
+        entry: 7,
+        start: 0,
+        len: 1,
+    };
+    assert!(floyd_cycle(7, |x| Some(*x)) == Some(fixed.clone()));
+    assert!(brent_cycle(7, |x| Some(*x)) == Some(fixed));
+
+    // Terminating sequences have no cycle.
+    let next = |x: &usize| (*x < 10).then_some(x + 1);
+    assert!(floyd_cycle(0, next).is_none());
+    assert!(brent_cycle(0, next).is_none());
+
+    let list: SingleLinkedList<_> = [1, 1, 1, 1].into_iter().collect();
+    let head = ByAddress(list.iter().next().unwrap());
+    assert!(floyd_cycle(head, |node| node.0.next().map(ByAddress)).is_none());
+    assert!(brent_cycle(head, |node| node.0.next().map(ByAddress)).is_none());
+}
+
+#[test]
+fn test_middle_kth_from_end() {
+    for len in 1..20usize {
+        let next = |x: &usize| (*x + 1 < len).then_some(x + 1);
+        assert!(middle(0, next) == len / 2);
+        for k in 0..len {
+            assert!(kth_from_end(0, k, next) == Some(len - 1 - k));
+        }
+        assert!(kth_from_end(0, len, next).is_none());
+
+        let list: SingleLinkedList<_> = (0..len).collect();
+        assert!(list.middle().unwrap().value == len / 2);
+        assert!(list.kth_from_end(0).unwrap().value == len - 1);
+        assert!(list.kth_from_end(len - 1).unwrap().value == 0);
+        assert!(list.kth_from_end(len).is_none());
+    }
+    assert!(SingleLinkedList::<i32>::new().middle().is_none());
+}
+
+#[test]
+fn test_intersection() {
+    // Two chains 0 -> 1 -> 2 -> 5 -> 6 and 3 -> 4 -> 5 -> 6 merging at 5,
+    // with usize::MAX ending the chain.
+    let f = [1, 2, 5, 4, 5, 6, usize::MAX];
+    let next = |x: &usize| f.get(*x).copied().filter(|y| *y != usize::MAX);
+    assert!(intersection(0, 3, next) == Some(5));
+    assert!(intersection(3, 0, next) == Some(5));
+    assert!(intersection(6, 0, next) == Some(6));
+    assert!(intersection(2, 2, next) == Some(2));
+
+    let disjoint = |x: &usize| (*x % 10 < 9).then_some(x + 1);
+    assert!(intersection(0, 13, disjoint).is_none());
+
+    // Separately owned lists never share nodes, even with equal values.
+    let a: SingleLinkedList<_> = (0..5).collect();
+    let b: SingleLinkedList<_> = (0..5).collect();
+    let (a0, b0) = (a.iter().next().unwrap(), b.iter().next().unwrap());
+    assert!(intersection(ByAddress(a0), ByAddress(b0), |node| node
+        .0
+        .next()
+        .map(ByAddress))
+    .is_none());
+}
+
+#[test]
+fn test_reverse_palindrome() {
+    let mut list: SingleLinkedList<_> = (0..10).collect();
+    list.reverse();
+    assert!(list.iter().map(|node| node.value).eq((0..10).rev()));
+
+    for input in [
+        vec![],
+        vec![1],
+        vec![1, 1],
+        vec![1, 2, 1],
+        vec![1, 2, 2, 1],
+        vec![3, 1, 4, 1, 3],
+    ] {
+        let mut list: SingleLinkedList<_> = input.iter().copied().collect();
+        assert!(list.is_palindrome());
+        assert!(list.iter().map(|node| node.value).eq(input.iter().copied()));
+    }
+
+    for input in [
+        vec![1, 2],
+        vec![1, 2, 3],
+        vec![1, 2, 2, 3],
+        vec![1, 2, 3, 2, 2],
+    ] {
+        let mut list: SingleLinkedList<_> = input.iter().copied().collect();
+        assert!(!list.is_palindrome());
+        assert!(list.iter().map(|node| node.value).eq(input.iter().copied()));
+    }
+}
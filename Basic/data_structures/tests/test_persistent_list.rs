@@ -0,0 +1,102 @@
+use std::thread;
+
+use data_structures::linked_list::{ArcList, RcList};
+
+#[test]
+fn test_push_tail_share_structure() {
+    let empty = RcList::new();
+    assert!(empty.is_empty() && empty.tail().is_none());
+
+    let a = empty.push_head(1).push_head(2);
+    let b = a.push_head(3);
+    let c = a.push_head(4);
+
+    assert!(a.iter().eq([2, 1].iter()) && a.len() == 2);
+    assert!(b.iter().eq([3, 2, 1].iter()) && b.len() == 3);
+    assert!(c.iter().eq([4, 2, 1].iter()) && c.head() == Some(&4));
+
+    assert!(b.tail().unwrap().ptr_eq(&a));
+    assert!(c.tail().unwrap().ptr_eq(&b.tail().unwrap()));
+    assert!(!b.ptr_eq(&c) && b != c);
+    assert!(b.tail().unwrap() == c.tail().unwrap());
+
+    drop(a);
+    assert!(b.tail().unwrap().iter().eq([2, 1].iter()));
+    assert!(format!("{b}") == "PersistentList [3, 2, 1]");
+    assert!(format!("{c:?}") == "[4, 2, 1]");
+}
+
+#[test]
+fn test_collect_reverse() {
+    let list: RcList<_> = (0..10).collect();
+    assert!(list.iter().copied().eq(0..10));
+    assert!(list.reverse().iter().copied().eq((0..10).rev()));
+    assert!(list.clone().ptr_eq(&list));
+}
+
+#[test]
+fn test_backtracking_snapshots() {
+    // Enumerate all subsets of 0..n that sum to target, keeping the partial
+    // choice as a persistent list so undoing a choice is free.
+    fn search(
+        n: i32,
+        i: i32,
+        sum: i32,
+        target: i32,
+        chosen: &RcList<i32>,
+        out: &mut Vec<Vec<i32>>,
+    ) {
+        if sum == target {
+            out.push(chosen.reverse().iter().copied().collect());
+        }
+        for x in i..n {
+            if sum + x <= target {
+                search(n, x + 1, sum + x, target, &chosen.push_head(x), out);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    search(6, 1, 0, 6, &RcList::new(), &mut out);
+    out.sort();
+    assert!(out == vec![vec![1, 2, 3], vec![1, 5], vec![2, 4]]);
+}
+
+#[test]
+fn test_arc_list_across_threads() {
+    let base: ArcList<_> = (0..1000).collect();
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let base = base.clone();
+            thread::spawn(move || {
+                let mine = base.push_head(-i);
+                assert!(mine.tail().unwrap().ptr_eq(&base));
+                mine.iter().sum::<i32>()
+            })
+        })
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert!(handle.join().unwrap() == (0..1000).sum::<i32>() - i as i32);
+    }
+    assert!(base.len() == 1000);
+}
+
+#[test]
+fn test_drop_long_shared_list() {
+    let mut list = RcList::new();
+    for i in 0..1_000_000 {
+        list = list.push_head(i);
+    }
+    let snapshot = list.tail().unwrap();
+    drop(list);
+    assert!(snapshot.len() == 999_999);
+    drop(snapshot);
+
+    let mut list = ArcList::new();
+    for i in 0..1_000_000 {
+        list = list.push_head(i);
+    }
+    drop(list);
+}
@@ -0,0 +1,105 @@
+use data_structures::tree::{Duplicates, PolicyTree};
+
+use rand::seq::SliceRandom;
+
+/// Ordered by `key` alone, so equal values can be told apart by `tag`.
+#[derive(Debug, Clone)]
+struct Tagged {
+    key: u32,
+    tag: u32,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+#[test]
+fn test_duplicate_policies() {
+    let tagged = |key, tag| Tagged { key, tag };
+    let tag_of = |tree: &PolicyTree<Tagged>, key| {
+        tree.find(&tagged(key, 0)).unwrap().borrow().value.value.tag
+    };
+
+    let mut allow = PolicyTree::new();
+    assert!(allow.duplicates() == Duplicates::Allow);
+    for tag in 0..3 {
+        assert!(allow.insert(tagged(1, tag)).is_none());
+    }
+    let tags = allow
+        .iter()
+        .map(|node| node.borrow().value.value.tag)
+        .collect::<Vec<_>>();
+    assert!(tags == [0, 1, 2]);
+
+    let mut reject = PolicyTree::with_duplicates(Duplicates::Reject);
+    assert!(reject.insert(tagged(1, 0)).is_none());
+    assert!(reject.insert(tagged(1, 1)).is_some_and(|x| x.tag == 1));
+    assert!(tag_of(&reject, 1) == 0 && reject.iter().count() == 1);
+
+    let mut replace = PolicyTree::with_duplicates(Duplicates::Replace);
+    replace.insert(tagged(1, 0));
+    replace.insert(tagged(2, 0));
+    assert!(replace.insert(tagged(1, 1)).is_some_and(|x| x.tag == 0));
+    assert!(tag_of(&replace, 1) == 1 && replace.iter().count() == 2);
+    assert!(replace
+        .remove_value(&tagged(1, 5))
+        .is_ok_and(|x| x.is_some_and(|x| x.tag == 1)));
+    assert!(replace.find(&tagged(1, 0)).is_none());
+}
+
+#[test]
+fn test_multiset() {
+    let mut rng = rand::thread_rng();
+    let mut input = (0..10)
+        .flat_map(|x| std::iter::repeat_n(x, x + 1))
+        .collect::<Vec<_>>();
+    input.shuffle(&mut rng);
+
+    let mut tree = PolicyTree::with_duplicates(Duplicates::Count);
+    for x in &input {
+        assert!(tree.insert(*x).is_none());
+    }
+    println!("tree = {tree}");
+    let counts = tree
+        .iter()
+        .map(|node| (node.borrow().value.value, node.borrow().value.count))
+        .collect::<Vec<_>>();
+    assert!(counts == (0..10).map(|x| (x, x + 1)).collect::<Vec<_>>());
+
+    // `remove_one` takes one count off, and the node goes with the last.
+    input.shuffle(&mut rng);
+    let mut remaining = (0..10).map(|x| x + 1).collect::<Vec<_>>();
+    for x in &input {
+        assert!(tree.remove_one(x) == Ok(Some(*x)));
+        remaining[*x] -= 1;
+        match tree.find(x) {
+            Some(node) => assert!(node.borrow().value.count == remaining[*x]),
+            None => assert!(remaining[*x] == 0),
+        }
+        let values = tree
+            .iter()
+            .map(|node| node.borrow().value.value)
+            .collect::<Vec<_>>();
+        assert!(values.is_sorted());
+    }
+    assert!(tree.is_empty() && tree.remove_one(&0) == Ok(None));
+
+    // `remove_value` takes the node with all its counts.
+    tree.insert(7);
+    tree.insert(7);
+    assert!(tree.remove_value(&7) == Ok(Some(7)) && tree.is_empty());
+
+    // A node held outside the tree is still removed, and handed back.
+    tree.insert(3);
+    let held = tree.find(&3).unwrap();
+    let removed = tree.remove_value(&3).unwrap_err();
+    assert!(std::rc::Rc::ptr_eq(&removed, &held) && tree.is_empty());
+}
@@ -0,0 +1,128 @@
+use std::{collections::BTreeMap, ops::Bound};
+
+use data_structures::tree::red_black_tree::Map;
+
+use rand::Rng;
+
+#[test]
+fn test_insert_get() {
+    let mut map = Map::new();
+    assert!(map.is_empty() && map.first().is_none() && map.validate() == Ok(0));
+
+    for x in [5, 3, 8, 1, 4, 7, 9] {
+        assert!(map.insert(x, x * 10).is_none());
+        assert!(map.validate().is_ok());
+    }
+    assert!(map.insert(4, 0) == Some(40));
+    assert!(map.len() == 7 && map.get(&4) == Some(&0) && map.get(&6).is_none());
+
+    *map.get_mut(&9).unwrap() += 1;
+    assert!(map.get(&9) == Some(&91) && map.contains_key(&1));
+    assert!(map.first() == Some((&1, &10)) && map.last() == Some((&9, &91)));
+    println!("map = {map:?}");
+
+    map.clear();
+    assert!(map.is_empty() && map.validate() == Ok(0));
+}
+
+#[test]
+fn test_sorted_insert() {
+    let size = 10000;
+    let map = (0..size).map(|x| (x, ())).collect::<Map<_, _>>();
+
+    // Every path holds as many black nodes, and no more red ones, so the
+    // height is at most twice the black height.
+    let black_height = map.validate().unwrap();
+    assert!(black_height <= 2 * (size as f64 + 1.0).log2() as usize);
+    assert!(map.iter().map(|(k, _)| *k).eq(0..size));
+    assert!(map.iter().rev().map(|(k, _)| *k).eq((0..size).rev()));
+}
+
+#[test]
+fn test_random_operations() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..10 {
+        let mut map = Map::new();
+        let mut model = BTreeMap::new();
+        for _ in 0..2000 {
+            let key = rng.gen_range(0..300);
+            match rng.gen_range(0..4) {
+                0 | 1 => {
+                    let value = rng.gen::<u32>();
+                    assert!(map.insert(key, value) == model.insert(key, value));
+                }
+                2 => assert!(map.remove(&key) == model.remove(&key)),
+                _ => assert!(map.get(&key) == model.get(&key)),
+            }
+            map.validate().unwrap();
+        }
+        assert!(map.len() == model.len());
+        assert!(map.iter().eq(model.iter()));
+        assert!(map.first() == model.first_key_value());
+        assert!(map.last() == model.last_key_value());
+
+        // Draining the map in random order keeps it valid all the way down.
+        let mut keys = model.keys().copied().collect::<Vec<_>>();
+        while !keys.is_empty() {
+            let key = keys.swap_remove(rng.gen_range(0..keys.len()));
+            assert!(map.remove_entry(&key) == model.remove_entry(&key));
+            map.validate().unwrap();
+        }
+        assert!(map.is_empty() && map.remove(&0).is_none());
+    }
+}
+
+#[test]
+fn test_predecessor_successor() {
+    let mut rng = rand::thread_rng();
+    let model = (0..500)
+        .map(|_| (rng.gen_range(0..2000), ()))
+        .collect::<BTreeMap<_, _>>();
+    let map = model.iter().map(|(k, v)| (*k, *v)).collect::<Map<_, _>>();
+
+    // The key asked about need not be in the map.
+    for key in -1..=2000 {
+        let before = model.range(..key).next_back();
+        let after = model.range((Bound::Excluded(key), Bound::Unbounded)).next();
+        assert!(map.predecessor(&key) == before);
+        assert!(map.successor(&key) == after);
+    }
+}
+
+#[test]
+fn test_range() {
+    let mut rng = rand::thread_rng();
+    let model = (0..300)
+        .map(|_| (rng.gen_range(0..1000), rng.gen::<u16>()))
+        .collect::<BTreeMap<_, _>>();
+    let map = model.iter().map(|(k, v)| (*k, *v)).collect::<Map<_, _>>();
+
+    for _ in 0..500 {
+        let a = rng.gen_range(-10..1010);
+        let b = rng.gen_range(a..1010);
+        assert!(map.range(a..b).eq(model.range(a..b)));
+        assert!(map.range(a..=b).rev().eq(model.range(a..=b).rev()));
+        assert!(map.range(..b).eq(model.range(..b)));
+        assert!(map.range(a..).rev().eq(model.range(a..).rev()));
+        let bounds = (Bound::Excluded(a), Bound::Included(b));
+        assert!(map.range(bounds).eq(model.range(bounds)));
+    }
+    assert!(map.range(..).count() == model.len());
+    assert!(map.range(5..5).next().is_none());
+
+    // Both ends can be taken from until they meet.
+    let mut range = map.range(100..200);
+    let mut expected = model.range(100..200);
+    loop {
+        let (x, y) = if rng.gen_bool(0.5) {
+            (range.next(), expected.next())
+        } else {
+            (range.next_back(), expected.next_back())
+        };
+        assert!(x == y);
+        if x.is_none() {
+            break;
+        }
+    }
+}
@@ -128,3 +128,133 @@ fn test_find_match() {
     assert!(list.len() == 99);
     assert!(list.find(&-37).is_none());
 }
+
+#[test]
+fn test_cursor_walk_insert_remove() {
+    let mut list = SingleLinkedList::new();
+
+    for i in (0..10).rev() {
+        list.push_head(i);
+    }
+
+    let mut cursor = list.cursor_front_mut();
+    assert!(cursor.index() == Some(0));
+    assert!(*cursor.current().unwrap() == 0);
+    assert!(*cursor.peek_next().unwrap() == 1);
+
+    while let Some(x) = cursor.current() {
+        if *x % 2 == 0 {
+            cursor.remove_current();
+        } else {
+            *x *= 10;
+            cursor.insert_before(-1);
+            cursor.insert_after(-2);
+            cursor.move_next();
+            cursor.move_next();
+        }
+    }
+    assert!(cursor.is_end() && cursor.peek_next().is_none());
+    cursor.insert_before(100);
+    cursor.insert_after(200);
+    assert!(*cursor.current().unwrap() == 200);
+
+    let iter = vec![
+        -1, 10, -2, -1, 30, -2, -1, 50, -2, -1, 70, -2, -1, 90, -2, 100, 200,
+    ];
+    assert!(list.len() == iter.len());
+    assert!(list_content_eq_to(&list, iter.iter()));
+}
+
+#[test]
+fn test_cursor_split_splice() {
+    let mut list = SingleLinkedList::new();
+
+    for i in (0..10).rev() {
+        list.push_head(i);
+    }
+
+    let mut cursor = list.cursor_front_mut();
+    for _ in 0..4 {
+        cursor.move_next();
+    }
+    let after = cursor.split_after();
+    let from = cursor.split_before();
+    assert!(cursor.is_end() && cursor.index().is_none());
+
+    let iter: Vec<_> = (5..10).collect();
+    assert!(after.len() == 5 && list_content_eq_to(&after, iter.iter()));
+    assert!(from.len() == 1 && from.iter().next().unwrap().value == 4);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    cursor.splice_before(after);
+    assert!(cursor.index() == Some(6));
+    cursor.splice_after(from);
+    assert!(*cursor.current().unwrap() == 1);
+    assert!(*cursor.peek_next().unwrap() == 4);
+
+    let iter = vec![0, 5, 6, 7, 8, 9, 1, 4, 2, 3];
+    assert!(list.len() == iter.len());
+    assert!(list_content_eq_to(&list, iter.iter()));
+}
+
+const STRESS_LEN: usize = 10_000_000;
+
+fn long_list() -> SingleLinkedList<usize> {
+    let mut list = SingleLinkedList::new();
+    for i in 0..STRESS_LEN {
+        list.push_head(i);
+    }
+    list
+}
+
+#[test]
+fn test_drop_long_list() {
+    let list = long_list();
+    drop(list);
+
+    let mut list = long_list();
+    let tail = {
+        let middle = list.find_mut(&(STRESS_LEN / 2));
+        SingleLinkedList::split(middle.unwrap())
+    };
+    drop(list);
+    drop(tail);
+}
+
+#[test]
+fn test_clone_eq_hash_long_list() {
+    use std::hash::{BuildHasher, RandomState};
+
+    let list = long_list();
+    let mut other = list.clone();
+    assert!(list == other);
+
+    let state = RandomState::new();
+    assert!(state.hash_one(&list) == state.hash_one(&other));
+
+    other.pop_head();
+    assert!(list != other);
+    assert!(state.hash_one(&list) != state.hash_one(&other));
+}
+
+#[test]
+fn test_debug_long_list() {
+    struct Sink(usize);
+
+    impl std::fmt::Write for Sink {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    let list = long_list();
+    let mut sink = Sink(0);
+    std::fmt::write(&mut sink, format_args!("{list:?}")).unwrap();
+    assert!(sink.0 > STRESS_LEN);
+
+    let short: SingleLinkedList<_> = (0..3).collect();
+    assert!(format!("{short:?}") == "[0, 1, 2]");
+    assert!(format!("{:?}", short.find(&1).unwrap()) == "SingleLinkedNode { value: 1, next: [2] }");
+}
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use data_structures::tree::{BinaryTree, TreeNode};
 
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 
 #[test]
 fn test_display() {
@@ -199,3 +199,42 @@ fn test_predecessor_successor() {
         }
     }
 }
+
+#[test]
+fn test_remove_value_equal_keys() {
+    let mut rng = rand::thread_rng();
+    let mut input = (0..10)
+        .flat_map(|x| std::iter::repeat_n(x, 50))
+        .collect::<Vec<_>>();
+    input.shuffle(&mut rng);
+
+    let mut tree = BinaryTree::new();
+    for x in &input {
+        tree.insert(*x);
+    }
+
+    // Hold on to a node, whose value then cannot be moved out.
+    let held = tree.find(&5).unwrap();
+    let mut held_removed = false;
+    let mut len = input.len();
+    input.shuffle(&mut rng);
+    for x in &input {
+        match tree.remove_value(x) {
+            Ok(value) => assert!(value == Some(*x)),
+            Err(node) => {
+                assert!(std::rc::Rc::ptr_eq(&node, &held) && !held_removed);
+                held_removed = true;
+            }
+        }
+        len -= 1;
+        let values = tree
+            .iter()
+            .map(|node| node.borrow().value)
+            .collect::<Vec<_>>();
+        assert!(values.len() == len && values.is_sorted());
+        verify_tree_order(&tree);
+    }
+    assert!(held_removed);
+    assert!(tree.is_empty() && tree.remove_value(&5) == Ok(None));
+    assert!(held.borrow().value == 5 && held.borrow().parent.upgrade().is_none());
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use data_structures::tree::{tree_map::Entry, TreeMap};
+
+use rand::Rng;
+
+#[test]
+fn test_insert_get_remove() {
+    let mut map = TreeMap::new();
+    assert!(map.is_empty() && map.get(&1).is_none() && map.first().is_none());
+
+    assert!(map.insert(3, "c").is_none());
+    assert!(map.insert(1, "a").is_none());
+    assert!(map.insert(2, "b").is_none());
+    assert!(map.insert(3, "C") == Some("c"));
+    assert!(map.len() == 3 && map.get(&3) == Some(&"C") && map.contains_key(&1));
+
+    *map.get_mut(&2).unwrap() = "B";
+    assert!(map.iter().eq([(&1, &"a"), (&2, &"B"), (&3, &"C")]));
+    assert!(map.keys().eq(&[1, 2, 3]) && map.values().eq(&["a", "B", "C"]));
+    assert!(map.first() == Some((&1, &"a")) && map.last() == Some((&3, &"C")));
+    println!("map = {map:?}");
+
+    assert!(map.remove(&2) == Some("B") && map.remove(&2).is_none());
+    assert!(map.remove_entry(&1) == Some((1, "a")));
+    assert!(map.len() == 1 && map.get(&1).is_none());
+    map.clear();
+    assert!(map.is_empty() && map.iter().next().is_none());
+}
+
+#[test]
+fn test_entry() {
+    let mut counts = TreeMap::new();
+    for word in "the cat and the hat and the bat".split(' ') {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    assert!(counts.get(&"the") == Some(&3) && counts.get(&"and") == Some(&2));
+    assert!(counts.len() == 5);
+
+    counts.entry("cat").and_modify(|n| *n += 10).or_default();
+    counts.entry("dog").and_modify(|n| *n += 10).or_default();
+    assert!(counts.get(&"cat") == Some(&11) && counts.get(&"dog") == Some(&0));
+
+    match counts.entry("hat") {
+        Entry::Occupied(mut entry) => {
+            assert!(*entry.key() == "hat" && *entry.get() == 1);
+            assert!(entry.insert(5) == 1);
+            *entry.get_mut() += 1;
+            assert!(entry.remove_entry() == ("hat", 6));
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    match counts.entry("hat") {
+        Entry::Occupied(_) => unreachable!(),
+        Entry::Vacant(entry) => {
+            assert!(*entry.key() == "hat");
+            *entry.insert(7) += 1;
+        }
+    }
+    assert!(counts.get(&"hat") == Some(&8));
+    let value = counts.entry("zebra").or_insert_with(|| 26);
+    *value *= 2;
+    assert!(counts.last() == Some((&"zebra", &52)));
+}
+
+#[test]
+fn test_random_operations() {
+    let mut rng = rand::thread_rng();
+    let mut map = TreeMap::new();
+    let mut model = BTreeMap::new();
+
+    for _ in 0..5000 {
+        let key = rng.gen_range(0..300);
+        match rng.gen_range(0..5) {
+            0 | 1 => {
+                let value = rng.gen::<u32>();
+                assert!(map.insert(key, value) == model.insert(key, value));
+            }
+            2 => assert!(map.remove(&key) == model.remove(&key)),
+            3 => {
+                let value = map.entry(key).or_insert(0);
+                *value = value.wrapping_add(1);
+                let expected = model.entry(key).or_insert(0);
+                *expected = expected.wrapping_add(1);
+            }
+            _ => assert!(map.get(&key) == model.get(&key)),
+        }
+        assert!(map.len() == model.len());
+    }
+    assert!(map.iter().eq(model.iter()));
+}
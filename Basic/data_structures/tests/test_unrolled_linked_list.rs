@@ -0,0 +1,90 @@
+use data_structures::linked_list::{SingleLinkedList, UnrolledLinkedList};
+
+use rand::Rng;
+
+fn verify_content<const N: usize>(list: &UnrolledLinkedList<i32, N>, expect: &[i32]) {
+    assert!(list.len() == expect.len() && list.is_empty() == expect.is_empty());
+    assert!(list.iter().eq(expect.iter()));
+    assert!(list.iter().len() == expect.len());
+    // Every node but the last is at least half full.
+    assert!(list.node_count() <= (2 * expect.len()).div_ceil(N) + 1);
+}
+
+#[test]
+fn test_push_pop() {
+    let mut list = UnrolledLinkedList::<_, 4>::new();
+    for i in 0..50 {
+        list.push_tail(i);
+        list.push_head(-i - 1);
+    }
+    let mut expect: Vec<_> = (-50..50).collect();
+    verify_content(&list, &expect);
+
+    for _ in 0..20 {
+        assert!(list.pop_head() == Some(expect.remove(0)));
+        assert!(list.pop_tail() == expect.pop());
+    }
+    verify_content(&list, &expect);
+
+    while list.pop_tail().is_some() {}
+    verify_content(&list, &[]);
+    assert!(list.node_count() == 0 && list.pop_head().is_none());
+}
+
+#[test]
+fn test_random_index_ops_against_vec() {
+    let mut rng = rand::thread_rng();
+    let mut list = UnrolledLinkedList::<_, 8>::new();
+    let mut model = Vec::new();
+
+    for i in 0..10000 {
+        if model.is_empty() || rng.gen_bool(0.55) {
+            let index = rng.gen_range(0..=model.len());
+            list.insert(index, i);
+            model.insert(index, i);
+        } else {
+            let index = rng.gen_range(0..model.len());
+            assert!(list.remove(index) == Some(model.remove(index)));
+        }
+
+        let index = rng.gen_range(0..=model.len());
+        assert!(list.get(index) == model.get(index));
+        if i % 100 == 0 {
+            verify_content(&list, &model);
+        }
+    }
+    verify_content(&list, &model);
+    assert!(list.remove(model.len()).is_none());
+}
+
+#[test]
+#[should_panic]
+fn test_insert_out_of_bounds() {
+    let mut list = UnrolledLinkedList::<_, 4>::new();
+    list.insert(1, 0);
+}
+
+#[test]
+fn test_collect_iter_mut() {
+    let mut list: UnrolledLinkedList<_, 4> = (0..10).collect();
+    assert!(list.node_count() == 3);
+
+    list.iter_mut().for_each(|x| *x *= 2);
+    *list.get_mut(9).unwrap() = -1;
+    let mut expect: Vec<_> = (0..10).map(|x| x * 2).collect();
+    expect[9] = -1;
+    verify_content(&list, &expect);
+
+    assert!(list.clone() == list);
+    assert!(format!("{}", list.clone()).starts_with("UnrolledLinkedList [0, 2, 4"));
+    assert!(format!("{:?}", (0..3).collect::<UnrolledLinkedList<_>>()) == "[0, 1, 2]");
+}
+
+#[test]
+fn test_traversal_matches_single_linked_list() {
+    let unrolled: UnrolledLinkedList<_> = (0..100_000).collect();
+    let single: SingleLinkedList<_> = (0..100_000).collect();
+
+    assert!(unrolled.iter().eq(single.iter().map(|node| &node.value)));
+    assert!(unrolled.iter().sum::<i64>() == single.iter().map(|node| node.value).sum());
+}